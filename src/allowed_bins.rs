@@ -0,0 +1,146 @@
+//! Packing rectangles that may only be placed into some of the bins.
+
+use crate::pack_options::PackContext;
+use crate::{
+    pack_rects_keyed_by, BoxSizeHeuristic, ComparePotentialContainers, GroupedRectsToPlace,
+    PackOptions, RectanglePackError, RectanglePackOk, TargetBin,
+};
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+/// Pack rectangles the same way as [`pack_rects_with_options`], but only place each rectangle
+/// that has an entry in `allowed_bins` into one of the bins that its entry lists.
+///
+/// Rectangles without an entry may be placed into any bin. A group is only placed into a bin
+/// that every one of its rectangles is allowed in, so a group whose rectangles have no bin in
+/// common can't be placed.
+///
+/// This routes rectangles independently of their groups, such as when normal maps have to go
+/// into a linear color atlas and albedo textures into an sRGB atlas.
+///
+/// ```
+/// use rectangle_pack::{
+///     contains_smallest_box, pack_rects_with_allowed_bins, volume_heuristic,
+///     GroupedRectsToPlace, PackOptions, RectToInsert, TargetBin,
+/// };
+/// use std::collections::BTreeMap;
+///
+/// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+/// rects_to_place.push_rect("grass-albedo", None, RectToInsert::new(8, 8, 1));
+/// rects_to_place.push_rect("grass-normal", None, RectToInsert::new(8, 8, 1));
+///
+/// let mut target_bins = BTreeMap::new();
+/// target_bins.insert("srgb-atlas", TargetBin::new(64, 64, 1));
+/// target_bins.insert("linear-atlas", TargetBin::new(64, 64, 1));
+///
+/// let mut allowed_bins = BTreeMap::new();
+/// allowed_bins.insert("grass-albedo", vec!["srgb-atlas"]);
+/// allowed_bins.insert("grass-normal", vec!["linear-atlas"]);
+///
+/// let packed = pack_rects_with_allowed_bins(
+///     &rects_to_place,
+///     &mut target_bins,
+///     &allowed_bins,
+///     &volume_heuristic,
+///     &contains_smallest_box,
+///     &PackOptions::default(),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(packed.packed_locations()["grass-albedo"].0, "srgb-atlas");
+/// assert_eq!(packed.packed_locations()["grass-normal"].0, "linear-atlas");
+/// ```
+///
+/// [`pack_rects_with_options`]: crate::pack_rects_with_options
+pub fn pack_rects_with_allowed_bins<RectToPlaceId, BinId, GroupId, Heuristic, Compare>(
+    rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    target_bins: &mut BTreeMap<BinId, TargetBin>,
+    allowed_bins: &BTreeMap<RectToPlaceId, Vec<BinId>>,
+    box_size_heuristic: &Heuristic,
+    more_suitable_containers_fn: &Compare,
+    options: &PackOptions,
+) -> Result<RectanglePackOk<RectToPlaceId, BinId>, RectanglePackError>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+{
+    let mut packed = RectanglePackOk::default();
+    pack_rects_keyed_by(
+        rects_to_place,
+        target_bins,
+        Some(allowed_bins),
+        PackContext::new(box_size_heuristic, more_suitable_containers_fn, options),
+        RectToPlaceId::clone,
+        &mut packed,
+    )?;
+
+    Ok(packed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{contains_smallest_box, volume_heuristic, RectToInsert};
+
+    /// Verify that a rectangle with allowed bins is placed into one of them even when another
+    /// bin would have been tried first, and that a rectangle without any can go anywhere.
+    #[test]
+    fn rect_placed_into_allowed_bin() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect("normal-map", None, RectToInsert::new(5, 5, 1));
+        rects_to_place.push_rect("anywhere", None, RectToInsert::new(5, 5, 1));
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert("small", TargetBin::new(10, 10, 1));
+        target_bins.insert("large", TargetBin::new(100, 100, 1));
+
+        let mut allowed_bins = BTreeMap::new();
+        allowed_bins.insert("normal-map", vec!["large"]);
+
+        let packed = pack_rects_with_allowed_bins(
+            &rects_to_place,
+            &mut target_bins,
+            &allowed_bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(packed.packed_locations()["normal-map"].0, "large");
+        assert_eq!(packed.packed_locations()["anywhere"].0, "small");
+    }
+
+    /// Verify that we return an error if a rectangle doesn't fit into any of its allowed bins,
+    /// even if another bin has enough space.
+    #[test]
+    fn error_if_allowed_bins_are_full() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect("normal-map", None, RectToInsert::new(50, 50, 1));
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert("small", TargetBin::new(10, 10, 1));
+        target_bins.insert("large", TargetBin::new(100, 100, 1));
+
+        let mut allowed_bins = BTreeMap::new();
+        allowed_bins.insert("normal-map", vec!["small"]);
+
+        let packed = pack_rects_with_allowed_bins(
+            &rects_to_place,
+            &mut target_bins,
+            &allowed_bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::default(),
+        );
+
+        assert!(matches!(
+            packed,
+            Err(RectanglePackError::NotEnoughBinSpace { .. })
+        ));
+    }
+}
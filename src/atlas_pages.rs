@@ -0,0 +1,271 @@
+//! A set of identically sized atlas pages built on top of [`pack_rects_with_options`].
+//!
+//! Enabled using the `atlas_pages` feature.
+//!
+//! [`pack_rects_with_options`]: crate::pack_rects_with_options
+
+use crate::{
+    contains_smallest_box, fits_in_any_bin, pack_rects_with_options, volume_heuristic,
+    GroupedRectsToPlace, PackOptions, RectToInsert, RectanglePackError, RectanglePackOk, TargetBin,
+};
+
+use alloc::{collections::BTreeMap, format, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+/// Packs rectangles into pages of a texture atlas that all have the same size, identified by
+/// their index instead of by a `BinId`.
+///
+/// - [`AtlasPages.pack`] places all of the rectangles or none of them, adding pages until they
+///   fit.
+/// - [`AtlasPages.pack_best_effort`] places as many of the rectangles as fit into the existing
+///   pages and reports the rest.
+/// - [`AtlasPages.page_stats`] reports how full each page is, such as for deciding when to
+///   re-pack.
+///
+/// ```
+/// use rectangle_pack::{AtlasPages, GroupedRectsToPlace, RectToInsert, TargetBin};
+///
+/// let mut atlas = AtlasPages::new(TargetBin::new(32, 32, 1));
+///
+/// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+/// rects_to_place.push_rect("grass", None, RectToInsert::new(32, 32, 1));
+/// rects_to_place.push_rect("stone", None, RectToInsert::new(32, 32, 1));
+///
+/// let packed = atlas.pack(&rects_to_place).unwrap();
+///
+/// assert_eq!(atlas.page_count(), 2);
+/// assert_ne!(packed.packed_locations()["grass"].0, packed.packed_locations()["stone"].0);
+/// assert_eq!(atlas.page_stats(0).unwrap().free_volume(), 0);
+/// ```
+///
+/// [`AtlasPages.pack`]: AtlasPages::pack
+/// [`AtlasPages.pack_best_effort`]: AtlasPages::pack_best_effort
+/// [`AtlasPages.page_stats`]: AtlasPages::page_stats
+#[derive(Debug, Clone)]
+pub struct AtlasPages {
+    /// An empty page that new pages are copied from.
+    empty_page: TargetBin,
+    pages: BTreeMap<usize, TargetBin>,
+    max_pages: Option<usize>,
+    options: PackOptions,
+}
+
+/// How full one of the pages of an [`AtlasPages`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageStats {
+    used_volume: u128,
+    free_volume: u128,
+    placed_rect_count: usize,
+}
+
+impl AtlasPages {
+    /// Create an atlas with a single, empty copy of the given page.
+    ///
+    /// Every page that gets added has the same size and configuration as the given page, such
+    /// as its [`TargetBin.with_block_size`] and exclusion zones, but starts out empty.
+    ///
+    /// [`TargetBin.with_block_size`]: TargetBin::with_block_size
+    pub fn new(page: TargetBin) -> Self {
+        let empty_page = page.emptied();
+
+        let mut pages = BTreeMap::new();
+        pages.insert(0, empty_page.clone());
+
+        AtlasPages {
+            empty_page,
+            pages,
+            max_pages: None,
+            options: PackOptions::default(),
+        }
+    }
+
+    /// Place rectangles using these options instead of the defaults.
+    pub fn with_pack_options(mut self, options: PackOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Never add pages past this many.
+    ///
+    /// Defaults to no limit.
+    pub fn with_max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = Some(max_pages);
+        self
+    }
+
+    /// Add an empty page, returning its index.
+    pub fn add_page(&mut self) -> usize {
+        let page = self.pages.len();
+        self.pages.insert(page, self.empty_page.clone());
+        page
+    }
+
+    /// Place every one of the rectangles into the pages, adding pages until they fit.
+    ///
+    /// Returns an error if they don't fit even after adding pages up to
+    /// [`AtlasPages.with_max_pages`], or if a rectangle doesn't fit into an empty page, in which
+    /// case the atlas is left unchanged.
+    ///
+    /// [`AtlasPages.with_max_pages`]: AtlasPages::with_max_pages
+    pub fn pack<RectToPlaceId, GroupId>(
+        &mut self,
+        rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    ) -> Result<RectanglePackOk<RectToPlaceId, usize>, RectanglePackError>
+    where
+        RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+        GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    {
+        let rects: Vec<RectToInsert> = rects_to_place.rects.values().copied().collect();
+        let mut empty_page = BTreeMap::new();
+        empty_page.insert(0, self.empty_page.clone());
+        let fits = fits_in_any_bin(&rects, &empty_page);
+        if let Some(too_large) = fits.iter().position(|fits| !fits) {
+            let rect_to_place_id = rects_to_place.rects.keys().nth(too_large).unwrap();
+
+            return Err(RectanglePackError::NotEnoughBinSpace {
+                rect_to_place_id: Some(format!("{:?}", rect_to_place_id)),
+                group_id: None,
+                required_volume: rects[too_large].whd.volume(),
+                available_volume: self.empty_page.free_volume(),
+            });
+        }
+
+        // Every added page holds at least one more rectangle, so more pages than rectangles are
+        // never needed.
+        let max_pages = self
+            .max_pages
+            .unwrap_or(usize::MAX)
+            .min(self.pages.len() + rects.len());
+
+        let mut pages = self.pages.clone();
+        loop {
+            let mut packed_pages = pages.clone();
+            match pack_rects_with_options(
+                rects_to_place,
+                &mut packed_pages,
+                &volume_heuristic,
+                &contains_smallest_box,
+                &self.options,
+            ) {
+                Ok(packed) => {
+                    self.pages = packed_pages;
+                    return Ok(packed);
+                }
+                Err(RectanglePackError::NotEnoughBinSpace { .. }) if pages.len() < max_pages => {
+                    pages.insert(pages.len(), self.empty_page.clone());
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Place as many of the rectangles as fit into the existing pages, without adding any.
+    ///
+    /// Every group that doesn't fit is left out, the same as an optional group (see
+    /// [`RectToInsert.with_optional`]), and is listed in the
+    /// [`RectanglePackOk.omitted_optional_rects`], such as for spilling them onto a new page.
+    ///
+    /// [`RectToInsert.with_optional`]: crate::RectToInsert::with_optional
+    /// [`RectanglePackOk.omitted_optional_rects`]: crate::RectanglePackOk::omitted_optional_rects
+    pub fn pack_best_effort<RectToPlaceId, GroupId>(
+        &mut self,
+        rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    ) -> Result<RectanglePackOk<RectToPlaceId, usize>, RectanglePackError>
+    where
+        RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+        GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    {
+        let options = PackOptions {
+            best_effort: true,
+            ..self.options.clone()
+        };
+
+        pack_rects_with_options(
+            rects_to_place,
+            &mut self.pages,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &options,
+        )
+    }
+
+    /// The page at the given index.
+    pub fn page(&self, page: usize) -> Option<&TargetBin> {
+        self.pages.get(&page)
+    }
+
+    /// How full the page at the given index is.
+    pub fn page_stats(&self, page: usize) -> Option<PageStats> {
+        self.pages.get(&page).map(|page| PageStats {
+            used_volume: page.used_volume(),
+            free_volume: page.free_volume(),
+            placed_rect_count: page.placed_rect_count(),
+        })
+    }
+
+    /// The number of pages.
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+}
+
+#[allow(missing_docs)]
+impl PageStats {
+    /// See [`TargetBin.used_volume`].
+    ///
+    /// [`TargetBin.used_volume`]: TargetBin::used_volume
+    pub fn used_volume(&self) -> u128 {
+        self.used_volume
+    }
+
+    /// See [`TargetBin.free_volume`].
+    ///
+    /// [`TargetBin.free_volume`]: TargetBin::free_volume
+    pub fn free_volume(&self) -> u128 {
+        self.free_volume
+    }
+
+    pub fn placed_rect_count(&self) -> usize {
+        self.placed_rect_count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that a best effort pack places what fits into the existing pages and reports the
+    /// rest, without adding pages.
+    #[test]
+    fn best_effort_reports_what_did_not_fit() {
+        let mut atlas = AtlasPages::new(TargetBin::new(10, 10, 1));
+
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect("large", None, RectToInsert::new(10, 8, 1));
+        rects_to_place.push_rect("medium", None, RectToInsert::new(10, 5, 1));
+
+        let packed = atlas.pack_best_effort(&rects_to_place).unwrap();
+
+        assert_eq!(atlas.page_count(), 1);
+        assert!(packed.packed_locations().contains_key("large"));
+        assert_eq!(packed.omitted_optional_rects(), &["medium"]);
+    }
+
+    /// Verify that a failed pack leaves the atlas unchanged.
+    #[test]
+    fn error_if_max_pages_reached() {
+        let mut atlas = AtlasPages::new(TargetBin::new(10, 10, 1)).with_max_pages(2);
+
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        for rect in 0..3 {
+            rects_to_place.push_rect(rect, None, RectToInsert::new(10, 10, 1));
+        }
+
+        assert!(matches!(
+            atlas.pack(&rects_to_place),
+            Err(RectanglePackError::NotEnoughBinSpace { .. })
+        ));
+        assert_eq!(atlas.page_count(), 1);
+        assert_eq!(atlas.page_stats(0).unwrap().placed_rect_count(), 0);
+    }
+}
@@ -0,0 +1,170 @@
+//! Packing the large rectangles first and then back-filling the space around them with the small
+//! rectangles, using a different way of splitting sections for each.
+
+use crate::pack_options::PackContext;
+use crate::{
+    pack_rects_keyed_by, BoxSizeHeuristic, ComparePotentialContainers, GroupedRectsToPlace,
+    PackOptions, RectanglePackError, RectanglePackOk, TargetBin,
+};
+
+use alloc::collections::BTreeMap;
+use core::{fmt::Debug, hash::Hash};
+
+/// Pack in two passes, the same way as [`PackOptions.with_two_phase`], but split the sections
+/// of the second, back-filling, pass using a different [`ComparePotentialContainersFn`].
+///
+/// First every group whose total size, according to the box size heuristic, is larger than
+/// `large_group_threshold` is placed, splitting sections using `large_containers_fn`. Then the
+/// remaining groups fill in the space that is left, splitting sections using
+/// `back_fill_containers_fn`.
+///
+/// A single comparator has to work for both the large rectangles and the small ones. Splitting
+/// differently for the small rectangles, such as to keep the left over slivers as large as
+/// possible, often recovers much of the space that a single pass leaves unused.
+///
+/// ```
+/// use rectangle_pack::{
+///     contains_smallest_box, pack_rects_with_back_fill, volume_heuristic, GroupedRectsToPlace,
+///     PackOptions, RectToInsert, TargetBin, WidthHeightDepth, BoxSizeHeuristicFn,
+/// };
+/// use std::cmp::Ordering;
+/// use std::collections::BTreeMap;
+///
+/// /// Keep the largest left over section as large as possible.
+/// fn contains_largest_box(
+///     a: [WidthHeightDepth; 3],
+///     b: [WidthHeightDepth; 3],
+///     heuristic: &BoxSizeHeuristicFn,
+/// ) -> Ordering {
+///     let largest = |sections: [WidthHeightDepth; 3]| {
+///         sections.iter().map(|section| heuristic(*section)).max()
+///     };
+///
+///     largest(a).cmp(&largest(b))
+/// }
+///
+/// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+/// rects_to_place.push_rect("background", None, RectToInsert::new(48, 48, 1));
+/// rects_to_place.push_rect("icon", None, RectToInsert::new(8, 8, 1));
+///
+/// let mut target_bins = BTreeMap::new();
+/// target_bins.insert(0, TargetBin::new(64, 64, 1));
+///
+/// let packed = pack_rects_with_back_fill(
+///     &rects_to_place,
+///     &mut target_bins,
+///     &volume_heuristic,
+///     &contains_smallest_box,
+///     &contains_largest_box,
+///     1024,
+///     &PackOptions::default(),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(packed.packed_locations().len(), 2);
+/// ```
+///
+/// [`PackOptions.with_two_phase`]: crate::PackOptions::with_two_phase
+/// [`ComparePotentialContainersFn`]: crate::ComparePotentialContainersFn
+pub fn pack_rects_with_back_fill<
+    RectToPlaceId,
+    BinId,
+    GroupId,
+    Heuristic,
+    Compare,
+    BackFillCompare,
+>(
+    rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    target_bins: &mut BTreeMap<BinId, TargetBin>,
+    box_size_heuristic: &Heuristic,
+    large_containers_fn: &Compare,
+    back_fill_containers_fn: &BackFillCompare,
+    large_group_threshold: u128,
+    options: &PackOptions,
+) -> Result<RectanglePackOk<RectToPlaceId, BinId>, RectanglePackError>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+    BackFillCompare: ComparePotentialContainers + ?Sized,
+{
+    let options = options.clone().with_two_phase(large_group_threshold);
+    // The two comparators can be different types, so they are both used as trait objects.
+    let ctx = PackContext {
+        back_fill_containers_fn: Some(back_fill_containers_fn.as_compare_potential_containers_fn()),
+        ..PackContext::new(
+            box_size_heuristic,
+            large_containers_fn.as_compare_potential_containers_fn(),
+            &options,
+        )
+    };
+
+    let mut packed = RectanglePackOk::default();
+    pack_rects_keyed_by(
+        rects_to_place,
+        target_bins,
+        None,
+        ctx,
+        RectToPlaceId::clone,
+        &mut packed,
+    )?;
+
+    Ok(packed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        contains_smallest_box, volume_heuristic, BoxSizeHeuristicFn, ComparePotentialContainersFn,
+        RectToInsert, WidthHeightDepth,
+    };
+    use core::cmp::Ordering;
+
+    /// Verify that only the sections that the small groups are placed into are split using the
+    /// back-fill comparator.
+    #[test]
+    fn back_fills_using_its_own_comparator() {
+        let free_sections = |large_group_threshold: u128| {
+            let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+            rects_to_place.push_rect("large", None, RectToInsert::new(6, 6, 1));
+            rects_to_place.push_rect("small", None, RectToInsert::new(2, 2, 1));
+
+            let pack = |back_fill_containers_fn: &ComparePotentialContainersFn| {
+                let mut target_bins = BTreeMap::new();
+                target_bins.insert(0, TargetBin::new(10, 10, 1));
+
+                pack_rects_with_back_fill(
+                    &rects_to_place,
+                    &mut target_bins,
+                    &volume_heuristic,
+                    &contains_smallest_box,
+                    back_fill_containers_fn,
+                    large_group_threshold,
+                    &PackOptions::default(),
+                )
+                .unwrap();
+
+                target_bins[&0].available_bin_sections.clone()
+            };
+
+            (pack(&contains_smallest_box), pack(&contains_largest_box))
+        };
+
+        let (smallest, largest) = free_sections(20);
+        assert_ne!(smallest, largest);
+
+        let (smallest, largest) = free_sections(0);
+        assert_eq!(smallest, largest);
+    }
+
+    fn contains_largest_box(
+        a: [WidthHeightDepth; 3],
+        b: [WidthHeightDepth; 3],
+        heuristic: &BoxSizeHeuristicFn,
+    ) -> Ordering {
+        contains_smallest_box(b, a, heuristic)
+    }
+}
@@ -0,0 +1,153 @@
+//! Packing rectangles into named pools of bins.
+
+use crate::{
+    pack_rects_with_options, BoxSizeHeuristic, ComparePotentialContainers, GroupedRectsToPlace,
+    PackOptions, RectanglePackError, RectanglePackOk, TargetBin,
+};
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+/// Pack rectangles into pools of bins, such as a pool of "diffuse pages" and a pool of "normal
+/// pages".
+///
+/// Each set of rectangles in `rects_per_pool` may only be placed into the bins that `bin_pools`
+/// lists for that same pool. Within a pool the bin that each rectangle goes into is chosen the
+/// same way as in [`pack_rects_with_options`].
+///
+/// Pools are packed one after another, in order of their `PoolId`. A bin may belong to more than
+/// one pool, in which case every one of those pools can place rectangles into it.
+///
+/// Rectangle ids are expected to be unique across all of the pools.
+///
+/// Returns an error if any pool can't fit its rectangles into its bins. Note that in this case
+/// the bins of the pools that were packed before the failing pool will have already been
+/// modified.
+pub fn pack_rects_into_pools<RectToPlaceId, BinId, GroupId, PoolId, Heuristic, Compare>(
+    rects_per_pool: &BTreeMap<PoolId, GroupedRectsToPlace<RectToPlaceId, GroupId>>,
+    bin_pools: &BTreeMap<PoolId, Vec<BinId>>,
+    target_bins: &mut BTreeMap<BinId, TargetBin>,
+    box_size_heuristic: &Heuristic,
+    more_suitable_containers_fn: &Compare,
+    options: &PackOptions,
+) -> Result<RectanglePackOk<RectToPlaceId, BinId>, RectanglePackError>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    PoolId: Debug + Ord + PartialOrd,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+{
+    let mut packed = RectanglePackOk::default();
+
+    for (pool_id, rects_to_place) in rects_per_pool.iter() {
+        let mut pool_bins = BTreeMap::new();
+        for bin_id in bin_pools.get(pool_id).into_iter().flatten() {
+            if let Some(bin) = target_bins.remove(bin_id) {
+                pool_bins.insert(bin_id.clone(), bin);
+            }
+        }
+
+        let pool_packed = pack_rects_with_options(
+            rects_to_place,
+            &mut pool_bins,
+            box_size_heuristic,
+            more_suitable_containers_fn,
+            options,
+        );
+
+        target_bins.extend(pool_bins);
+
+        let pool_packed = pool_packed?;
+        packed.packed_locations.extend(pool_packed.packed_locations);
+        packed
+            .omitted_optional_rects
+            .extend(pool_packed.omitted_optional_rects);
+    }
+    packed.omitted_optional_rects.sort();
+
+    Ok(packed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{contains_smallest_box, volume_heuristic, RectToInsert};
+
+    /// Verify that rectangles are only placed into the bins of their own pool, even if a bin in
+    /// another pool would have been tried first.
+    #[test]
+    fn rects_placed_into_bins_of_their_pool() {
+        let mut diffuse = GroupedRectsToPlace::<_, ()>::new();
+        diffuse.push_rect("grass-diffuse", None, RectToInsert::new(5, 5, 1));
+
+        let mut normal = GroupedRectsToPlace::<_, ()>::new();
+        normal.push_rect("grass-normal", None, RectToInsert::new(5, 5, 1));
+
+        let mut rects_per_pool = BTreeMap::new();
+        rects_per_pool.insert(Pool::Diffuse, diffuse);
+        rects_per_pool.insert(Pool::Normal, normal);
+
+        let mut bin_pools = BTreeMap::new();
+        bin_pools.insert(Pool::Diffuse, vec!["diffuse-page"]);
+        bin_pools.insert(Pool::Normal, vec!["normal-page"]);
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert("diffuse-page", TargetBin::new(100, 100, 1));
+        target_bins.insert("normal-page", TargetBin::new(10, 10, 1));
+
+        let packed = pack_rects_into_pools(
+            &rects_per_pool,
+            &bin_pools,
+            &mut target_bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(packed.packed_locations["grass-diffuse"].0, "diffuse-page");
+        assert_eq!(packed.packed_locations["grass-normal"].0, "normal-page");
+        assert_eq!(target_bins.len(), 2);
+    }
+
+    /// Verify that we return an error if a pool's rectangles don't fit into its bins, even if
+    /// another pool has enough space.
+    #[test]
+    fn error_if_pool_is_full() {
+        let mut normal = GroupedRectsToPlace::<_, ()>::new();
+        normal.push_rect("big-normal", None, RectToInsert::new(50, 50, 1));
+
+        let mut rects_per_pool = BTreeMap::new();
+        rects_per_pool.insert(Pool::Normal, normal);
+
+        let mut bin_pools = BTreeMap::new();
+        bin_pools.insert(Pool::Diffuse, vec!["diffuse-page"]);
+        bin_pools.insert(Pool::Normal, vec!["normal-page"]);
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert("diffuse-page", TargetBin::new(100, 100, 1));
+        target_bins.insert("normal-page", TargetBin::new(10, 10, 1));
+
+        let packed = pack_rects_into_pools(
+            &rects_per_pool,
+            &bin_pools,
+            &mut target_bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::default(),
+        );
+
+        assert!(matches!(
+            packed,
+            Err(RectanglePackError::NotEnoughBinSpace { .. })
+        ));
+    }
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd)]
+    enum Pool {
+        Diffuse,
+        Normal,
+    }
+}
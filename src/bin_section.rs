@@ -1,12 +1,22 @@
 use crate::packed_location::RotatedBy;
-use crate::{BoxSizeHeuristicFn, PackedLocation, RectToInsert, WidthHeightDepth};
+use crate::{
+    BinEdge, BoxSizeHeuristic, BoxSizeHeuristicFn, CompareOrientations, OrientationCandidate,
+    PackedLocation, RectToInsert, WidthHeightDepth,
+};
 
 use core::{
     cmp::Ordering,
     fmt::{Debug, Display, Error as FmtError, Formatter},
 };
 
+pub use self::remaining_sections::{ExactFit, RemainingSections};
+
 mod overlaps;
+mod remaining_sections;
+
+/// The number of ways that the space left over after placing a box into a section can be split
+/// into three new sections.
+pub(crate) const SPLIT_CONFIGURATION_COUNT: usize = 6;
 
 /// Given two sets of containers, which of these is the more suitable for our packing.
 ///
@@ -21,8 +31,55 @@ mod overlaps;
 ///
 /// Ordering::Greater means the first set of containers is better.
 /// Ordering::Less means the second set of containers is better.
-pub type ComparePotentialContainersFn =
-    dyn Fn([WidthHeightDepth; 3], [WidthHeightDepth; 3], &BoxSizeHeuristicFn) -> Ordering;
+///
+/// The comparison may borrow from its surroundings for the lifetime `'a`.
+pub type ComparePotentialContainersFn<'a> =
+    dyn Fn([WidthHeightDepth; 3], [WidthHeightDepth; 3], &BoxSizeHeuristicFn<'_>) -> Ordering + 'a;
+
+/// Anything that can be used as a [`ComparePotentialContainersFn`], so that [`pack_rects`]
+/// accepts functions and closures as well as `&ComparePotentialContainersFn` trait objects.
+///
+/// [`pack_rects`]: crate::pack_rects
+pub trait ComparePotentialContainers {
+    /// The comparison as a trait object.
+    fn as_compare_potential_containers_fn(&self) -> &ComparePotentialContainersFn<'_>;
+
+    /// Compare the two sets of containers.
+    ///
+    /// Functions and closures are called directly instead of through the trait object.
+    fn compare_containers(
+        &self,
+        container1: [WidthHeightDepth; 3],
+        container2: [WidthHeightDepth; 3],
+        heuristic: &BoxSizeHeuristicFn,
+    ) -> Ordering {
+        (self.as_compare_potential_containers_fn())(container1, container2, heuristic)
+    }
+}
+
+impl<F> ComparePotentialContainers for F
+where
+    F: Fn([WidthHeightDepth; 3], [WidthHeightDepth; 3], &BoxSizeHeuristicFn<'_>) -> Ordering,
+{
+    fn as_compare_potential_containers_fn(&self) -> &ComparePotentialContainersFn<'_> {
+        self
+    }
+
+    fn compare_containers(
+        &self,
+        container1: [WidthHeightDepth; 3],
+        container2: [WidthHeightDepth; 3],
+        heuristic: &BoxSizeHeuristicFn,
+    ) -> Ordering {
+        self(container1, container2, heuristic)
+    }
+}
+
+impl ComparePotentialContainers for ComparePotentialContainersFn<'_> {
+    fn as_compare_potential_containers_fn(&self) -> &ComparePotentialContainersFn<'_> {
+        self
+    }
+}
 
 /// Select the container that has the smallest box.
 ///
@@ -32,8 +89,8 @@ pub fn contains_smallest_box(
     mut container2: [WidthHeightDepth; 3],
     heuristic: &BoxSizeHeuristicFn,
 ) -> Ordering {
-    container1.sort_by(|a, b| heuristic(*a).cmp(&heuristic(*b)));
-    container2.sort_by(|a, b| heuristic(*a).cmp(&heuristic(*b)));
+    container1.sort_by_key(|a| heuristic(*a));
+    container2.sort_by_key(|a| heuristic(*a));
 
     match heuristic(container2[0]).cmp(&heuristic(container1[0])) {
         Ordering::Equal => heuristic(container2[1]).cmp(&heuristic(container1[1])),
@@ -52,11 +109,34 @@ pub struct BinSection {
 
 /// An error while attempting to place a rectangle within a bin section;
 #[derive(Debug, Eq, PartialEq)]
-#[allow(missing_docs)]
+#[allow(missing_docs, clippy::enum_variant_names)]
 pub enum BinSectionError {
     PlacementWiderThanBinSection,
     PlacementTallerThanBinSection,
     PlacementDeeperThanBinSection,
+    /// The rectangle would end past the [`RectToInsert.with_max_x`] column.
+    ///
+    /// [`RectToInsert.with_max_x`]: crate::RectToInsert::with_max_x
+    PlacementPastMaxX,
+    /// The rectangle would end past the [`RectToInsert.with_max_y`] row.
+    ///
+    /// [`RectToInsert.with_max_y`]: crate::RectToInsert::with_max_y
+    PlacementPastMaxY,
+    /// The rectangle needs more layers than are left in the bin section of a bin with a
+    /// [`TargetBin.with_layered_depth`].
+    ///
+    /// [`TargetBin.with_layered_depth`]: crate::TargetBin::with_layered_depth
+    PlacementHasMoreLayersThanBinSection,
+    /// The bin section doesn't cover the [`RectToInsert.with_layer`] that the rectangle has to be
+    /// placed onto.
+    ///
+    /// [`RectToInsert.with_layer`]: crate::RectToInsert::with_layer
+    PlacementNotOnLayer,
+    /// The rectangle would not touch the [`RectToInsert.with_edge`] of the bin that it is
+    /// anchored to.
+    ///
+    /// [`RectToInsert.with_edge`]: crate::RectToInsert::with_edge
+    PlacementNotOnEdge,
 }
 
 impl Display for BinSectionError {
@@ -71,6 +151,21 @@ impl Display for BinSectionError {
             BinSectionError::PlacementDeeperThanBinSection => {
                 "Can not place a rectangle inside of a bin that is deeper than that rectangle."
             }
+            BinSectionError::PlacementPastMaxX => {
+                "Can not place a rectangle where it would end past its maximum x coordinate."
+            }
+            BinSectionError::PlacementPastMaxY => {
+                "Can not place a rectangle where it would end past its maximum y coordinate."
+            }
+            BinSectionError::PlacementHasMoreLayersThanBinSection => {
+                "Can not place a rectangle inside of a bin section that has fewer layers than that rectangle."
+            }
+            BinSectionError::PlacementNotOnLayer => {
+                "Can not place a rectangle inside of a bin section that does not cover its layer."
+            }
+            BinSectionError::PlacementNotOnEdge => {
+                "Can not place a rectangle where it would not touch the edge that it is anchored to."
+            }
         };
 
         f.write_str(err)
@@ -83,6 +178,41 @@ impl BinSection {
         BinSection { x, y, z, whd }
     }
 
+    #[allow(missing_docs)]
+    pub fn x(&self) -> u32 {
+        self.x
+    }
+
+    #[allow(missing_docs)]
+    pub fn y(&self) -> u32 {
+        self.y
+    }
+
+    #[allow(missing_docs)]
+    pub fn z(&self) -> u32 {
+        self.z
+    }
+
+    #[allow(missing_docs)]
+    pub fn width(&self) -> u32 {
+        self.whd.width
+    }
+
+    #[allow(missing_docs)]
+    pub fn height(&self) -> u32 {
+        self.whd.height
+    }
+
+    #[allow(missing_docs)]
+    pub fn depth(&self) -> u32 {
+        self.whd.depth
+    }
+
+    /// The width, height and depth of the section.
+    pub fn whd(&self) -> WidthHeightDepth {
+        self.whd
+    }
+
     // TODO: Delete - just the old API before we had the WidthHeightDepth struct
     fn new_spread(x: u32, y: u32, z: u32, width: u32, height: u32, depth: u32) -> Self {
         BinSection {
@@ -123,6 +253,13 @@ impl BinSection {
     /// There are six possible configurations of newly created sections. The configuration to use
     /// is decided on based on a a function provided by the consumer.
     ///
+    /// When the box exactly matches this section in one or more dimensions some of those new
+    /// sections would be empty. Only the non empty sections are returned, and the
+    /// [`RemainingSections.exact_fit`] reports which dimensions matched. A box that exactly fills
+    /// the section leaves no sections behind.
+    ///
+    /// [`RemainingSections.exact_fit`]: crate::RemainingSections::exact_fit
+    ///
     ///
     /// ```text
     ///             ┌┬───────────────────┬┐
@@ -151,14 +288,66 @@ impl BinSection {
     /// Written to be readable/maintainable, not to minimize conditional logic, under the
     /// (unverified) assumption that a release compilation will inline and dedupe the function
     /// calls and conditionals.
-    pub fn try_place(
+    pub fn try_place<Compare, Heuristic>(
         &self,
         incoming: &RectToInsert,
-        container_comparison_fn: &ComparePotentialContainersFn,
-        heuristic_fn: &BoxSizeHeuristicFn,
-    ) -> Result<(PackedLocation, [BinSection; 3]), BinSectionError> {
+        container_comparison_fn: &Compare,
+        heuristic_fn: &Heuristic,
+    ) -> Result<(PackedLocation, RemainingSections), BinSectionError>
+    where
+        Compare: ComparePotentialContainers + ?Sized,
+        Heuristic: BoxSizeHeuristic + ?Sized,
+    {
+        self.try_place_with_split_rank(incoming, container_comparison_fn, heuristic_fn, 0)
+    }
+
+    /// The same as [`BinSection.try_place`], but split the remaining space using the
+    /// configuration at the given rank instead of the best one.
+    ///
+    /// A `split_rank` of `0` is the most suitable configuration, `1` is the second most suitable
+    /// and so on up to `SPLIT_CONFIGURATION_COUNT - 1`.
+    pub(crate) fn try_place_with_split_rank<Compare, Heuristic>(
+        &self,
+        incoming: &RectToInsert,
+        container_comparison_fn: &Compare,
+        heuristic_fn: &Heuristic,
+        split_rank: usize,
+    ) -> Result<(PackedLocation, RemainingSections), BinSectionError>
+    where
+        Compare: ComparePotentialContainers + ?Sized,
+        Heuristic: BoxSizeHeuristic + ?Sized,
+    {
         self.incoming_can_fit(incoming)?;
 
+        let packed_location = PackedLocation {
+            x: self.x,
+            y: self.y,
+            z: self.z,
+            whd: incoming.whd,
+            allocated_whd: incoming.whd,
+            x_axis_rotation: RotatedBy::ZeroDegrees,
+            y_axis_rotation: RotatedBy::ZeroDegrees,
+            z_axis_rotation: RotatedBy::ZeroDegrees,
+            flipped_horizontally: false,
+            flipped_vertically: false,
+            padding: 0,
+            content_hash: None,
+            origin: [0, 0, 0],
+        };
+
+        let exact_fit = ExactFit {
+            width: incoming.width() == self.whd.width,
+            height: incoming.height() == self.whd.height,
+            depth: incoming.depth() == self.whd.depth,
+        };
+
+        if exact_fit.is_complete() {
+            return Ok((
+                packed_location,
+                RemainingSections::new([BinSection::default(); 3], exact_fit),
+            ));
+        }
+
         let mut all_combinations = [
             self.depth_largest_height_second_largest_width_smallest(incoming),
             self.depth_largest_width_second_largest_height_smallest(incoming),
@@ -168,32 +357,116 @@ impl BinSection {
             self.width_largest_height_second_largest_depth_smallest(incoming),
         ];
 
+        let heuristic_fn = heuristic_fn.as_box_size_heuristic_fn();
         all_combinations.sort_by(|a, b| {
-            container_comparison_fn(
+            container_comparison_fn.compare_containers(
                 [a[0].whd, a[1].whd, a[2].whd],
                 [b[0].whd, b[1].whd, b[2].whd],
                 heuristic_fn,
             )
         });
 
-        let packed_location = PackedLocation {
-            x: self.x,
-            y: self.y,
-            z: self.z,
-            whd: WidthHeightDepth {
-                width: incoming.width(),
-                height: incoming.height(),
-                depth: incoming.depth(),
-            },
-            x_axis_rotation: RotatedBy::ZeroDegrees,
-            y_axis_rotation: RotatedBy::ZeroDegrees,
-            z_axis_rotation: RotatedBy::ZeroDegrees,
-        };
+        Ok((
+            packed_location,
+            RemainingSections::new(
+                all_combinations[SPLIT_CONFIGURATION_COUNT - 1 - split_rank],
+                exact_fit,
+            ),
+        ))
+    }
 
-        Ok((packed_location, all_combinations[5]))
+    /// The same as [`BinSection.try_place_with_split_rank`], but if the rectangle does not fit
+    /// as is, try each of the other orientations that it is allowed to be rotated into.
+    ///
+    /// With a [`CompareOrientations`] every allowed orientation, and every allowed way of flipping
+    /// it, is tried and the one that it prefers is used.
+    pub(crate) fn try_place_in_allowed_orientations<Compare, Heuristic>(
+        &self,
+        incoming: &RectToInsert,
+        container_comparison_fn: &Compare,
+        heuristic_fn: &Heuristic,
+        orientation_comparator: Option<&dyn CompareOrientations>,
+        split_rank: usize,
+    ) -> Result<(PackedLocation, RemainingSections), BinSectionError>
+    where
+        Compare: ComparePotentialContainers + ?Sized,
+        Heuristic: BoxSizeHeuristic + ?Sized,
+    {
+        let mut first_err = None;
+        let mut chosen: Option<OrientationCandidate> = None;
+
+        for (whd, [x_axis_rotation, y_axis_rotation, z_axis_rotation]) in
+            incoming.allowed_orientations()
+        {
+            let mut oriented = *incoming;
+            oriented.whd = whd;
+
+            let (mut location, remaining_sections) = match self.try_place_with_split_rank(
+                &oriented,
+                container_comparison_fn,
+                heuristic_fn,
+                split_rank,
+            ) {
+                Ok(placement) => placement,
+                Err(err) => {
+                    first_err.get_or_insert(err);
+                    continue;
+                }
+            };
+
+            location.whd = incoming.whd;
+            location.allocated_whd = incoming.whd;
+            location.x_axis_rotation = x_axis_rotation;
+            location.y_axis_rotation = y_axis_rotation;
+            location.z_axis_rotation = z_axis_rotation;
+
+            let orientation_comparator = match orientation_comparator {
+                Some(orientation_comparator) => orientation_comparator,
+                None => return Ok((location, remaining_sections)),
+            };
+
+            for (flipped_horizontally, flipped_vertically) in incoming.allowed_flips() {
+                location.flipped_horizontally = flipped_horizontally;
+                location.flipped_vertically = flipped_vertically;
+
+                let candidate = OrientationCandidate {
+                    location,
+                    remaining_sections,
+                };
+
+                let is_better = match chosen.as_ref() {
+                    None => true,
+                    Some(chosen) => {
+                        orientation_comparator.compare(
+                            &candidate,
+                            chosen,
+                            heuristic_fn.as_box_size_heuristic_fn(),
+                        ) == Ordering::Less
+                    }
+                };
+                if is_better {
+                    chosen = Some(candidate);
+                }
+            }
+        }
+
+        match chosen {
+            Some(chosen) => Ok((chosen.location, chosen.remaining_sections)),
+            None => Err(first_err.unwrap()),
+        }
+    }
+
+    /// Whether the rectangle fits into this section in any of its allowed orientations.
+    pub(crate) fn incoming_can_fit_in_allowed_orientation(&self, incoming: &RectToInsert) -> bool {
+        incoming.allowed_orientations().any(|(whd, _)| {
+            let mut oriented = *incoming;
+            oriented.whd = whd;
+
+            self.incoming_can_fit(&oriented).is_ok()
+        })
     }
 
-    fn incoming_can_fit(&self, incoming: &RectToInsert) -> Result<(), BinSectionError> {
+    pub(crate) fn incoming_can_fit(&self, incoming: &RectToInsert) -> Result<(), BinSectionError> {
         if incoming.width() > self.whd.width {
             return Err(BinSectionError::PlacementWiderThanBinSection);
         }
@@ -202,7 +475,48 @@ impl BinSection {
         }
 
         if incoming.depth() > self.whd.depth {
-            return Err(BinSectionError::PlacementDeeperThanBinSection);
+            return Err(match incoming.layered_depth {
+                true => BinSectionError::PlacementHasMoreLayersThanBinSection,
+                false => BinSectionError::PlacementDeeperThanBinSection,
+            });
+        }
+
+        if let Some(max_x) = incoming.max_x {
+            if self.x as u64 + incoming.width() as u64 > max_x as u64 {
+                return Err(BinSectionError::PlacementPastMaxX);
+            }
+        }
+        if let Some(max_y) = incoming.max_y {
+            if self.y as u64 + incoming.height() as u64 > max_y as u64 {
+                return Err(BinSectionError::PlacementPastMaxY);
+            }
+        }
+        if let Some(layer) = incoming.layer {
+            let block_depth = incoming.block_size.depth;
+            let layer_block = layer / block_depth;
+            let covers_layer = layer % block_depth == 0
+                && self.z <= layer_block
+                && layer_block as u64 + incoming.depth() as u64
+                    <= self.z as u64 + self.whd.depth as u64;
+            if !covers_layer {
+                return Err(BinSectionError::PlacementNotOnLayer);
+            }
+        }
+        if let Some(edge) = incoming.edge {
+            let touches_edge = match (edge, incoming.bin_whd) {
+                (BinEdge::Left, _) => self.x == 0,
+                (BinEdge::Bottom, _) => self.y == 0,
+                (BinEdge::Right, Some(bin_whd)) => {
+                    self.x as u64 + incoming.width() as u64 == bin_whd.width as u64
+                }
+                (BinEdge::Top, Some(bin_whd)) => {
+                    self.y as u64 + incoming.height() as u64 == bin_whd.height as u64
+                }
+                (_, None) => true,
+            };
+            if !touches_edge {
+                return Err(BinSectionError::PlacementNotOnEdge);
+            }
         }
 
         Ok(())
@@ -474,6 +788,83 @@ mod tests {
         );
     }
 
+    /// If we're trying to place a rectangle that needs more layers than the container has we
+    /// return an error
+    #[test]
+    fn error_if_placement_has_more_layers_than_bin_section() {
+        let bin_section = bin_section_width_height_depth(5, 20, 1);
+        let mut placement = RectToInsert::new(5, 20, 2);
+        placement.layered_depth = true;
+
+        assert_eq!(
+            bin_section
+                .try_place(&placement, &contains_smallest_box, &volume_heuristic)
+                .unwrap_err(),
+            BinSectionError::PlacementHasMoreLayersThanBinSection
+        );
+    }
+
+    /// If the section doesn't cover the layer that the rectangle has to be placed onto we return
+    /// an error
+    #[test]
+    fn error_if_section_does_not_cover_layer() {
+        let bin_section = BinSection::new(0, 0, 2, WidthHeightDepth::new(4, 4, 3));
+
+        for (placement, expected) in [
+            (
+                RectToInsert::new(4, 4, 1).with_layer(1),
+                Err(BinSectionError::PlacementNotOnLayer),
+            ),
+            (RectToInsert::new(4, 4, 1).with_layer(2), Ok(())),
+            (RectToInsert::new(4, 4, 1).with_layer(4), Ok(())),
+            (
+                RectToInsert::new(4, 4, 2).with_layer(4),
+                Err(BinSectionError::PlacementNotOnLayer),
+            ),
+            (
+                RectToInsert::new(4, 4, 1).with_layer(5),
+                Err(BinSectionError::PlacementNotOnLayer),
+            ),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                bin_section
+                    .try_place(placement, &contains_smallest_box, &volume_heuristic)
+                    .map(|_| ()),
+                *expected
+            );
+        }
+    }
+
+    /// If the rectangle would end past its maximum coordinates we return an error
+    #[test]
+    fn error_if_placement_is_past_max_coordinates() {
+        let bin_section = BinSection::new(4, 6, 0, WidthHeightDepth::new(6, 4, 1));
+
+        for (placement, expected) in [
+            (
+                RectToInsert::new(6, 4, 1).with_max_y(9),
+                Err(BinSectionError::PlacementPastMaxY),
+            ),
+            (RectToInsert::new(6, 4, 1).with_max_y(10), Ok(())),
+            (
+                RectToInsert::new(6, 4, 1).with_max_x(9),
+                Err(BinSectionError::PlacementPastMaxX),
+            ),
+            (RectToInsert::new(6, 4, 1).with_max_x(10), Ok(())),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                bin_section
+                    .try_place(placement, &contains_smallest_box, &volume_heuristic)
+                    .map(|_| ()),
+                *expected
+            );
+        }
+    }
+
     fn test_splits(
         container_dimensions: u32,
         rect_to_place: WidthHeightDepth,
@@ -486,14 +877,54 @@ mod tests {
 
         let placement = RectToInsert::new(whd.width, whd.height, whd.depth);
 
-        let mut packed = bin_section
+        let packed = bin_section
             .try_place(&placement, &contains_smallest_box, &volume_heuristic)
             .unwrap();
 
-        packed.1.sort();
+        let mut remaining = packed.1.sections().to_vec();
+        remaining.sort();
         expected.sort();
 
-        assert_eq!(packed.1, expected);
+        assert_eq!(remaining, expected);
+    }
+
+    /// Verify that exactly matching a section in some dimensions only leaves non empty sections.
+    #[test]
+    fn exact_fit_leaves_only_non_empty_sections() {
+        let bin_section = bin_section_width_height_depth(10, 20, 1);
+
+        let packed = bin_section
+            .try_place(
+                &RectToInsert::new(10, 5, 1),
+                &contains_smallest_box,
+                &volume_heuristic,
+            )
+            .unwrap();
+
+        assert_eq!(
+            packed.1.sections(),
+            &[BinSection::new_spread(0, 5, 0, 10, 15, 1)]
+        );
+        assert!(packed.1.exact_fit().width());
+        assert!(!packed.1.exact_fit().height());
+        assert!(packed.1.exact_fit().depth());
+    }
+
+    /// Verify that a box that exactly fills a section leaves nothing behind.
+    #[test]
+    fn complete_exact_fit_leaves_no_sections() {
+        let bin_section = bin_section_width_height_depth(10, 20, 1);
+
+        let packed = bin_section
+            .try_place(
+                &RectToInsert::new(10, 20, 1),
+                &contains_smallest_box,
+                &volume_heuristic,
+            )
+            .unwrap();
+
+        assert!(packed.1.sections().is_empty());
+        assert!(packed.1.exact_fit().is_complete());
     }
 
     /// Verify that we choose the correct splits when the placed rectangle is width > height > depth
@@ -1,4 +1,5 @@
 use crate::bin_section::BinSection;
+use crate::width_height_depth::WidthHeightDepth;
 
 impl BinSection {
     /// Whether or not two bin sections overlap each other.
@@ -8,6 +9,50 @@ impl BinSection {
             && (self.z >= other.z && self.z <= other.back())
     }
 
+    /// The part of this section that is also within the other section, if any.
+    pub(crate) fn intersection(&self, other: &Self) -> Option<BinSection> {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let z = self.z.max(other.z);
+        let right = (self.x + self.whd.width).min(other.x + other.whd.width);
+        let top = (self.y + self.whd.height).min(other.y + other.whd.height);
+        let back = (self.z + self.whd.depth).min(other.z + other.whd.depth);
+
+        if x >= right || y >= top || z >= back {
+            return None;
+        }
+
+        Some(BinSection::new(
+            x,
+            y,
+            z,
+            WidthHeightDepth {
+                width: right - x,
+                height: top - y,
+                depth: back - z,
+            },
+        ))
+    }
+
+    /// Whether the other section lies entirely within this section.
+    pub(crate) fn contains_section(&self, other: &Self) -> bool {
+        let within = |start: u32, len: u32, other_start: u32, other_len: u32| {
+            other_start >= start
+                && other_start as u64 + other_len as u64 <= start as u64 + len as u64
+        };
+
+        within(self.x, self.whd.width, other.x, other.whd.width)
+            && within(self.y, self.whd.height, other.y, other.whd.height)
+            && within(self.z, self.whd.depth, other.z, other.whd.depth)
+    }
+
+    /// Whether the unit cube at the given position is within this section.
+    pub(crate) fn contains_point(&self, x: u32, y: u32, z: u32) -> bool {
+        (x >= self.x && x - self.x < self.whd.width)
+            && (y >= self.y && y - self.y < self.whd.height)
+            && (z >= self.z && z - self.z < self.whd.depth)
+    }
+
     fn right(&self) -> u32 {
         self.x + (self.whd.width - 1)
     }
@@ -0,0 +1,85 @@
+use crate::bin_section::BinSection;
+
+/// The free sections that are left over after placing a box into a [`BinSection`].
+///
+/// Only sections that have a volume are kept. When the box exactly matches the section in one or
+/// more dimensions there is nothing left over along those dimensions, so fewer than three
+/// sections remain, down to none when the box exactly filled the section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RemainingSections {
+    sections: [BinSection; 3],
+    len: usize,
+    exact_fit: ExactFit,
+}
+
+/// The dimensions in which a placed box exactly matched the section that it was placed into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ExactFit {
+    pub(crate) width: bool,
+    pub(crate) height: bool,
+    pub(crate) depth: bool,
+}
+
+impl RemainingSections {
+    /// Keep only the sections that have a volume.
+    pub(crate) fn new(split: [BinSection; 3], exact_fit: ExactFit) -> Self {
+        let mut sections = [BinSection::default(); 3];
+        let mut len = 0;
+
+        for section in split.iter().filter(|section| section.whd.volume() > 0) {
+            sections[len] = *section;
+            len += 1;
+        }
+
+        RemainingSections {
+            sections,
+            len,
+            exact_fit,
+        }
+    }
+
+    /// The non empty sections that are left over, between zero and three of them.
+    pub fn sections(&self) -> &[BinSection] {
+        &self.sections[..self.len]
+    }
+
+    pub(crate) fn sections_mut(&mut self) -> &mut [BinSection] {
+        &mut self.sections[..self.len]
+    }
+
+    /// The dimensions in which the box exactly matched the section.
+    pub fn exact_fit(&self) -> ExactFit {
+        self.exact_fit
+    }
+}
+
+impl AsRef<[BinSection]> for RemainingSections {
+    fn as_ref(&self) -> &[BinSection] {
+        self.sections()
+    }
+}
+
+#[allow(missing_docs)]
+impl ExactFit {
+    pub fn width(&self) -> bool {
+        self.width
+    }
+
+    pub fn height(&self) -> bool {
+        self.height
+    }
+
+    pub fn depth(&self) -> bool {
+        self.depth
+    }
+
+    /// Whether the box exactly filled the section, leaving nothing over.
+    pub fn is_complete(&self) -> bool {
+        self.width && self.height && self.depth
+    }
+
+    /// Whether the box exactly matched the section in at least one dimension.
+    pub fn any_dimension(&self) -> bool {
+        self.width || self.height || self.depth
+    }
+}
@@ -0,0 +1,237 @@
+//! Choosing the size of a bin for rectangles that don't have one yet.
+
+use crate::width_height_depth::WidthHeightDepth;
+use crate::{
+    contains_smallest_box, pack_rects_with_options, volume_heuristic, GroupedRectsToPlace,
+    PackOptions, TargetBin,
+};
+
+use alloc::collections::BTreeMap;
+use core::{fmt::Debug, hash::Hash};
+
+/// Limits on the bin sizes that [`suggest_bin_size`] may suggest.
+#[derive(Debug, Clone)]
+pub struct BinSizeConstraints {
+    max_width: u32,
+    max_height: u32,
+    power_of_two: bool,
+    square: bool,
+    options: PackOptions,
+}
+
+impl BinSizeConstraints {
+    /// Suggest bins of up to the given width and height.
+    pub fn new(max_width: u32, max_height: u32) -> Self {
+        BinSizeConstraints {
+            max_width,
+            max_height,
+            power_of_two: false,
+            square: false,
+            options: PackOptions::default(),
+        }
+    }
+
+    /// Only suggest widths and heights that are powers of two, such as for GPU textures.
+    ///
+    /// Defaults to `false`.
+    pub fn with_power_of_two(mut self, power_of_two: bool) -> Self {
+        self.power_of_two = power_of_two;
+        self
+    }
+
+    /// Only suggest bins whose width and height are the same.
+    ///
+    /// Defaults to `false`.
+    pub fn with_square(mut self, square: bool) -> Self {
+        self.square = square;
+        self
+    }
+
+    /// Check whether the rectangles fit by packing them with these options instead of the
+    /// defaults, which should be the options that they will later be packed with.
+    pub fn with_pack_options(mut self, options: PackOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Round a size up to the next one that meets the constraints.
+    fn round_up(&self, mut width: u32, mut height: u32) -> (u32, u32) {
+        if self.square {
+            width = width.max(height);
+            height = width;
+        }
+        if self.power_of_two {
+            width = width.checked_next_power_of_two().unwrap_or(u32::MAX);
+            height = height.checked_next_power_of_two().unwrap_or(u32::MAX);
+        }
+
+        (width, height)
+    }
+
+    /// The widest width that meets the constraints.
+    fn widest(&self) -> u32 {
+        if self.power_of_two {
+            1 << (31 - self.max_width.max(1).leading_zeros())
+        } else {
+            self.max_width
+        }
+    }
+}
+
+/// Suggest the size of a single bin that all of the rectangles fit into, such as for creating an
+/// atlas from scratch.
+///
+/// The search starts at about the smallest square that has as much area as the rectangles,
+/// widened to fit the widest and tallest of them but no wider than the maximum width. It grows
+/// the shorter side of the bin, or the other side once the shorter one is as long as the
+/// constraints allow, until packing the rectangles into it succeeds. The suggested bin is as deep as the deepest rectangle.
+///
+/// This is a starting point rather than the smallest possible bin, since the packer's heuristics
+/// can't always find a packing that a slightly smaller bin would allow.
+///
+/// Returns `None` if the rectangles don't fit into any bin that meets the constraints.
+///
+/// ```
+/// use rectangle_pack::{
+///     suggest_bin_size, BinSizeConstraints, GroupedRectsToPlace, RectToInsert, WidthHeightDepth,
+/// };
+///
+/// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+/// for sprite in 0..5 {
+///     rects_to_place.push_rect(sprite, None, RectToInsert::new(16, 16, 1));
+/// }
+///
+/// let constraints = BinSizeConstraints::new(1024, 1024).with_power_of_two(true);
+///
+/// assert_eq!(
+///     suggest_bin_size(&rects_to_place, &constraints),
+///     Some(WidthHeightDepth::new(64, 32, 1))
+/// );
+/// ```
+pub fn suggest_bin_size<RectToPlaceId, GroupId>(
+    rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    constraints: &BinSizeConstraints,
+) -> Option<WidthHeightDepth>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+{
+    let rects = rects_to_place.rects.values();
+    let depth = rects.clone().map(|rect| rect.whd.depth).max().unwrap_or(1);
+    let min_width = rects.clone().map(|rect| rect.whd.width).max().unwrap_or(1);
+    let min_height = rects.clone().map(|rect| rect.whd.height).max().unwrap_or(1);
+    let area: u128 = rects.map(|rect| rect.whd.volume()).sum::<u128>() / depth.max(1) as u128;
+
+    let side = ceil_sqrt(area).min(u32::MAX as u128) as u32;
+    let (width, _) = constraints.round_up(side.max(min_width).max(1), 1);
+    let width = width.min(constraints.widest());
+    let height = area.div_ceil(width as u128);
+    let height = (height.min(u32::MAX as u128) as u32).max(min_height).max(1);
+    let (mut width, mut height) = constraints.round_up(width, height);
+
+    while width <= constraints.max_width && height <= constraints.max_height {
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert((), TargetBin::new(width, height, depth));
+
+        let fits = pack_rects_with_options(
+            rects_to_place,
+            &mut target_bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &constraints.options,
+        )
+        .is_ok();
+        if fits {
+            return Some(WidthHeightDepth::new(width, height, depth));
+        }
+
+        // Grow the shorter side, unless it is already as long as it may be.
+        let wider = constraints.round_up(width.saturating_add(grow_step(width)), height);
+        let taller = constraints.round_up(width, height.saturating_add(grow_step(height)));
+        let can_widen = wider != (width, height) && wider.0 <= constraints.max_width;
+        let can_heighten = taller != (width, height) && taller.1 <= constraints.max_height;
+        let grown = match (width <= height, can_widen, can_heighten) {
+            (true, true, _) | (false, true, false) => wider,
+            (_, _, true) => taller,
+            (_, false, false) => return None,
+        };
+        width = grown.0;
+        height = grown.1;
+    }
+
+    None
+}
+
+/// How much to grow a side of the bin by after the rectangles didn't fit.
+fn grow_step(side: u32) -> u32 {
+    (side / 8).max(1)
+}
+
+/// The smallest number whose square is at least `n`.
+fn ceil_sqrt(n: u128) -> u128 {
+    let (mut low, mut high) = (0, 1u128 << 64);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if mid * mid >= n {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+
+    low
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RectToInsert;
+
+    /// Verify that a square bin is suggested for rectangles that tile it exactly, and that a bin
+    /// is only suggested within the maximum size.
+    #[test]
+    fn suggests_smallest_square_that_fits() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        for sprite in 0..9 {
+            rects_to_place.push_rect(sprite, None, RectToInsert::new(10, 10, 1));
+        }
+
+        assert_eq!(
+            suggest_bin_size(&rects_to_place, &BinSizeConstraints::new(100, 100)),
+            Some(WidthHeightDepth::new(30, 30, 1))
+        );
+        assert_eq!(
+            suggest_bin_size(&rects_to_place, &BinSizeConstraints::new(100, 20)),
+            None
+        );
+    }
+
+    /// Verify that a narrow maximum width is kept to, growing the height instead, for plain and
+    /// power of two sizes.
+    #[test]
+    fn grows_height_once_width_is_at_its_maximum() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        for sprite in 0..50 {
+            rects_to_place.push_rect(sprite, None, RectToInsert::new(16, 16, 1));
+        }
+
+        let suggested =
+            suggest_bin_size(&rects_to_place, &BinSizeConstraints::new(64, 1024)).unwrap();
+        assert_eq!(suggested.width(), 64);
+        assert!(suggested.height() >= 208);
+
+        let constraints = BinSizeConstraints::new(100, 1024).with_power_of_two(true);
+        assert_eq!(
+            suggest_bin_size(&rects_to_place, &constraints),
+            Some(WidthHeightDepth::new(64, 256, 1))
+        );
+    }
+
+    /// Verify that the square root is rounded up.
+    #[test]
+    fn ceil_sqrt_rounds_up() {
+        assert_eq!(ceil_sqrt(0), 0);
+        assert_eq!(ceil_sqrt(16), 4);
+        assert_eq!(ceil_sqrt(17), 5);
+    }
+}
@@ -5,7 +5,45 @@ use crate::WidthHeightDepth;
 /// "small" vs. "large" is based on the heuristic function.
 ///
 /// A larger heuristic means that the box is larger.
-pub type BoxSizeHeuristicFn = dyn Fn(WidthHeightDepth) -> u128;
+///
+/// The heuristic may borrow from its surroundings for the lifetime `'a`, such as the
+/// [`PlacementHeuristic`] of [`pack_rects_with_heuristic`].
+///
+/// [`PlacementHeuristic`]: crate::PlacementHeuristic
+/// [`pack_rects_with_heuristic`]: crate::pack_rects_with_heuristic
+pub type BoxSizeHeuristicFn<'a> = dyn Fn(WidthHeightDepth) -> u128 + 'a;
+
+/// Anything that can be used as a [`BoxSizeHeuristicFn`], so that [`pack_rects`] accepts
+/// functions and closures as well as `&BoxSizeHeuristicFn` trait objects.
+///
+/// [`pack_rects`]: crate::pack_rects
+pub trait BoxSizeHeuristic {
+    /// The heuristic as a trait object.
+    fn as_box_size_heuristic_fn(&self) -> &BoxSizeHeuristicFn<'_>;
+
+    /// The size of the box.
+    ///
+    /// Functions and closures are called directly instead of through the trait object.
+    fn box_size(&self, whd: WidthHeightDepth) -> u128 {
+        (self.as_box_size_heuristic_fn())(whd)
+    }
+}
+
+impl<F: Fn(WidthHeightDepth) -> u128> BoxSizeHeuristic for F {
+    fn as_box_size_heuristic_fn(&self) -> &BoxSizeHeuristicFn<'_> {
+        self
+    }
+
+    fn box_size(&self, whd: WidthHeightDepth) -> u128 {
+        self(whd)
+    }
+}
+
+impl BoxSizeHeuristic for BoxSizeHeuristicFn<'_> {
+    fn as_box_size_heuristic_fn(&self) -> &BoxSizeHeuristicFn<'_> {
+        self
+    }
+}
 
 /// The volume of the box
 pub fn volume_heuristic(whd: WidthHeightDepth) -> u128 {
@@ -0,0 +1,425 @@
+//! Consolidating a pack so that the most lightly used bin can be freed.
+
+use crate::grouped_rects_to_place::Group;
+use crate::pack_options::PackContext;
+use crate::{
+    sort_bins_smallest_to_largest, BoxSizeHeuristic, ComparePotentialContainers,
+    GroupedRectsToPlace, PackOptions, RectanglePackOk, TargetBin,
+};
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+/// Attempt to move every rectangle out of the most lightly used bin and into the remaining free
+/// space of the other bins that were used by the pack.
+///
+/// A bin's usage is the sum of the `box_size_heuristic` of the rectangles that were placed into
+/// it. Ties are broken by picking the smallest `BinId`. Frozen bins (see [`TargetBin.freeze`])
+/// are never emptied, and nothing is moved into them.
+///
+/// Rectangles that share a group are moved together, so that every group still ends up within a
+/// single bin. Rectangles are never moved into a bin that holds another rectangle from one of
+/// their distinct bin sets (see [`GroupedRectsToPlace.push_distinct_bin_set`]).
+///
+/// This is all or nothing. If every rectangle in the lightest bin can be relocated, the
+/// `target_bins` and `packed` are updated, the space that the rectangles occupied is returned to
+/// the lightest bin and the ids of the moved rectangles are returned, allowing the caller to
+/// re-upload only those rectangles and potentially drop the now empty bin.
+///
+/// Otherwise nothing is modified and an empty `Vec` is returned.
+///
+/// Rectangles are placed again using the `options` and `allowed_bins` that they were packed
+/// with, so constraints such as a [`PackOptions.with_split_strategy`] or
+/// [`PackOptions.with_placement_validator`] still hold for the moved rectangles, and a rectangle
+/// that has an entry in `allowed_bins` is only moved into one of the bins that its entry lists
+/// (see [`pack_rects_with_allowed_bins`]).
+///
+/// Note that the space returned to the emptied bin is pushed back as one section per moved
+/// rectangle. See [`TargetBin.coalesce_available_sections`] for combining them.
+///
+/// [`GroupedRectsToPlace.push_distinct_bin_set`]: GroupedRectsToPlace::push_distinct_bin_set
+/// [`TargetBin.freeze`]: TargetBin::freeze
+/// [`PackOptions.with_split_strategy`]: PackOptions::with_split_strategy
+/// [`PackOptions.with_placement_validator`]: PackOptions::with_placement_validator
+/// [`pack_rects_with_allowed_bins`]: crate::pack_rects_with_allowed_bins
+/// [`TargetBin.coalesce_available_sections`]: struct.TargetBin.html#method.coalesce_available_sections
+pub fn compact_lightest_bin<RectToPlaceId, BinId, GroupId, Heuristic, Compare>(
+    rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    target_bins: &mut BTreeMap<BinId, TargetBin>,
+    packed: &mut RectanglePackOk<RectToPlaceId, BinId>,
+    allowed_bins: Option<&BTreeMap<RectToPlaceId, Vec<BinId>>>,
+    box_size_heuristic: &Heuristic,
+    more_suitable_containers_fn: &Compare,
+    options: &PackOptions,
+) -> Vec<RectToPlaceId>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+{
+    let mut usage_per_bin: BTreeMap<BinId, u128> = BTreeMap::new();
+    for (bin_id, location) in packed.packed_locations.values() {
+        *usage_per_bin.entry(bin_id.clone()).or_insert(0) +=
+            box_size_heuristic.box_size(location.effective_whd());
+    }
+
+    if usage_per_bin.len() < 2 {
+        return vec![];
+    }
+
+    let lightest_bin_id = match usage_per_bin
+        .iter()
+        .filter(|(bin_id, _)| matches!(target_bins.get(*bin_id), Some(bin) if !bin.frozen))
+        .min_by_key(|(_, usage)| **usage)
+    {
+        Some((bin_id, _)) => bin_id.clone(),
+        None => return vec![],
+    };
+
+    let mut rects_in_lightest_bin: Vec<RectToPlaceId> = packed
+        .packed_locations
+        .iter()
+        .filter(|(_, (bin_id, _))| bin_id == &lightest_bin_id)
+        .map(|(rect_id, _)| rect_id.clone())
+        .collect();
+    rects_in_lightest_bin.sort();
+
+    let mut clusters = clusters_that_must_move_together(&rects_in_lightest_bin, rects_to_place);
+    clusters.sort_by_key(|cluster| {
+        core::cmp::Reverse(
+            cluster
+                .iter()
+                .map(|rect_id| box_size_heuristic.box_size(rects_to_place.rects[rect_id].whd))
+                .sum::<u128>(),
+        )
+    });
+
+    let mut candidate_bins: Vec<(&BinId, &mut TargetBin)> = target_bins
+        .iter_mut()
+        .filter(|(bin_id, _)| *bin_id != &lightest_bin_id && usage_per_bin.contains_key(*bin_id))
+        .collect();
+    sort_bins_smallest_to_largest(&mut candidate_bins, box_size_heuristic);
+
+    let ctx = PackContext::new(box_size_heuristic, more_suitable_containers_fn, options);
+    let is_allowed_bin = |rect_id: &RectToPlaceId, bin_id: &BinId| match allowed_bins
+        .and_then(|allowed_bins| allowed_bins.get(rect_id))
+    {
+        Some(allowed) => allowed.contains(bin_id),
+        None => true,
+    };
+
+    let mut working_bins: Vec<(BinId, TargetBin)> = candidate_bins
+        .into_iter()
+        .map(|(bin_id, bin)| (bin_id.clone(), bin.clone_without_metrics()))
+        .collect();
+    let mut new_locations = vec![];
+
+    'cluster: for cluster in clusters.iter() {
+        for (bin_id, working_bin) in working_bins.iter_mut() {
+            if !cluster
+                .iter()
+                .all(|rect_id| is_allowed_bin(rect_id, bin_id))
+            {
+                continue;
+            }
+
+            let mut rects_in_bin = packed
+                .packed_locations
+                .iter()
+                .chain(
+                    new_locations
+                        .iter()
+                        .map(|(rect_id, location)| (rect_id, location)),
+                )
+                .filter(|(_, (in_bin_id, _))| in_bin_id == bin_id)
+                .map(|(rect_id, _)| rect_id);
+            let shares_distinct_bin_set = !rects_to_place.distinct_bin_sets.is_empty()
+                && rects_in_bin.any(|in_bin| {
+                    cluster.iter().any(|rect_id| {
+                        rects_to_place
+                            .shared_distinct_bin_set(rect_id, in_bin)
+                            .is_some()
+                    })
+                });
+            if shares_distinct_bin_set {
+                continue;
+            }
+
+            let mut bin = working_bin.clone();
+            let mut cluster_locations = vec![];
+
+            for rect_id in cluster.iter() {
+                let previous = &packed.packed_locations[rect_id].1;
+
+                match bin.place_again(&rects_to_place.rects[rect_id], previous, &ctx) {
+                    Some(location) => {
+                        cluster_locations.push((rect_id.clone(), (bin_id.clone(), location)))
+                    }
+                    None => break,
+                };
+            }
+
+            if cluster_locations.len() == cluster.len() {
+                *working_bin = bin;
+                new_locations.extend(cluster_locations);
+                continue 'cluster;
+            }
+        }
+
+        return vec![];
+    }
+
+    for (bin_id, mut bin) in working_bins {
+        let target_bin = target_bins.get_mut(&bin_id).unwrap();
+        bin.metrics = target_bin.metrics.take();
+        *target_bin = bin;
+    }
+
+    let lightest_bin = target_bins.get_mut(&lightest_bin_id).unwrap();
+    let mut moved = Vec::with_capacity(new_locations.len());
+    for (rect_id, new_location) in new_locations {
+        let (_, old_location) = packed
+            .packed_locations
+            .insert(rect_id.clone(), new_location)
+            .unwrap();
+
+        let freed = lightest_bin.section_occupied_by(&old_location);
+        lightest_bin.push_available_bin_section_unchecked(freed);
+        lightest_bin.placed_weight = lightest_bin
+            .placed_weight
+            .saturating_sub(rects_to_place.rects[&rect_id].weight);
+
+        moved.push(rect_id);
+    }
+
+    moved
+}
+
+/// Split the rectangles into clusters of rectangles that are (transitively) grouped together.
+fn clusters_that_must_move_together<RectToPlaceId, GroupId>(
+    rect_ids: &[RectToPlaceId],
+    rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+) -> Vec<Vec<RectToPlaceId>>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+{
+    let mut clusters: Vec<Vec<RectToPlaceId>> = vec![];
+
+    for rect_id in rect_ids {
+        let mut cluster = vec![rect_id.clone()];
+
+        let groups = rects_to_place.inbound_id_to_group_ids.get(rect_id);
+        for group in groups.into_iter().flatten() {
+            if let Group::Grouped(_) = group {
+                for member in rects_to_place.group_id_to_inbound_ids[group].iter() {
+                    if !cluster.contains(member) && rect_ids.contains(member) {
+                        cluster.push(member.clone());
+                    }
+                }
+            }
+        }
+
+        let (overlapping, disjoint): (Vec<_>, Vec<_>) =
+            clusters
+                .into_iter()
+                .partition(|existing: &Vec<RectToPlaceId>| {
+                    existing.iter().any(|r| cluster.contains(r))
+                });
+
+        for member in overlapping.into_iter().flatten() {
+            if !cluster.contains(&member) {
+                cluster.push(member);
+            }
+        }
+
+        clusters = disjoint;
+        clusters.push(cluster);
+    }
+
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        contains_smallest_box, pack_rects, volume_heuristic, PackedLocation, RectToInsert,
+        ValidatePlacement,
+    };
+
+    use alloc::sync::Arc;
+
+    /// Verify that the rectangles in the most lightly used bin get moved into the free space of
+    /// the other bin, and that their old space is returned to the emptied bin.
+    #[test]
+    fn moves_rects_out_of_lightest_bin() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect(RectToPlaceId::One, None, RectToInsert::new(5, 10, 1));
+        rects_to_place.push_rect(RectToPlaceId::Two, None, RectToInsert::new(2, 2, 1));
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert(BinId::Three, TargetBin::new(10, 10, 1));
+        target_bins.insert(BinId::Four, TargetBin::new(10, 10, 1));
+
+        let mut packed = pack_then_spill_into_second_bin(&rects_to_place, &mut target_bins);
+
+        let moved = compact_lightest_bin(
+            &rects_to_place,
+            &mut target_bins,
+            &mut packed,
+            None,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::default(),
+        );
+
+        assert_eq!(moved, vec![RectToPlaceId::Two]);
+        assert_eq!(packed.packed_locations[&RectToPlaceId::Two].0, BinId::Three);
+        assert_eq!(packed.packed_locations[&RectToPlaceId::One].0, BinId::Three);
+
+        assert_eq!(
+            target_bins[&BinId::Four].available_bin_sections.len(),
+            // The two sections created by the original placement and the section returned by
+            // moving the rectangle.
+            3
+        );
+    }
+
+    /// Verify that nothing changes if the lightest bin can't be fully emptied.
+    #[test]
+    fn leaves_pack_untouched_if_bin_cannot_be_emptied() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect(RectToPlaceId::One, None, RectToInsert::new(10, 10, 1));
+        rects_to_place.push_rect(RectToPlaceId::Two, None, RectToInsert::new(2, 2, 1));
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert(BinId::Three, TargetBin::new(10, 10, 1));
+        target_bins.insert(BinId::Four, TargetBin::new(10, 10, 1));
+
+        let mut packed = pack_then_spill_into_second_bin(&rects_to_place, &mut target_bins);
+        let packed_before = packed.packed_locations.clone();
+
+        let moved = compact_lightest_bin(
+            &rects_to_place,
+            &mut target_bins,
+            &mut packed,
+            None,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::default(),
+        );
+
+        assert!(moved.is_empty());
+        assert_eq!(packed.packed_locations, packed_before);
+    }
+
+    /// Verify that the moved rectangles are placed using the options and allowed bins that they
+    /// were packed with.
+    #[test]
+    fn compaction_keeps_pack_constraints() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect(RectToPlaceId::One, None, RectToInsert::new(5, 10, 1));
+        rects_to_place.push_rect(RectToPlaceId::Two, None, RectToInsert::new(2, 2, 1));
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert(BinId::Three, TargetBin::new(10, 10, 1));
+        target_bins.insert(BinId::Four, TargetBin::new(10, 10, 1));
+
+        let mut packed = pack_then_spill_into_second_bin(&rects_to_place, &mut target_bins);
+        let packed_before = packed.packed_locations.clone();
+
+        // The only free space in the other bin is to the right of x = 5.
+        let options = PackOptions::new().with_placement_validator(Arc::new(LeftHalfOnly));
+        let moved = compact_lightest_bin(
+            &rects_to_place,
+            &mut target_bins,
+            &mut packed,
+            None,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &options,
+        );
+        assert!(moved.is_empty());
+
+        let mut allowed_bins = BTreeMap::new();
+        allowed_bins.insert(RectToPlaceId::Two, vec![BinId::Four]);
+        let moved = compact_lightest_bin(
+            &rects_to_place,
+            &mut target_bins,
+            &mut packed,
+            Some(&allowed_bins),
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::default(),
+        );
+        assert!(moved.is_empty());
+
+        assert_eq!(packed.packed_locations, packed_before);
+    }
+
+    /// Only allows placements that start in the left half of a 10 texel wide bin.
+    #[derive(Debug)]
+    struct LeftHalfOnly;
+
+    impl ValidatePlacement for LeftHalfOnly {
+        fn is_valid(&self, location: &PackedLocation) -> bool {
+            location.x() < 5
+        }
+    }
+
+    /// Place `RectToPlaceId::One` into `BinId::Three` and then `RectToPlaceId::Two` into
+    /// `BinId::Four`, as if they were packed at different times.
+    fn pack_then_spill_into_second_bin(
+        rects_to_place: &GroupedRectsToPlace<RectToPlaceId, ()>,
+        target_bins: &mut BTreeMap<BinId, TargetBin>,
+    ) -> RectanglePackOk<RectToPlaceId, BinId> {
+        let mut packed = None;
+
+        for (rect_id, bin_id) in [
+            (RectToPlaceId::One, BinId::Three),
+            (RectToPlaceId::Two, BinId::Four),
+        ]
+        .iter()
+        {
+            let mut single: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+            single.push_rect(*rect_id, None, rects_to_place.rects[rect_id]);
+
+            let mut single_bin = BTreeMap::new();
+            single_bin.insert(*bin_id, target_bins[bin_id].clone());
+
+            let result = pack_rects(
+                &single,
+                &mut single_bin,
+                &volume_heuristic,
+                &contains_smallest_box,
+            )
+            .unwrap();
+            target_bins.insert(*bin_id, single_bin.remove(bin_id).unwrap());
+
+            match packed.as_mut() {
+                None => packed = Some(result),
+                Some(packed) => {
+                    let (rect_id, location) = result.packed_locations.into_iter().next().unwrap();
+                    packed.packed_locations.insert(rect_id, location);
+                }
+            };
+        }
+
+        packed.unwrap()
+    }
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+    enum RectToPlaceId {
+        One,
+        Two,
+    }
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+    enum BinId {
+        Three,
+        Four,
+    }
+}
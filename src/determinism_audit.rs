@@ -0,0 +1,112 @@
+//! Checking that packing does not depend on the order that bins and groups are iterated in.
+//!
+//! See [`PackOptions.with_determinism_audit`].
+//!
+//! [`PackOptions.with_determinism_audit`]: crate::PackOptions::with_determinism_audit
+
+use crate::{PackedLocation, RectanglePackError, TargetBin};
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::fmt::Debug;
+
+/// The seed used to shuffle the bins and groups during an audit.
+pub(crate) const SHUFFLE_SEED: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Shuffle the items in place using a [xorshift] generator with the given seed.
+///
+/// [xorshift]: https://en.wikipedia.org/wiki/Xorshift
+pub(crate) fn shuffle<T>(items: &mut [T], seed: u64) {
+    let mut state = seed.max(1);
+
+    for idx in (1..items.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+
+        items.swap(idx, (state % (idx as u64 + 1)) as usize);
+    }
+}
+
+/// Panic if the shuffled pack had a different outcome than the regular one.
+pub(crate) fn assert_identical<RectToPlaceId, BinId>(
+    placements: &Result<Vec<(&RectToPlaceId, BinId, PackedLocation)>, RectanglePackError>,
+    shuffled_placements: &Result<Vec<(&RectToPlaceId, BinId, PackedLocation)>, RectanglePackError>,
+    target_bins: &BTreeMap<BinId, TargetBin>,
+    shuffled_bins: &BTreeMap<BinId, TargetBin>,
+) where
+    RectToPlaceId: Debug + Ord,
+    BinId: Debug + Ord + Clone,
+{
+    assert_eq!(
+        sorted(placements),
+        sorted(shuffled_placements),
+        "Packing depends on the order that bins or groups are iterated in"
+    );
+
+    for (bin_id, bin) in target_bins.iter() {
+        assert_eq!(
+            bin.available_bin_sections, shuffled_bins[bin_id].available_bin_sections,
+            "The free sections of bin {:?} depend on the order that bins or groups are iterated in",
+            bin_id
+        );
+    }
+}
+
+fn sorted<'a, 'b, RectToPlaceId: Ord, BinId: Clone>(
+    placements: &'b Result<Vec<(&'a RectToPlaceId, BinId, PackedLocation)>, RectanglePackError>,
+) -> Result<Vec<(&'a RectToPlaceId, BinId, PackedLocation)>, &'b RectanglePackError> {
+    let mut placements = placements.as_ref()?.clone();
+    placements.sort_by(|a, b| a.0.cmp(b.0));
+    Ok(placements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        contains_smallest_box, pack_rects_with_options, volume_heuristic, GroupedRectsToPlace,
+        PackOptions, RectToInsert,
+    };
+
+    /// Verify that shuffling changes the order of the items, and always in the same way.
+    #[test]
+    fn shuffle_is_fixed() {
+        let mut items: Vec<u32> = (0..10).collect();
+        shuffle(&mut items, SHUFFLE_SEED);
+
+        let mut again: Vec<u32> = (0..10).collect();
+        shuffle(&mut again, SHUFFLE_SEED);
+
+        assert_ne!(items, (0..10).collect::<Vec<_>>());
+        assert_eq!(items, again);
+    }
+
+    /// Verify that packing many identically sized rectangles into identically sized bins passes
+    /// the audit, since ties are broken using the ids.
+    #[test]
+    fn ties_pass_audit() {
+        let mut rects_to_place: GroupedRectsToPlace<_, u8> = GroupedRectsToPlace::new();
+        for rect_id in 0..12u32 {
+            let group = if rect_id % 3 == 0 {
+                Some(vec![(rect_id % 2) as u8])
+            } else {
+                None
+            };
+            rects_to_place.push_rect(rect_id, group, RectToInsert::new(4, 4, 1));
+        }
+
+        let mut target_bins = BTreeMap::new();
+        for bin_id in 0..4 {
+            target_bins.insert(bin_id, TargetBin::new(8, 8, 1));
+        }
+
+        pack_rects_with_options(
+            &rects_to_place,
+            &mut target_bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::new().with_determinism_audit(true),
+        )
+        .unwrap();
+    }
+}
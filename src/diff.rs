@@ -0,0 +1,151 @@
+//! Comparing the results of two packs.
+
+use crate::RectanglePackOk;
+
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+
+/// How the placements of one [`RectanglePackOk`] differ from those of another.
+///
+/// See [`RectanglePackOk.diff`].
+///
+/// [`RectanglePackOk.diff`]: RectanglePackOk::diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackDiff<'a, RectToPlaceId> {
+    added: Vec<&'a RectToPlaceId>,
+    removed: Vec<&'a RectToPlaceId>,
+    moved: Vec<&'a RectToPlaceId>,
+}
+
+impl<RectToPlaceId, BinId> RectanglePackOk<RectToPlaceId, BinId>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+{
+    /// Compare these placements with those of a newer pack, such as to only upload the regions
+    /// of a streamed texture that changed after a [`repack`].
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace, RectToInsert,
+    ///     TargetBin,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let pack = |rects: &[(&'static str, u32)]| {
+    ///     let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    ///     for (rect_id, width) in rects {
+    ///         rects_to_place.push_rect(*rect_id, None, RectToInsert::new(*width, 4, 1));
+    ///     }
+    ///
+    ///     let mut target_bins = BTreeMap::new();
+    ///     target_bins.insert("atlas", TargetBin::new(16, 16, 1));
+    ///
+    ///     pack_rects(&rects_to_place, &mut target_bins, &volume_heuristic, &contains_smallest_box)
+    ///         .unwrap()
+    /// };
+    ///
+    /// let before = pack(&[("grass", 4), ("stone", 4)]);
+    /// let after = pack(&[("grass", 4), ("stone", 8), ("dirt", 4)]);
+    ///
+    /// let diff = before.diff(&after);
+    /// assert_eq!(diff.added(), &[&"dirt"]);
+    /// assert!(diff.removed().is_empty());
+    /// assert!(diff.moved().contains(&&"stone"));
+    /// ```
+    ///
+    /// [`repack`]: crate::repack
+    pub fn diff<'a>(&'a self, newer: &'a Self) -> PackDiff<'a, RectToPlaceId> {
+        let mut added = Vec::new();
+        let mut moved = Vec::new();
+        for (rect_id, placement) in newer.packed_locations.iter() {
+            match self.packed_locations.get(rect_id) {
+                None => added.push(rect_id),
+                Some(old_placement) if old_placement != placement => moved.push(rect_id),
+                Some(_) => {}
+            }
+        }
+
+        let mut removed: Vec<&RectToPlaceId> = self
+            .packed_locations
+            .keys()
+            .filter(|rect_id| !newer.packed_locations.contains_key(rect_id))
+            .collect();
+
+        added.sort();
+        removed.sort();
+        moved.sort();
+
+        PackDiff {
+            added,
+            removed,
+            moved,
+        }
+    }
+}
+
+impl<'a, RectToPlaceId> PackDiff<'a, RectToPlaceId> {
+    /// The rectangles that are only placed in the newer pack, in ascending order.
+    pub fn added(&self) -> &[&'a RectToPlaceId] {
+        &self.added
+    }
+
+    /// The rectangles that are only placed in the older pack, in ascending order.
+    pub fn removed(&self) -> &[&'a RectToPlaceId] {
+        &self.removed
+    }
+
+    /// The rectangles that are placed in both packs, but into a different bin or with a
+    /// different [`PackedLocation`], such as at another position or with another rotation, in
+    /// ascending order.
+    ///
+    /// [`PackedLocation`]: crate::PackedLocation
+    pub fn moved(&self) -> &[&'a RectToPlaceId] {
+        &self.moved
+    }
+
+    /// Whether both packs placed the same rectangles in the same places.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.moved.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace, RectToInsert,
+        TargetBin,
+    };
+    use alloc::collections::BTreeMap;
+
+    /// Verify that rectangles placed into another bin count as moved, while rectangles that
+    /// stayed put don't show up at all.
+    #[test]
+    fn diff_of_rect_in_other_bin() {
+        let pack = |bin_sizes: [u32; 2]| {
+            let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+            rects_to_place.push_rect("a", None, RectToInsert::new(4, 4, 1));
+            rects_to_place.push_rect("b", None, RectToInsert::new(2, 2, 1));
+
+            let mut target_bins = BTreeMap::new();
+            target_bins.insert(0, TargetBin::new(bin_sizes[0], 4, 1));
+            target_bins.insert(1, TargetBin::new(bin_sizes[1], 4, 1));
+
+            pack_rects(
+                &rects_to_place,
+                &mut target_bins,
+                &volume_heuristic,
+                &contains_smallest_box,
+            )
+            .unwrap()
+        };
+
+        let before = pack([4, 2]);
+        assert!(before.diff(&before).is_empty());
+
+        let after = pack([2, 4]);
+        let diff = before.diff(&after);
+        assert_eq!(diff.moved(), &[&"a", &"b"]);
+        assert!(diff.added().is_empty() && diff.removed().is_empty());
+    }
+}
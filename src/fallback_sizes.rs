@@ -0,0 +1,207 @@
+//! Packing rectangles that can be shrunk to smaller sizes when they don't fit.
+
+use crate::pack_options::PackContext;
+use crate::{
+    pack_rects_keyed_by, BoxSizeHeuristic, ComparePotentialContainers, GroupedRectsToPlace,
+    PackOptions, RectanglePackError, RectanglePackOk, TargetBin, WidthHeightDepth,
+};
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+/// The placements along with the index of the fallback size that each rectangle that needed one
+/// was placed using.
+type FallbackSizesPackOk<RectToPlaceId, BinId> = (
+    RectanglePackOk<RectToPlaceId, BinId>,
+    BTreeMap<RectToPlaceId, usize>,
+);
+
+/// Pack rectangles the same way as [`pack_rects_with_options`], but when a rectangle that has an
+/// entry in `fallback_sizes` doesn't fit, try again using the next of its sizes.
+///
+/// Every rectangle starts out at the size that it was pushed with. Whenever a group doesn't fit,
+/// each of its rectangles that has a size left moves on to its next size and the rectangles are
+/// packed again, until everything fits or no group that doesn't fit has any sizes left. This
+/// lets texture streaming fall back to half or quarter resolution mips instead of failing.
+///
+/// Along with the placements, returns the index into its `fallback_sizes` of the size that every
+/// rectangle that was placed using a fallback size ended up with. Rectangles that were placed at
+/// the size that they were pushed with have no entry.
+///
+/// If there is still no room once the sizes are used up, the error from packing at the smallest
+/// sizes is returned.
+///
+/// ```
+/// use rectangle_pack::{
+///     contains_smallest_box, pack_rects_with_fallback_sizes, volume_heuristic,
+///     GroupedRectsToPlace, PackOptions, RectToInsert, TargetBin, WidthHeightDepth,
+/// };
+/// use std::collections::BTreeMap;
+///
+/// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+/// rects_to_place.push_rect("grass", None, RectToInsert::new(32, 32, 1));
+/// rects_to_place.push_rect("stone", None, RectToInsert::new(32, 32, 1));
+///
+/// let mut fallback_sizes = BTreeMap::new();
+/// fallback_sizes.insert(
+///     "stone",
+///     vec![WidthHeightDepth::new(16, 16, 1), WidthHeightDepth::new(8, 8, 1)],
+/// );
+///
+/// let mut target_bins = BTreeMap::new();
+/// target_bins.insert("atlas", TargetBin::new(48, 32, 1));
+///
+/// let (packed, sizes_used) = pack_rects_with_fallback_sizes(
+///     &rects_to_place,
+///     &mut target_bins,
+///     &fallback_sizes,
+///     &volume_heuristic,
+///     &contains_smallest_box,
+///     &PackOptions::default(),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(packed.packed_locations()["stone"].1.width(), 16);
+/// assert_eq!(sizes_used.get("stone"), Some(&0));
+/// assert_eq!(sizes_used.get("grass"), None);
+/// ```
+///
+/// [`pack_rects_with_options`]: crate::pack_rects_with_options
+pub fn pack_rects_with_fallback_sizes<RectToPlaceId, BinId, GroupId, Heuristic, Compare>(
+    rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    target_bins: &mut BTreeMap<BinId, TargetBin>,
+    fallback_sizes: &BTreeMap<RectToPlaceId, Vec<WidthHeightDepth>>,
+    box_size_heuristic: &Heuristic,
+    more_suitable_containers_fn: &Compare,
+    options: &PackOptions,
+) -> Result<FallbackSizesPackOk<RectToPlaceId, BinId>, RectanglePackError>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+{
+    let mut resized = rects_to_place.clone();
+    let mut sizes_used: BTreeMap<RectToPlaceId, usize> = BTreeMap::new();
+
+    // Leave out the groups that don't fit instead of failing, so that we can tell which of the
+    // rectangles need to shrink.
+    let trial_options = PackOptions {
+        best_effort: true,
+        ..options.clone()
+    };
+
+    loop {
+        let mut trial_bins: BTreeMap<BinId, TargetBin> = target_bins
+            .iter()
+            .map(|(bin_id, bin)| (bin_id.clone(), bin.clone_without_metrics()))
+            .collect();
+        let mut trial = RectanglePackOk::default();
+        pack_rects_keyed_by(
+            &resized,
+            &mut trial_bins,
+            None,
+            PackContext::new(
+                box_size_heuristic,
+                more_suitable_containers_fn,
+                &trial_options,
+            ),
+            RectToPlaceId::clone,
+            &mut trial,
+        )?;
+
+        let mut shrunk = false;
+        for rect_to_place_id in trial.omitted_optional_rects() {
+            let sizes = match fallback_sizes.get(rect_to_place_id) {
+                Some(sizes) => sizes,
+                None => continue,
+            };
+            let next = sizes_used.get(rect_to_place_id).map_or(0, |used| used + 1);
+            let whd = match sizes.get(next) {
+                Some(whd) => *whd,
+                None => continue,
+            };
+
+            let rect = resized.rects.get_mut(rect_to_place_id).unwrap();
+            rect.whd = whd;
+            sizes_used.insert(rect_to_place_id.clone(), next);
+            shrunk = true;
+        }
+
+        if !shrunk {
+            break;
+        }
+    }
+
+    let mut packed = RectanglePackOk::default();
+    pack_rects_keyed_by(
+        &resized,
+        target_bins,
+        None,
+        PackContext::new(box_size_heuristic, more_suitable_containers_fn, options),
+        RectToPlaceId::clone,
+        &mut packed,
+    )?;
+
+    sizes_used
+        .retain(|rect_to_place_id, _| packed.packed_locations().contains_key(rect_to_place_id));
+
+    Ok((packed, sizes_used))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{contains_smallest_box, volume_heuristic, RectToInsert};
+
+    use alloc::vec;
+
+    /// Verify that a rectangle skips past fallback sizes that still don't fit, and that we
+    /// return an error once a rectangle has run out of sizes.
+    #[test]
+    fn falls_back_until_rect_fits() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect("a", None, RectToInsert::new(8, 8, 1));
+
+        let mut fallback_sizes = BTreeMap::new();
+        fallback_sizes.insert(
+            "a",
+            vec![
+                WidthHeightDepth::new(6, 6, 1),
+                WidthHeightDepth::new(4, 4, 1),
+            ],
+        );
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert("bin", TargetBin::new(4, 4, 1));
+
+        let (packed, sizes_used) = pack_rects_with_fallback_sizes(
+            &rects_to_place,
+            &mut target_bins.clone(),
+            &fallback_sizes,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(packed.packed_locations()["a"].1.width(), 4);
+        assert_eq!(sizes_used["a"], 1);
+
+        fallback_sizes.get_mut("a").unwrap().pop();
+        let packed = pack_rects_with_fallback_sizes(
+            &rects_to_place,
+            &mut target_bins,
+            &fallback_sizes,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::default(),
+        );
+
+        assert!(matches!(
+            packed,
+            Err(RectanglePackError::NotEnoughBinSpace { .. })
+        ));
+    }
+}
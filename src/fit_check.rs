@@ -0,0 +1,193 @@
+//! Checking whether rectangles would fit without placing them.
+
+use crate::pack_options::PackContext;
+use crate::{
+    pack_rects_keyed_by, BoxSizeHeuristic, ComparePotentialContainers, GroupedRectsToPlace,
+    PackOptions, RectToInsert, RectanglePackOk, TargetBin,
+};
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+/// For each of the `rects`, whether it would currently fit into one of the free sections of any of
+/// the `target_bins` that aren't frozen.
+///
+/// Nothing is placed. Every rectangle is checked on its own, so two rectangles that each fit might
+/// not both fit at the same time.
+///
+/// Useful for disabling the assets that can no longer be added to an atlas.
+///
+/// Free sections are checked as they are, without first being merged with their neighbors.
+///
+/// Only padding that is set using [`RectToInsert.with_padding`] is taken into account.
+///
+/// ```
+/// use rectangle_pack::{fits_in_any_bin, RectToInsert, TargetBin};
+/// use std::collections::BTreeMap;
+///
+/// let mut target_bins = BTreeMap::new();
+/// target_bins.insert("atlas", TargetBin::new(64, 64, 1));
+///
+/// let fits = fits_in_any_bin(
+///     &[RectToInsert::new(32, 32, 1), RectToInsert::new(128, 32, 1)],
+///     &target_bins,
+/// );
+///
+/// assert_eq!(fits, vec![true, false]);
+/// ```
+///
+/// [`RectToInsert.with_padding`]: crate::RectToInsert::with_padding
+pub fn fits_in_any_bin<BinId>(
+    rects: &[RectToInsert],
+    target_bins: &BTreeMap<BinId, TargetBin>,
+) -> Vec<bool> {
+    rects
+        .iter()
+        .map(|rect| {
+            target_bins
+                .values()
+                .filter(|bin| !bin.frozen && bin.can_carry(rect.weight))
+                .any(|bin| {
+                    if rect.is_empty() {
+                        return true;
+                    }
+
+                    let rect = bin.rect_for_bin(&rect.padded(rect.padding.unwrap_or(0)));
+
+                    bin.available_bin_sections
+                        .iter()
+                        .any(|section| section.incoming_can_fit_in_allowed_orientation(&rect))
+                })
+        })
+        .collect()
+}
+
+/// Whether packing the rectangles with [`pack_rects_with_options`] would succeed, without
+/// changing the `target_bins`.
+///
+/// Unlike [`fits_in_any_bin`] this takes the whole batch into account, the same way that packing
+/// it would, such as before committing to uploading a batch of textures. The bins are copied and
+/// packed into, so this costs about as much as packing.
+///
+/// ```
+/// use rectangle_pack::{
+///     can_fit, contains_smallest_box, volume_heuristic, GroupedRectsToPlace, PackOptions,
+///     RectToInsert, TargetBin,
+/// };
+/// use std::collections::BTreeMap;
+///
+/// let mut target_bins = BTreeMap::new();
+/// target_bins.insert("atlas", TargetBin::new(64, 64, 1));
+///
+/// let mut batch: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+/// batch.push_rect("grass", None, RectToInsert::new(64, 32, 1));
+/// batch.push_rect("stone", None, RectToInsert::new(64, 32, 1));
+///
+/// let options = PackOptions::default();
+/// assert!(can_fit(&batch, &target_bins, &volume_heuristic, &contains_smallest_box, &options));
+///
+/// batch.push_rect("dirt", None, RectToInsert::new(64, 32, 1));
+/// assert!(!can_fit(&batch, &target_bins, &volume_heuristic, &contains_smallest_box, &options));
+/// ```
+///
+/// [`pack_rects_with_options`]: crate::pack_rects_with_options
+pub fn can_fit<RectToPlaceId, BinId, GroupId, Heuristic, Compare>(
+    rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    target_bins: &BTreeMap<BinId, TargetBin>,
+    box_size_heuristic: &Heuristic,
+    more_suitable_containers_fn: &Compare,
+    options: &PackOptions,
+) -> bool
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+{
+    // Copied without their metrics, so that the dry run isn't reported as allocations.
+    let mut trial_bins: BTreeMap<BinId, TargetBin> = target_bins
+        .iter()
+        .map(|(bin_id, bin)| (bin_id.clone(), bin.clone_without_metrics()))
+        .collect();
+
+    let mut packed = RectanglePackOk::default();
+    pack_rects_keyed_by(
+        rects_to_place,
+        &mut trial_bins,
+        None,
+        PackContext::new(box_size_heuristic, more_suitable_containers_fn, options),
+        |rect_to_place_id| rect_to_place_id,
+        &mut packed,
+    )
+    .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace};
+
+    /// Verify that the check uses the space that is left after packing, and does not modify the
+    /// bins.
+    #[test]
+    fn checks_remaining_space_without_placing() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect(1, None, RectToInsert::new(10, 6, 1));
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert(0, TargetBin::new(10, 10, 1));
+
+        pack_rects(
+            &rects_to_place,
+            &mut target_bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+        let sections_before = target_bins[&0].available_bin_sections.clone();
+
+        let fits = fits_in_any_bin(
+            &[
+                RectToInsert::new(10, 4, 1),
+                RectToInsert::new(10, 5, 1),
+                RectToInsert::new(5, 10, 1).with_global_z_axis_rotation(true),
+            ],
+            &target_bins,
+        );
+
+        assert_eq!(fits, vec![true, false, false]);
+        assert_eq!(target_bins[&0].available_bin_sections, sections_before);
+    }
+
+    /// Verify that a batch whose rectangles each fit on their own, but not all together, can't
+    /// fit, and that the bins are left unchanged either way.
+    #[test]
+    fn can_fit_checks_whole_batch() {
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert(0, TargetBin::new(10, 10, 1));
+
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect(1, None, RectToInsert::new(10, 6, 1));
+        let options = PackOptions::default();
+
+        assert!(can_fit(
+            &rects_to_place,
+            &target_bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &options
+        ));
+
+        rects_to_place.push_rect(2, None, RectToInsert::new(10, 6, 1));
+        assert!(!can_fit(
+            &rects_to_place,
+            &target_bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &options
+        ));
+
+        assert_eq!(target_bins[&0].free_volume(), 100);
+    }
+}
@@ -0,0 +1,352 @@
+//! A glyph atlas built on top of [`pack_rects_with_options`].
+//!
+//! Enabled using the `font_atlas` feature.
+
+use crate::{
+    contains_smallest_box, pack_rects_with_options, volume_heuristic, GroupedRectsToPlace,
+    PackOptions, PlacementPolicy, RectToInsert, RectanglePackError, TargetBin,
+};
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+/// Packs the glyphs of one or more fonts into pages of a texture atlas.
+///
+/// - Every call to [`FontAtlas.add_glyphs`] places all of the given glyphs onto the same page, so
+///   that a run of text can be drawn from a single texture.
+/// - Each glyph reserves [`FontAtlas.with_padding`] texels on every side so that neighboring
+///   glyphs don't bleed into each other when sampled.
+/// - Glyphs are placed as low and as far left as possible (see [`PlacementPolicy::BottomLeft`]),
+///   which keeps the used part of each page compact.
+/// - When the glyphs don't fit, the pages are doubled in size up to
+///   [`FontAtlas.with_max_page_size`] and everything is re-packed. After that a new page is
+///   added instead.
+///
+/// ```
+/// use rectangle_pack::FontAtlas;
+///
+/// let mut atlas = FontAtlas::new(64, 64).with_padding(1);
+///
+/// atlas.add_glyphs("serif", &[('a', 10, 12), ('b', 10, 14)]).unwrap();
+///
+/// let glyph = atlas.glyph(&"serif", &'b').unwrap();
+/// assert_eq!(glyph.page(), 0);
+/// assert_eq!((glyph.width(), glyph.height()), (10, 14));
+/// ```
+///
+/// [`pack_rects_with_options`]: crate::pack_rects_with_options
+/// [`FontAtlas.add_glyphs`]: FontAtlas::add_glyphs
+/// [`FontAtlas.with_padding`]: FontAtlas::with_padding
+/// [`FontAtlas.with_max_page_size`]: FontAtlas::with_max_page_size
+#[derive(Debug, Clone)]
+pub struct FontAtlas<FontId, GlyphId> {
+    page_width: u32,
+    page_height: u32,
+    max_page_width: u32,
+    max_page_height: u32,
+    padding: u32,
+    pages: BTreeMap<usize, TargetBin>,
+    glyphs: BTreeMap<(FontId, GlyphId), AtlasGlyph>,
+    /// The glyphs added by each call to [`FontAtlas.add_glyphs`], in order, so that they can be
+    /// re-packed together.
+    batches: Vec<GlyphBatch<FontId, GlyphId>>,
+}
+
+/// A font and the `(glyph id, width, height)` of some of its glyphs.
+type GlyphBatch<FontId, GlyphId> = (FontId, Vec<(GlyphId, u32, u32)>);
+
+/// Where a glyph was placed within a [`FontAtlas`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtlasGlyph {
+    page: usize,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    uv: [f32; 4],
+}
+
+/// What happened to the atlas when adding glyphs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AtlasChange {
+    /// The glyphs were placed into the free space of the existing pages. Glyphs that were already
+    /// in the atlas did not move.
+    Placed,
+    /// The glyphs were placed onto a newly added page. Glyphs that were already in the atlas did
+    /// not move.
+    AddedPage,
+    /// The pages were grown and every glyph was re-packed, so every page needs to be uploaded
+    /// again.
+    Grew,
+}
+
+impl<FontId, GlyphId> FontAtlas<FontId, GlyphId>
+where
+    FontId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GlyphId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+{
+    /// Create an atlas with a single, empty page of the given size.
+    pub fn new(page_width: u32, page_height: u32) -> Self {
+        let mut pages = BTreeMap::new();
+        pages.insert(0, TargetBin::new(page_width, page_height, 1));
+
+        FontAtlas {
+            page_width,
+            page_height,
+            max_page_width: page_width,
+            max_page_height: page_height,
+            padding: 0,
+            pages,
+            glyphs: BTreeMap::new(),
+            batches: vec![],
+        }
+    }
+
+    /// Reserve this many texels on every side of every glyph.
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Allow the pages to be doubled in size, up to the given size, before adding more pages.
+    pub fn with_max_page_size(mut self, max_page_width: u32, max_page_height: u32) -> Self {
+        self.max_page_width = max_page_width.max(self.page_width);
+        self.max_page_height = max_page_height.max(self.page_height);
+        self
+    }
+
+    /// Add the `(glyph id, width, height)` of some of a font's glyphs to the atlas, all on the
+    /// same page.
+    ///
+    /// Glyphs that are already in the atlas are skipped.
+    ///
+    /// Returns an error if the glyphs wouldn't fit onto a single page even at the maximum page
+    /// size, in which case the atlas is left unchanged.
+    pub fn add_glyphs(
+        &mut self,
+        font: FontId,
+        glyphs: &[(GlyphId, u32, u32)],
+    ) -> Result<AtlasChange, RectanglePackError> {
+        let mut batch: Vec<(GlyphId, u32, u32)> = vec![];
+        for glyph in glyphs.iter() {
+            let is_new = !self.glyphs.contains_key(&(font.clone(), glyph.0.clone()))
+                && batch.iter().all(|added| added.0 != glyph.0);
+            if is_new {
+                batch.push(glyph.clone());
+            }
+        }
+        if batch.is_empty() {
+            return Ok(AtlasChange::Placed);
+        }
+
+        let batch = (font, batch);
+        let page_size = (self.page_width, self.page_height);
+
+        let mut pages = self.pages.clone();
+        if let Ok(placed) = place_batches(
+            &mut pages,
+            core::slice::from_ref(&batch),
+            page_size,
+            self.padding,
+        ) {
+            self.pages = pages;
+            self.glyphs.extend(placed);
+            self.batches.push(batch);
+            return Ok(AtlasChange::Placed);
+        }
+
+        let mut all_batches = self.batches.clone();
+        all_batches.push(batch.clone());
+
+        let (mut page_width, mut page_height) = page_size;
+        while page_width < self.max_page_width || page_height < self.max_page_height {
+            if page_width <= page_height && page_width < self.max_page_width {
+                page_width = (page_width * 2).min(self.max_page_width);
+            } else {
+                page_height = (page_height * 2).min(self.max_page_height);
+            }
+
+            let mut pages: BTreeMap<usize, TargetBin> = (0..self.pages.len())
+                .map(|page| (page, TargetBin::new(page_width, page_height, 1)))
+                .collect();
+
+            if let Ok(placed) = place_batches(
+                &mut pages,
+                &all_batches,
+                (page_width, page_height),
+                self.padding,
+            ) {
+                self.page_width = page_width;
+                self.page_height = page_height;
+                self.pages = pages;
+                self.glyphs = placed;
+                self.batches = all_batches;
+                return Ok(AtlasChange::Grew);
+            }
+        }
+
+        let mut pages = BTreeMap::new();
+        pages.insert(
+            self.pages.len(),
+            TargetBin::new(self.page_width, self.page_height, 1),
+        );
+
+        let placed = place_batches(
+            &mut pages,
+            core::slice::from_ref(&batch),
+            page_size,
+            self.padding,
+        )?;
+        self.pages.extend(pages);
+        self.glyphs.extend(placed);
+        self.batches.push(batch);
+
+        Ok(AtlasChange::AddedPage)
+    }
+
+    /// Where the glyph was placed, if it is in the atlas.
+    pub fn glyph(&self, font: &FontId, glyph: &GlyphId) -> Option<&AtlasGlyph> {
+        self.glyphs.get(&(font.clone(), glyph.clone()))
+    }
+
+    /// The current size of every page.
+    pub fn page_size(&self) -> (u32, u32) {
+        (self.page_width, self.page_height)
+    }
+
+    /// The number of pages.
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+}
+
+/// Place each batch of glyphs onto a single one of the pages, returning where each glyph went, or
+/// `None` if any of the batches don't fit.
+fn place_batches<FontId, GlyphId>(
+    pages: &mut BTreeMap<usize, TargetBin>,
+    batches: &[GlyphBatch<FontId, GlyphId>],
+    (page_width, page_height): (u32, u32),
+    padding: u32,
+) -> Result<BTreeMap<(FontId, GlyphId), AtlasGlyph>, RectanglePackError>
+where
+    FontId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GlyphId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+{
+    let mut rects_to_place = GroupedRectsToPlace::new();
+    for (batch_idx, (font, glyphs)) in batches.iter().enumerate() {
+        for (glyph, width, height) in glyphs.iter() {
+            rects_to_place.push_rect(
+                (font.clone(), glyph.clone()),
+                Some(vec![batch_idx]),
+                RectToInsert::new(*width, *height, 1).with_padding(padding),
+            );
+        }
+    }
+
+    let packed = pack_rects_with_options(
+        &rects_to_place,
+        pages,
+        &volume_heuristic,
+        &contains_smallest_box,
+        &PackOptions::new().with_placement_policy(PlacementPolicy::BottomLeft),
+    )?;
+
+    let mut placed = BTreeMap::new();
+    for (font, glyphs) in batches.iter() {
+        for (glyph, width, height) in glyphs.iter() {
+            let key = (font.clone(), glyph.clone());
+            let (page, location) = packed.packed_locations()[&key];
+
+            let x = location.x();
+            let y = location.y();
+
+            let uv = [
+                x as f32 / page_width as f32,
+                y as f32 / page_height as f32,
+                (x + width) as f32 / page_width as f32,
+                (y + height) as f32 / page_height as f32,
+            ];
+
+            placed.insert(
+                key,
+                AtlasGlyph {
+                    page,
+                    x,
+                    y,
+                    width: *width,
+                    height: *height,
+                    uv,
+                },
+            );
+        }
+    }
+
+    Ok(placed)
+}
+
+#[allow(missing_docs)]
+impl AtlasGlyph {
+    pub fn page(&self) -> usize {
+        self.page
+    }
+
+    pub fn x(&self) -> u32 {
+        self.x
+    }
+
+    pub fn y(&self) -> u32 {
+        self.y
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The `[u_min, v_min, u_max, v_max]` texture coordinates of the glyph within its page,
+    /// leaving out the padding.
+    pub fn uv(&self) -> [f32; 4] {
+        self.uv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that the atlas grows, moving the existing glyphs, before it adds another page.
+    #[test]
+    fn grows_then_adds_pages() {
+        let mut atlas = FontAtlas::new(8, 8)
+            .with_padding(1)
+            .with_max_page_size(16, 8);
+
+        assert_eq!(
+            atlas.add_glyphs("sans", &[('a', 6, 6)]).unwrap(),
+            AtlasChange::Placed
+        );
+        assert_eq!(
+            atlas.add_glyphs("sans", &[('b', 6, 6)]).unwrap(),
+            AtlasChange::Grew
+        );
+        assert_eq!(atlas.page_size(), (16, 8));
+        assert_eq!(
+            atlas.add_glyphs("mono", &[('a', 6, 6)]).unwrap(),
+            AtlasChange::AddedPage
+        );
+        assert_eq!(atlas.page_count(), 2);
+
+        let glyph = atlas.glyph(&"mono", &'a').unwrap();
+        assert_eq!((glyph.page(), glyph.x(), glyph.y()), (1, 1, 1));
+        assert_eq!(glyph.uv(), [1. / 16., 1. / 8., 7. / 16., 7. / 8.]);
+
+        assert!(matches!(
+            atlas.add_glyphs("mono", &[('b', 20, 20)]),
+            Err(RectanglePackError::NotEnoughBinSpace { .. })
+        ));
+        assert!(atlas.glyph(&"mono", &'b').is_none());
+    }
+}
@@ -0,0 +1,385 @@
+//! Suggesting changes that would allow rectangles that didn't fit to be placed.
+
+use crate::pack_options::PackContext;
+use crate::width_height_depth::WidthHeightDepth;
+use crate::{
+    BoxSizeHeuristic, ComparePotentialContainers, GroupedRectsToPlace, PackOptions, RectToInsert,
+    RectanglePackOk, TargetBin,
+};
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+/// A single change that would allow more of the unplaced rectangles to be placed.
+///
+/// See [`analyze_gaps`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct GapSuggestion<RectToPlaceId, BinId> {
+    pub(crate) change: GapChange<RectToPlaceId, BinId>,
+    pub(crate) additional_placements: usize,
+}
+
+/// A change to the rectangles or bins of a pack.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GapChange<RectToPlaceId, BinId> {
+    /// Rotate an unplaced rectangle by 90 degrees, swapping its width and height.
+    RotateRect {
+        #[allow(missing_docs)]
+        rect_id: RectToPlaceId,
+    },
+    /// Grow a bin along one of its axes. Only one of the extra sizes is ever non zero.
+    GrowBin {
+        #[allow(missing_docs)]
+        bin_id: BinId,
+        #[allow(missing_docs)]
+        extra_width: u32,
+        #[allow(missing_docs)]
+        extra_height: u32,
+        #[allow(missing_docs)]
+        extra_depth: u32,
+    },
+    /// Remove a placed rectangle from its bin, freeing up the space that it occupied.
+    EvictRect {
+        #[allow(missing_docs)]
+        rect_id: RectToPlaceId,
+        #[allow(missing_docs)]
+        bin_id: BinId,
+    },
+}
+
+impl<RectToPlaceId, BinId> GapSuggestion<RectToPlaceId, BinId> {
+    /// The suggested change.
+    pub fn change(&self) -> &GapChange<RectToPlaceId, BinId> {
+        &self.change
+    }
+
+    /// How many more of the unplaced rectangles could be placed after making the change.
+    ///
+    /// When evicting a rectangle this does not subtract the evicted rectangle itself.
+    pub fn additional_placements(&self) -> usize {
+        self.additional_placements
+    }
+}
+
+/// Inspect the remaining free sections of the bins and suggest single changes that would allow
+/// more of the `unplaced` rectangles to be placed.
+///
+/// The suggestions are sorted so that the change that allows the most additional placements comes
+/// first. Ties keep the order rotations, then bin growth (by bin id, then x, y and z axis, then
+/// smallest growth first), then evictions (by rect id), so that the least disruptive changes are
+/// suggested first. Changes that would not allow any additional placements are left out.
+///
+/// Bins are only grown by amounts that match one of the unplaced rectangles' sizes along that
+/// axis, rounded up to whole blocks for bins with a [`TargetBin.with_block_size`].
+///
+/// Each change is evaluated by placing the unplaced rectangles, largest first, into the free
+/// sections that the bins would have after the change. Groups are not taken into account.
+///
+/// The space freed by evicting a rectangle is merged with its neighboring free sections, as with
+/// [`TargetBin.coalesce_all_available_sections`].
+///
+/// [`TargetBin.with_block_size`]: crate::TargetBin::with_block_size
+/// [`TargetBin.coalesce_all_available_sections`]: crate::TargetBin::coalesce_all_available_sections
+///
+/// ## Example
+///
+/// ```
+/// use rectangle_pack::{
+///     analyze_gaps, contains_smallest_box, pack_rects, volume_heuristic, GapChange,
+///     GroupedRectsToPlace, RectToInsert, TargetBin,
+/// };
+/// use std::collections::BTreeMap;
+///
+/// let mut placed: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+/// placed.push_rect("wide", None, RectToInsert::new(10, 6, 1));
+///
+/// let mut all_rects: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+/// all_rects.push_rect("wide", None, RectToInsert::new(10, 6, 1));
+/// all_rects.push_rect("tall", None, RectToInsert::new(4, 10, 1));
+///
+/// let mut target_bins = BTreeMap::new();
+/// target_bins.insert("bin", TargetBin::new(10, 10, 1));
+///
+/// let packed = pack_rects(
+///     &placed,
+///     &mut target_bins,
+///     &volume_heuristic,
+///     &contains_smallest_box,
+/// ).unwrap();
+///
+/// let suggestions = analyze_gaps(
+///     &["tall"],
+///     &all_rects,
+///     &target_bins,
+///     &packed,
+///     &volume_heuristic,
+///     &contains_smallest_box,
+/// );
+///
+/// assert_eq!(suggestions[0].change(), &GapChange::RotateRect { rect_id: "tall" });
+/// ```
+pub fn analyze_gaps<RectToPlaceId, BinId, GroupId, Heuristic, Compare>(
+    unplaced: &[RectToPlaceId],
+    rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    target_bins: &BTreeMap<BinId, TargetBin>,
+    packed: &RectanglePackOk<RectToPlaceId, BinId>,
+    box_size_heuristic: &Heuristic,
+    more_suitable_containers_fn: &Compare,
+) -> Vec<GapSuggestion<RectToPlaceId, BinId>>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+{
+    let options = PackOptions::default();
+    let ctx = PackContext::new(box_size_heuristic, more_suitable_containers_fn, &options);
+
+    let mut bins: Vec<(&BinId, &TargetBin)> = target_bins.iter().collect();
+    bins.sort_by_key(|(_, bin)| box_size_heuristic.box_size(bin.dimensions()));
+    let bins_as_packed = || -> Vec<TargetBin> {
+        bins.iter()
+            .map(|(_, bin)| bin.clone_without_metrics())
+            .collect()
+    };
+
+    let unplaced_rects: Vec<RectToInsert> = unplaced
+        .iter()
+        .map(|rect_id| rects_to_place.rects[rect_id])
+        .collect();
+
+    let baseline = count_placeable(bins_as_packed(), unplaced_rects.clone(), &ctx);
+
+    let mut suggestions = vec![];
+    let mut suggest = |change, placeable: usize| {
+        if placeable > baseline {
+            suggestions.push(GapSuggestion {
+                change,
+                additional_placements: placeable - baseline,
+            });
+        }
+    };
+
+    for (idx, rect_id) in unplaced.iter().enumerate() {
+        let rect = unplaced_rects[idx];
+        if rect.width() == rect.height() {
+            continue;
+        }
+
+        let mut rects = unplaced_rects.clone();
+        rects[idx].whd = WidthHeightDepth {
+            width: rect.height(),
+            height: rect.width(),
+            depth: rect.depth(),
+        };
+
+        suggest(
+            GapChange::RotateRect {
+                rect_id: rect_id.clone(),
+            },
+            count_placeable(bins_as_packed(), rects, &ctx),
+        );
+    }
+
+    let mut bin_indices: Vec<usize> = (0..bins.len()).collect();
+    bin_indices.sort_by_key(|idx| bins[*idx].0);
+
+    for bin_idx in bin_indices {
+        let block_size = bins[bin_idx].1.block_size;
+        let block_size = [block_size.width, block_size.height, block_size.depth];
+
+        for axis in 0..3 {
+            let mut blocks: Vec<u32> = unplaced_rects
+                .iter()
+                .map(|rect| {
+                    let blocks = rect.whd.blocks_covering(bins[bin_idx].1.block_size);
+                    [blocks.width, blocks.height, blocks.depth][axis]
+                })
+                .collect();
+            blocks.sort_unstable();
+            blocks.dedup();
+
+            for amount in blocks {
+                let mut extra_blocks = [0; 3];
+                extra_blocks[axis] = amount;
+
+                let mut extra = [0; 3];
+                extra[axis] = amount * block_size[axis];
+
+                let mut grown = bins_as_packed();
+                grow_bin(&mut grown[bin_idx], extra_blocks);
+
+                suggest(
+                    GapChange::GrowBin {
+                        bin_id: bins[bin_idx].0.clone(),
+                        extra_width: extra[0],
+                        extra_height: extra[1],
+                        extra_depth: extra[2],
+                    },
+                    count_placeable(grown, unplaced_rects.clone(), &ctx),
+                );
+            }
+        }
+    }
+
+    let mut placed: Vec<_> = packed.packed_locations.iter().collect();
+    placed.sort_by(|a, b| a.0.cmp(b.0));
+
+    for (rect_id, (bin_id, location)) in placed {
+        let bin_idx = match bins.iter().position(|(id, _)| *id == bin_id) {
+            Some(bin_idx) => bin_idx,
+            None => continue,
+        };
+
+        let mut evicted = bins_as_packed();
+        let freed = evicted[bin_idx].section_occupied_by(location);
+        evicted[bin_idx].push_available_bin_section_unchecked(freed);
+        evicted[bin_idx].coalesce_all_available_sections();
+
+        suggest(
+            GapChange::EvictRect {
+                rect_id: rect_id.clone(),
+                bin_id: bin_id.clone(),
+            },
+            count_placeable(evicted, unplaced_rects.clone(), &ctx),
+        );
+    }
+
+    suggestions.sort_by_key(|suggestion| core::cmp::Reverse(suggestion.additional_placements));
+
+    suggestions
+}
+
+/// Place as many of the rectangles as possible, largest first, returning how many were placed.
+fn count_placeable<Heuristic, Compare>(
+    mut bins: Vec<TargetBin>,
+    mut rects: Vec<RectToInsert>,
+    ctx: &PackContext<Heuristic, Compare>,
+) -> usize
+where
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+{
+    rects.sort_by_key(|rect| core::cmp::Reverse(ctx.box_size_heuristic.box_size(rect.whd)));
+
+    rects
+        .iter()
+        .filter(|rect| bins.iter_mut().any(|bin| bin.place(rect, ctx).is_some()))
+        .count()
+}
+
+/// Grow the bin along a single axis by the given number of blocks, adding the new space as a free
+/// section.
+fn grow_bin(bin: &mut TargetBin, [extra_width, extra_height, extra_depth]: [u32; 3]) {
+    bin.grow_to_blocks(WidthHeightDepth {
+        width: bin.max_width + extra_width,
+        height: bin.max_height + extra_height,
+        depth: bin.max_depth + extra_depth,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{contains_smallest_box, pack_rects, volume_heuristic};
+
+    /// Verify that rotating the unplaced rectangle is suggested before the more disruptive changes
+    /// that would also allow it to be placed.
+    #[test]
+    fn suggests_rotation_first() {
+        let (all_rects, target_bins, packed) = pack_wide_rect();
+
+        let suggestions = analyze_gaps(
+            &[RectId::Tall],
+            &all_rects,
+            &target_bins,
+            &packed,
+            &volume_heuristic,
+            &contains_smallest_box,
+        );
+
+        assert_eq!(
+            suggestions[0].change(),
+            &GapChange::RotateRect {
+                rect_id: RectId::Tall
+            }
+        );
+        assert_eq!(suggestions[0].additional_placements(), 1);
+
+        assert!(suggestions.contains(&GapSuggestion {
+            change: GapChange::EvictRect {
+                rect_id: RectId::Wide,
+                bin_id: "bin"
+            },
+            additional_placements: 1
+        }));
+        assert!(suggestions.contains(&GapSuggestion {
+            change: GapChange::GrowBin {
+                bin_id: "bin",
+                extra_width: 4,
+                extra_height: 0,
+                extra_depth: 0
+            },
+            additional_placements: 1
+        }));
+    }
+
+    /// Verify that changes that don't allow any more placements are not suggested.
+    #[test]
+    fn unhelpful_changes_not_suggested() {
+        let (all_rects, target_bins, packed) = pack_wide_rect();
+
+        let suggestions = analyze_gaps(
+            &[RectId::Tall],
+            &all_rects,
+            &target_bins,
+            &packed,
+            &volume_heuristic,
+            &contains_smallest_box,
+        );
+
+        // Growing the bin's height by the tall rectangle's width leaves too short of a gap.
+        assert!(!suggestions.iter().any(|suggestion| suggestion.change
+            == GapChange::GrowBin {
+                bin_id: "bin",
+                extra_width: 0,
+                extra_height: 4,
+                extra_depth: 0
+            }));
+    }
+
+    /// A 10x10 bin with a 10x6 rectangle in it, along with a 4x10 rectangle that doesn't fit.
+    fn pack_wide_rect() -> PackedWideRect {
+        let mut placed = GroupedRectsToPlace::new();
+        placed.push_rect(RectId::Wide, None, RectToInsert::new(10, 6, 1));
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert("bin", TargetBin::new(10, 10, 1));
+
+        let packed = pack_rects(
+            &placed,
+            &mut target_bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+
+        let mut all_rects = placed;
+        all_rects.push_rect(RectId::Tall, None, RectToInsert::new(4, 10, 1));
+
+        (all_rects, target_bins, packed)
+    }
+
+    type PackedWideRect = (
+        GroupedRectsToPlace<RectId, ()>,
+        BTreeMap<&'static str, TargetBin>,
+        RectanglePackOk<RectId, &'static str>,
+    );
+
+    #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Ord, PartialOrd)]
+    enum RectId {
+        Wide,
+        Tall,
+    }
+}
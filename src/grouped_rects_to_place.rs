@@ -1,11 +1,7 @@
-use crate::RectToInsert;
-
-#[cfg(not(std))]
-use alloc::collections::BTreeMap as KeyValMap;
-#[cfg(std)]
-use std::collections::HashMap as KeyValMap;
+use crate::{KeyValMap, RectToInsert};
 
 use alloc::{
+    borrow::Cow,
     collections::{btree_map::Entry, BTreeMap},
     vec::Vec,
 };
@@ -18,7 +14,7 @@ use core::{fmt::Debug, hash::Hash};
 ///
 /// A group's heuristic is computed by calculating the heuristic of all of the rectangles inside
 /// the group and then summing them.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct GroupedRectsToPlace<RectToPlaceId, GroupId = ()>
 where
     RectToPlaceId: Debug + Hash + Eq + Ord + PartialOrd,
@@ -30,10 +26,20 @@ where
         KeyValMap<RectToPlaceId, Vec<Group<GroupId, RectToPlaceId>>>,
     pub(crate) group_id_to_inbound_ids: BTreeMap<Group<GroupId, RectToPlaceId>, Vec<RectToPlaceId>>,
     pub(crate) rects: KeyValMap<RectToPlaceId, RectToInsert>,
+    pub(crate) distinct_bin_sets: Vec<Vec<RectToPlaceId>>,
+    pub(crate) inbound_id_to_distinct_bin_sets: KeyValMap<RectToPlaceId, Vec<usize>>,
 }
 
+/// A group along with the ids of its rectangles, after merging the groups that share rectangles.
+///
+/// See [`GroupedRectsToPlace.merged_groups`].
+///
+/// [`GroupedRectsToPlace.merged_groups`]: GroupedRectsToPlace::merged_groups
+pub(crate) type MergedGroup<'a, GroupId, RectToPlaceId> =
+    (&'a Group<GroupId, RectToPlaceId>, Cow<'a, [RectToPlaceId]>);
+
 /// A group of rectangles that need to be placed together
-#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 pub enum Group<GroupId, RectToPlaceId>
 where
     GroupId: Debug + Hash + Eq + PartialEq + Ord + PartialOrd,
@@ -62,11 +68,19 @@ where
             inbound_id_to_group_ids: Default::default(),
             group_id_to_inbound_ids: Default::default(),
             rects: Default::default(),
+            distinct_bin_sets: Default::default(),
+            inbound_id_to_distinct_bin_sets: Default::default(),
         }
     }
 
     /// Push one or more rectangles
     ///
+    /// A rectangle that is pushed with more than one group id is only placed once, and ties its
+    /// groups together so that all of their rectangles end up in the same bin.
+    ///
+    /// Pushing a rectangle id that was already pushed replaces the earlier rectangle, along with
+    /// the groups that it was pushed with.
+    ///
     /// # Panics
     ///
     /// Panics if a `Some(Vec<GroupId>)` passed in but the length is 0, as this is likely a
@@ -77,8 +91,19 @@ where
         group_ids: Option<Vec<GroupId>>,
         inbound: RectToInsert,
     ) {
+        self.remove_from_groups(&inbound_id);
         self.rects.insert(inbound_id.clone(), inbound);
 
+        let group_ids = group_ids.map(|group_ids| {
+            let mut unique = Vec::with_capacity(group_ids.len());
+            for group_id in group_ids {
+                if !unique.contains(&group_id) {
+                    unique.push(group_id);
+                }
+            }
+            unique
+        });
+
         match group_ids {
             None => {
                 self.group_id_to_inbound_ids.insert(
@@ -112,6 +137,364 @@ where
             }
         };
     }
+
+    /// Remove a previously pushed rectangle from all of the groups that it was pushed with.
+    fn remove_from_groups(&mut self, inbound_id: &RectToPlaceId) {
+        let groups = match self.inbound_id_to_group_ids.remove(inbound_id) {
+            Some(groups) => groups,
+            None => return,
+        };
+
+        for group in groups {
+            if let Entry::Occupied(mut o) = self.group_id_to_inbound_ids.entry(group) {
+                o.get_mut().retain(|member| member != inbound_id);
+
+                if o.get().is_empty() {
+                    o.remove();
+                }
+            }
+        }
+    }
+
+    /// Fill `merged` with every group, with the groups that share a rectangle merged into the
+    /// first of them, in order.
+    ///
+    /// Every rectangle appears in exactly one of the merged groups.
+    pub(crate) fn merged_groups_into<'a>(
+        &'a self,
+        merged: &mut Vec<MergedGroup<'a, GroupId, RectToPlaceId>>,
+    ) {
+        merged.clear();
+
+        let shares_rects = self
+            .inbound_id_to_group_ids
+            .values()
+            .any(|groups| groups.len() > 1);
+        if !shares_rects {
+            merged.extend(
+                self.group_id_to_inbound_ids
+                    .iter()
+                    .map(|(group, members)| (group, Cow::Borrowed(&members[..]))),
+            );
+            return;
+        }
+
+        let groups: Vec<&Group<GroupId, RectToPlaceId>> =
+            self.group_id_to_inbound_ids.keys().collect();
+        let group_idx =
+            |group: &Group<GroupId, RectToPlaceId>| groups.binary_search(&group).unwrap();
+
+        // The index of the group that each group was merged into, following the chain until a
+        // group that points at itself.
+        let mut merged_into: Vec<usize> = (0..groups.len()).collect();
+        let root = |merged_into: &[usize], mut idx: usize| {
+            while merged_into[idx] != idx {
+                idx = merged_into[idx];
+            }
+            idx
+        };
+        for shared in self.inbound_id_to_group_ids.values() {
+            for pair in shared.windows(2) {
+                let a = root(&merged_into, group_idx(&pair[0]));
+                let b = root(&merged_into, group_idx(&pair[1]));
+                merged_into[a.max(b)] = a.min(b);
+            }
+        }
+
+        let mut merged_idx_of_root = vec![None; groups.len()];
+        for (idx, members) in self.group_id_to_inbound_ids.values().enumerate() {
+            let root = root(&merged_into, idx);
+
+            match merged_idx_of_root[root] {
+                None if root == idx && !self.has_shared_member(members) => {
+                    merged.push((groups[idx], Cow::Borrowed(&members[..])));
+                }
+                None => {
+                    merged_idx_of_root[root] = Some(merged.len());
+                    merged.push((groups[root], Cow::Owned(vec![])));
+                }
+                Some(_) => {}
+            }
+
+            if let Some(merged_idx) = merged_idx_of_root[root] {
+                let merged_members = merged[merged_idx].1.to_mut();
+                for member in members.iter() {
+                    if !merged_members.contains(member) {
+                        merged_members.push(member.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    fn has_shared_member(&self, members: &[RectToPlaceId]) -> bool {
+        members
+            .iter()
+            .any(|member| self.inbound_id_to_group_ids[member].len() > 1)
+    }
+
+    /// Require every one of the rectangles to be placed into a different bin, such as for
+    /// redundant copies of a texture that are spread across pages so that losing one page while
+    /// streaming doesn't lose the texture.
+    ///
+    /// This is the opposite of a group, whose rectangles must all be placed into the same bin.
+    ///
+    /// Returns the index of the set, which is used to name the set in
+    /// [`RectanglePackError::DistinctBinSetUnsatisfiable`] if its rectangles can't be spread
+    /// across the bins.
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace,
+    ///     RectToInsert, RectanglePackError, TargetBin,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    /// rects_to_place.push_rect("copy-a", None, RectToInsert::new(4, 4, 1));
+    /// rects_to_place.push_rect("copy-b", None, RectToInsert::new(4, 4, 1));
+    /// let copies = rects_to_place.push_distinct_bin_set(vec!["copy-a", "copy-b"]);
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert(0, TargetBin::new(16, 16, 1));
+    ///
+    /// let packed = pack_rects(
+    ///     &rects_to_place,
+    ///     &mut target_bins.clone(),
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    /// );
+    /// assert_eq!(
+    ///     packed,
+    ///     Err(RectanglePackError::DistinctBinSetUnsatisfiable {
+    ///         distinct_bin_set: copies
+    ///     })
+    /// );
+    ///
+    /// target_bins.insert(1, TargetBin::new(16, 16, 1));
+    /// let packed = pack_rects(
+    ///     &rects_to_place,
+    ///     &mut target_bins,
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_ne!(
+    ///     packed.packed_locations()["copy-a"].0,
+    ///     packed.packed_locations()["copy-b"].0
+    /// );
+    /// ```
+    ///
+    /// [`RectanglePackError::DistinctBinSetUnsatisfiable`]: crate::RectanglePackError::DistinctBinSetUnsatisfiable
+    pub fn push_distinct_bin_set(&mut self, inbound_ids: Vec<RectToPlaceId>) -> usize {
+        let distinct_bin_set = self.distinct_bin_sets.len();
+
+        for inbound_id in inbound_ids.iter() {
+            self.inbound_id_to_distinct_bin_sets
+                .entry(inbound_id.clone())
+                .or_default()
+                .push(distinct_bin_set);
+        }
+        self.distinct_bin_sets.push(inbound_ids);
+
+        distinct_bin_set
+    }
+
+    /// The sets of rectangles that must each be placed into a different bin, indexed by the
+    /// values returned from [`GroupedRectsToPlace.push_distinct_bin_set`].
+    ///
+    /// [`GroupedRectsToPlace.push_distinct_bin_set`]: GroupedRectsToPlace::push_distinct_bin_set
+    pub fn distinct_bin_sets(&self) -> &[Vec<RectToPlaceId>] {
+        &self.distinct_bin_sets
+    }
+
+    /// The ids of the groups that any of the given rectangles was pushed with, in ascending
+    /// order and without duplicates, such as for finding the groups that
+    /// [`pack_rects_partial`] left out.
+    ///
+    /// Rectangles that were pushed without any groups, or never pushed, have no group ids.
+    ///
+    /// [`pack_rects_partial`]: crate::pack_rects_partial
+    pub fn group_ids_of(&self, inbound_ids: &[RectToPlaceId]) -> Vec<&GroupId> {
+        let mut group_ids: Vec<&GroupId> = inbound_ids
+            .iter()
+            .filter_map(|inbound_id| self.inbound_id_to_group_ids.get(inbound_id))
+            .flatten()
+            .filter_map(|group| match group {
+                Group::Grouped(group_id) => Some(group_id),
+                Group::Ungrouped(_) => None,
+            })
+            .collect();
+        group_ids.sort();
+        group_ids.dedup();
+
+        group_ids
+    }
+
+    /// The number of rectangles that were pushed, counting a rectangle that was pushed again
+    /// with the same id once.
+    pub fn len(&self) -> usize {
+        self.rects.len()
+    }
+
+    /// Whether no rectangles were pushed.
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    /// Whether a rectangle with the given id was pushed.
+    pub fn contains(&self, inbound_id: &RectToPlaceId) -> bool {
+        self.rects.contains_key(inbound_id)
+    }
+
+    /// The rectangle that was pushed with the given id.
+    pub fn get(&self, inbound_id: &RectToPlaceId) -> Option<&RectToInsert> {
+        self.rects.get(inbound_id)
+    }
+
+    /// Every rectangle along with its id, in an unspecified order.
+    pub fn rects(&self) -> impl Iterator<Item = (&RectToPlaceId, &RectToInsert)> + '_ {
+        self.rects.iter()
+    }
+
+    /// The combined volume of every rectangle, such as for checking whether they could possibly
+    /// fit into the free space of the bins (see [`TargetBin.free_volume`]).
+    ///
+    /// Padding is not included, since it depends on the [`PackOptions`] that get used.
+    ///
+    /// ```
+    /// use rectangle_pack::{GroupedRectsToPlace, RectToInsert};
+    ///
+    /// let mut rects_to_place = GroupedRectsToPlace::new();
+    /// rects_to_place.push_rect("a", Some(vec!["terrain"]), RectToInsert::new(4, 4, 1));
+    /// rects_to_place.push_rect("b", Some(vec!["terrain"]), RectToInsert::new(2, 2, 1));
+    /// rects_to_place.push_rect("c", None, RectToInsert::new(8, 8, 1));
+    ///
+    /// assert_eq!(rects_to_place.total_volume(), 16 + 4 + 64);
+    /// assert_eq!(rects_to_place.total_volume_of_group(&"terrain"), 16 + 4);
+    /// ```
+    ///
+    /// [`TargetBin.free_volume`]: crate::TargetBin::free_volume
+    /// [`PackOptions`]: crate::PackOptions
+    pub fn total_volume(&self) -> u128 {
+        self.rects.values().map(|rect| rect.whd.volume()).sum()
+    }
+
+    /// The combined volume of the rectangles that were pushed with the given group id, or `0` if
+    /// there aren't any.
+    ///
+    /// See [`GroupedRectsToPlace.total_volume`].
+    ///
+    /// [`GroupedRectsToPlace.total_volume`]: GroupedRectsToPlace::total_volume
+    pub fn total_volume_of_group(&self, group_id: &GroupId) -> u128 {
+        match self
+            .group_id_to_inbound_ids
+            .get(&Group::Grouped(group_id.clone()))
+        {
+            Some(members) => members
+                .iter()
+                .map(|member| self.rects[member].whd.volume())
+                .sum(),
+            None => 0,
+        }
+    }
+
+    /// Every group id along with the ids of the rectangles that were pushed with it, in ascending
+    /// order of group id.
+    ///
+    /// Rectangles that were pushed without any groups aren't part of any of them.
+    pub fn groups(&self) -> impl Iterator<Item = (&GroupId, &[RectToPlaceId])> + '_ {
+        self.group_id_to_inbound_ids
+            .iter()
+            .filter_map(|(group, members)| match group {
+                Group::Grouped(group_id) => Some((group_id, &members[..])),
+                Group::Ungrouped(_) => None,
+            })
+    }
+
+    /// The indices of the distinct bin sets that the rectangle belongs to.
+    pub(crate) fn distinct_bin_sets_of(&self, inbound_id: &RectToPlaceId) -> &[usize] {
+        match self.inbound_id_to_distinct_bin_sets.get(inbound_id) {
+            Some(distinct_bin_sets) => distinct_bin_sets,
+            None => &[],
+        }
+    }
+
+    /// The distinct bin set that both of the (different) rectangles belong to, if any.
+    pub(crate) fn shared_distinct_bin_set(
+        &self,
+        a: &RectToPlaceId,
+        b: &RectToPlaceId,
+    ) -> Option<usize> {
+        if a == b {
+            return None;
+        }
+
+        let b_sets = self.distinct_bin_sets_of(b);
+        self.distinct_bin_sets_of(a)
+            .iter()
+            .find(|distinct_bin_set| b_sets.contains(distinct_bin_set))
+            .copied()
+    }
+}
+
+impl<IdBase, GroupId> GroupedRectsToPlace<(IdBase, usize), GroupId>
+where
+    IdBase: Debug + Hash + Clone + Eq + Ord + PartialOrd,
+    GroupId: Debug + Hash + Clone + Eq + Ord + PartialOrd,
+{
+    /// Push `count` copies of the same rectangle, with the ids `(id_base, 0)` through
+    /// `(id_base, count - 1)`, such as for hundreds of identical tiles.
+    ///
+    /// Every copy is pushed with the same group ids, so copies that are given groups all end up
+    /// in the same bin.
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace, RectToInsert,
+    ///     TargetBin,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    /// rects_to_place.push_rect_n("tile", 16, None, RectToInsert::new(8, 8, 1));
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert(0, TargetBin::new(32, 32, 1));
+    ///
+    /// let packed = pack_rects(
+    ///     &rects_to_place,
+    ///     &mut target_bins,
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(packed.packed_locations().len(), 16);
+    /// assert!(packed.packed_locations().contains_key(&("tile", 15)));
+    /// ```
+    pub fn push_rect_n(
+        &mut self,
+        id_base: IdBase,
+        count: usize,
+        group_ids: Option<Vec<GroupId>>,
+        inbound: RectToInsert,
+    ) {
+        for copy in 0..count {
+            self.push_rect((id_base.clone(), copy), group_ids.clone(), inbound);
+        }
+    }
+}
+
+impl<RectToPlaceId, GroupId> Default for GroupedRectsToPlace<RectToPlaceId, GroupId>
+where
+    RectToPlaceId: Debug + Hash + Clone + Eq + Ord + PartialOrd,
+    GroupId: Debug + Hash + Clone + Eq + Ord + PartialOrd,
+{
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[cfg(test)]
@@ -119,6 +502,45 @@ mod tests {
     use super::*;
     use crate::RectToInsert;
 
+    /// Verify that pushed rectangles can be counted, looked up and iterated over, and that groups
+    /// only include the user provided group ids.
+    #[test]
+    fn query_pushed_rects() {
+        let mut lrg = GroupedRectsToPlace::new();
+        assert!(lrg.is_empty());
+
+        lrg.push_rect(
+            RectToPlaceId::One,
+            Some(vec![0]),
+            RectToInsert::new(1, 1, 1),
+        );
+        lrg.push_rect(
+            RectToPlaceId::Two,
+            Some(vec![0]),
+            RectToInsert::new(2, 2, 1),
+        );
+        lrg.push_rect(RectToPlaceId::Three, None, RectToInsert::new(3, 3, 1));
+        lrg.push_rect(RectToPlaceId::Three, None, RectToInsert::new(4, 4, 1));
+
+        assert_eq!(lrg.len(), 3);
+        assert!(lrg.contains(&RectToPlaceId::Two));
+        assert_eq!(lrg.get(&RectToPlaceId::Three).unwrap().width(), 4);
+        assert!(lrg.get(&RectToPlaceId::Four).is_none());
+
+        let mut ids: Vec<_> = lrg.rects().map(|(id, _)| *id).collect();
+        ids.sort();
+        assert_eq!(
+            ids,
+            vec![RectToPlaceId::One, RectToPlaceId::Two, RectToPlaceId::Three]
+        );
+
+        let groups: Vec<_> = lrg.groups().collect();
+        assert_eq!(
+            groups,
+            vec![(&0, &[RectToPlaceId::One, RectToPlaceId::Two][..])]
+        );
+    }
+
     /// Verify that if we insert a rectangle that doesn't have a group it is given a group ID based
     /// on its RectToPlaceId.
     #[test]
@@ -194,9 +616,130 @@ mod tests {
         assert_eq!(lrg.rects[&RectToPlaceId::One], RectToInsert::new(10, 10, 1));
     }
 
+    /// Verify that every copy is pushed with its own id and the same groups.
+    #[test]
+    fn push_rect_n_pushes_every_copy() {
+        let mut lrg = GroupedRectsToPlace::new();
+
+        lrg.push_rect_n(
+            RectToPlaceId::One,
+            3,
+            Some(vec![0]),
+            RectToInsert::new(2, 2, 1),
+        );
+
+        assert_eq!(lrg.rects.len(), 3);
+        assert_eq!(
+            lrg.group_id_to_inbound_ids[&Group::Grouped(0)],
+            vec![
+                (RectToPlaceId::One, 0),
+                (RectToPlaceId::One, 1),
+                (RectToPlaceId::One, 2)
+            ]
+        );
+    }
+
+    /// Verify that pushing a rectangle id again replaces the rectangle and its groups.
+    #[test]
+    fn pushing_the_same_id_again_replaces_it() {
+        let mut lrg = GroupedRectsToPlace::new();
+
+        lrg.push_rect(
+            RectToPlaceId::One,
+            Some(vec![0, 1]),
+            RectToInsert::new(10, 10, 1),
+        );
+        lrg.push_rect(
+            RectToPlaceId::Two,
+            Some(vec![1]),
+            RectToInsert::new(5, 5, 1),
+        );
+        lrg.push_rect(
+            RectToPlaceId::One,
+            Some(vec![2]),
+            RectToInsert::new(3, 3, 1),
+        );
+
+        assert_eq!(lrg.rects[&RectToPlaceId::One], RectToInsert::new(3, 3, 1));
+        assert!(!lrg.group_id_to_inbound_ids.contains_key(&Group::Grouped(0)));
+        assert_eq!(
+            lrg.group_id_to_inbound_ids[&Group::Grouped(1)],
+            vec![RectToPlaceId::Two]
+        );
+        assert_eq!(
+            lrg.group_id_to_inbound_ids[&Group::Grouped(2)],
+            vec![RectToPlaceId::One]
+        );
+    }
+
+    /// Verify that groups which share a rectangle are merged into one group that contains every
+    /// rectangle once, while the other groups are left alone.
+    #[test]
+    fn groups_that_share_a_rect_are_merged() {
+        let mut lrg = GroupedRectsToPlace::new();
+
+        lrg.push_rect(
+            RectToPlaceId::One,
+            Some(vec![0]),
+            RectToInsert::new(1, 1, 1),
+        );
+        lrg.push_rect(
+            RectToPlaceId::Two,
+            Some(vec![1]),
+            RectToInsert::new(1, 1, 1),
+        );
+        lrg.push_rect(
+            RectToPlaceId::Three,
+            Some(vec![2, 0]),
+            RectToInsert::new(1, 1, 1),
+        );
+
+        let mut merged_groups = vec![];
+        lrg.merged_groups_into(&mut merged_groups);
+        let merged: Vec<(&Group<_, _>, Vec<RectToPlaceId>)> = merged_groups
+            .into_iter()
+            .map(|(group, members)| (group, members.into_owned()))
+            .collect();
+
+        assert_eq!(
+            merged,
+            vec![
+                (
+                    &Group::Grouped(0),
+                    vec![RectToPlaceId::One, RectToPlaceId::Three]
+                ),
+                (&Group::Grouped(1), vec![RectToPlaceId::Two]),
+            ]
+        );
+    }
+
+    /// Verify that we can look up which distinct bin set two rectangles share.
+    #[test]
+    fn shared_distinct_bin_set() {
+        let mut lrg: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+
+        lrg.push_distinct_bin_set(vec![RectToPlaceId::One, RectToPlaceId::Two]);
+        let second = lrg.push_distinct_bin_set(vec![RectToPlaceId::Two, RectToPlaceId::Three]);
+
+        assert_eq!(
+            lrg.shared_distinct_bin_set(&RectToPlaceId::Three, &RectToPlaceId::Two),
+            Some(second)
+        );
+        assert_eq!(
+            lrg.shared_distinct_bin_set(&RectToPlaceId::One, &RectToPlaceId::Three),
+            None
+        );
+        assert_eq!(
+            lrg.shared_distinct_bin_set(&RectToPlaceId::One, &RectToPlaceId::One),
+            None
+        );
+    }
+
     #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
     enum RectToPlaceId {
         One,
         Two,
+        Three,
+        Four,
     }
 }
@@ -0,0 +1,146 @@
+//! Packing into bins that aren't stored in a `BTreeMap`.
+
+use crate::{
+    pack_rects_with_options, BoxSizeHeuristic, ComparePotentialContainers, GroupedRectsToPlace,
+    PackOptions, RectanglePackError, RectanglePackOk, TargetBin,
+};
+
+use alloc::{collections::BTreeMap, format, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+/// Pack rectangles the same way as [`pack_rects_with_options`], into bins that can come from
+/// anywhere instead of only from a `BTreeMap`, such as a `Vec`, a slice or a `HashMap`.
+///
+/// The bins are moved into a `BTreeMap` while packing and moved back afterwards, so they are
+/// never cloned. How the bins were given doesn't change which bin a rectangle goes into, since
+/// bins are always tried in the same order (see [`pack_rects`]).
+///
+/// ```
+/// use rectangle_pack::{
+///     contains_smallest_box, pack_rects_into, volume_heuristic, GroupedRectsToPlace,
+///     PackOptions, RectToInsert, TargetBin,
+/// };
+///
+/// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+/// rects_to_place.push_rect("grass", None, RectToInsert::new(10, 10, 1));
+///
+/// let mut pages = vec![TargetBin::new(8, 8, 1), TargetBin::new(16, 16, 1)];
+///
+/// let packed = pack_rects_into(
+///     &rects_to_place,
+///     pages.iter_mut().enumerate(),
+///     &volume_heuristic,
+///     &contains_smallest_box,
+///     &PackOptions::default(),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(packed.packed_locations()["grass"].0, 1);
+/// assert_eq!(pages[1].placed_rect_count(), 1);
+/// ```
+///
+/// Returns [`RectanglePackError::DuplicateBinId`] without packing anything if two of the bins
+/// have the same id.
+///
+/// [`pack_rects`]: crate::pack_rects
+/// [`pack_rects_with_options`]: crate::pack_rects_with_options
+pub fn pack_rects_into<'b, RectToPlaceId, BinId, GroupId, Heuristic, Compare>(
+    rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    target_bins: impl IntoIterator<Item = (BinId, &'b mut TargetBin)>,
+    box_size_heuristic: &Heuristic,
+    more_suitable_containers_fn: &Compare,
+    options: &PackOptions,
+) -> Result<RectanglePackOk<RectToPlaceId, BinId>, RectanglePackError>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+{
+    let mut borrowed: Vec<(BinId, &'b mut TargetBin)> = target_bins.into_iter().collect();
+
+    // Checked up front so that no bin has been moved out yet when returning the error.
+    let mut bin_ids: Vec<&BinId> = borrowed.iter().map(|(bin_id, _)| bin_id).collect();
+    bin_ids.sort();
+    if let Some(duplicate) = bin_ids.windows(2).find(|pair| pair[0] == pair[1]) {
+        return Err(RectanglePackError::DuplicateBinId {
+            bin_id: format!("{:?}", duplicate[0]),
+        });
+    }
+
+    let mut owned = BTreeMap::new();
+    for (bin_id, bin) in borrowed.iter_mut() {
+        owned.insert(
+            bin_id.clone(),
+            core::mem::replace(*bin, TargetBin::new(0, 0, 0)),
+        );
+    }
+
+    let packed = pack_rects_with_options(
+        rects_to_place,
+        &mut owned,
+        box_size_heuristic,
+        more_suitable_containers_fn,
+        options,
+    );
+
+    for (bin_id, bin) in borrowed {
+        *bin = owned.remove(&bin_id).unwrap();
+    }
+
+    packed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{contains_smallest_box, volume_heuristic, RectToInsert};
+
+    /// Verify that every bin is moved back where it came from, including when packing fails.
+    #[test]
+    fn bins_are_moved_back() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect("a", None, RectToInsert::new(5, 5, 1));
+        rects_to_place.push_rect("b", None, RectToInsert::new(20, 20, 1));
+
+        let mut bins = [TargetBin::new(10, 10, 1), TargetBin::new(5, 5, 1)];
+
+        let packed = pack_rects_into(
+            &rects_to_place,
+            bins.iter_mut().enumerate(),
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::default(),
+        );
+
+        assert!(packed.is_err());
+        assert_eq!(bins[0].dimensions().width, 10);
+        assert_eq!(bins[1].dimensions().width, 5);
+    }
+
+    /// Verify that bins with the same id are reported without packing into any of them.
+    #[test]
+    fn error_if_bin_ids_repeat() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect("a", None, RectToInsert::new(5, 5, 1));
+
+        let mut bins = [TargetBin::new(10, 10, 1), TargetBin::new(10, 10, 1)];
+
+        let packed = pack_rects_into(
+            &rects_to_place,
+            bins.iter_mut().map(|bin| ("page", bin)),
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::default(),
+        );
+
+        assert_eq!(
+            packed.unwrap_err(),
+            RectanglePackError::DuplicateBinId {
+                bin_id: "\"page\"".into()
+            }
+        );
+        assert!(bins.iter().all(|bin| bin.placed_rect_count() == 0));
+    }
+}
@@ -1,41 +1,112 @@
 //! `rectangle-pack` is a library focused on laying out any number of smaller rectangles
 //! (both 2d rectangles and 3d rectangular prisms) inside any number of larger rectangles.
-#![cfg_attr(not(std), no_std)]
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(missing_docs)]
 
 #[macro_use]
 extern crate alloc;
 
-#[cfg(not(std))]
+use alloc::collections::btree_map::IntoIter as KeyValMapIntoIter;
 use alloc::collections::BTreeMap as KeyValMap;
-#[cfg(std)]
-use std::collections::HashMap as KeyValMap;
 
-use alloc::{collections::BTreeMap, vec::Vec};
+use alloc::{collections::BTreeMap, format, string::String, vec::Vec};
 
 use core::{
+    cell::{Cell, RefCell},
+    cmp::Ordering,
     fmt::{Debug, Display, Error as FmtError, Formatter},
     hash::Hash,
+    ops::Index,
 };
 
+pub use crate::allowed_bins::pack_rects_with_allowed_bins;
+#[cfg(feature = "atlas_pages")]
+pub use crate::atlas_pages::{AtlasPages, PageStats};
+pub use crate::back_fill::pack_rects_with_back_fill;
+pub use crate::bin_pools::pack_rects_into_pools;
 pub use crate::bin_section::contains_smallest_box;
-pub use crate::bin_section::BinSection;
-pub use crate::bin_section::ComparePotentialContainersFn;
-use crate::grouped_rects_to_place::Group;
+use crate::bin_section::SPLIT_CONFIGURATION_COUNT;
+pub use crate::bin_section::{BinSection, BinSectionError};
+pub use crate::bin_section::{ComparePotentialContainers, ComparePotentialContainersFn};
+pub use crate::bin_section::{ExactFit, RemainingSections};
+pub use crate::bin_size::{suggest_bin_size, BinSizeConstraints};
+pub use crate::compaction::compact_lightest_bin;
+pub use crate::diff::PackDiff;
+pub use crate::fallback_sizes::pack_rects_with_fallback_sizes;
+pub use crate::fit_check::{can_fit, fits_in_any_bin};
+#[cfg(feature = "font_atlas")]
+pub use crate::font_atlas::{AtlasChange, AtlasGlyph, FontAtlas};
+pub use crate::gap_analysis::{analyze_gaps, GapChange, GapSuggestion};
 pub use crate::grouped_rects_to_place::GroupedRectsToPlace;
-pub use crate::target_bin::TargetBin;
-use crate::width_height_depth::WidthHeightDepth;
+use crate::grouped_rects_to_place::{Group, MergedGroup};
+pub use crate::into_bins::pack_rects_into;
+pub use crate::merge::MergeError;
+pub use crate::mirrored_bins::pack_rects_with_mirrored_bins;
+use crate::pack_options::PackContext;
+use crate::packer::{recycle, PackScratch};
+pub use crate::target_bin::{
+    BinMetrics, BinSnapshot, CountingBinMetrics, GrowBinError, PushBinSectionError, SizeClasses,
+    TargetBin,
+};
+pub use crate::width_height_depth::WidthHeightDepth;
+
+pub use self::box_size_heuristics::{volume_heuristic, BoxSizeHeuristic, BoxSizeHeuristicFn};
+pub use self::rect_to_insert::{BinEdge, RectToInsert, RotationAllowances};
+pub use crate::pack_2d::{pack_into_bin, pack_rects_2d, PackedLocation2d, RectanglePack2dOk};
+pub use crate::pack_options::{
+    CompareOrientations, OrientationCandidate, PackOptions, PlacementPolicy, SplitStrategy,
+    ValidatePlacement,
+};
+pub use crate::packed_bin::PackedBin;
+pub use crate::packed_location::{PackedLocation, RotatedBy};
+pub use crate::packer::Packer;
+pub use crate::partial::pack_rects_partial;
+pub use crate::payload::GroupedRectsWithPayloads;
+pub use crate::placement_heuristic::{pack_rects_with_heuristic, PlacementHeuristic};
+pub use crate::ranges::pack_ranges;
+pub use crate::repack::repack;
+#[cfg(feature = "rolling_atlas")]
+pub use crate::rolling_atlas::{RollingAtlas, RollingPlacement};
+pub use crate::verify::PlacementIssue;
 
-pub use self::box_size_heuristics::{volume_heuristic, BoxSizeHeuristicFn};
-pub use self::rect_to_insert::RectToInsert;
-pub use crate::packed_location::PackedLocation;
+#[macro_use]
+mod logging;
 
+mod allowed_bins;
+#[cfg(feature = "atlas_pages")]
+mod atlas_pages;
+mod back_fill;
+mod bin_pools;
 mod bin_section;
+mod bin_size;
+mod compaction;
+mod determinism_audit;
+mod diff;
+mod fallback_sizes;
+mod fit_check;
+#[cfg(feature = "font_atlas")]
+mod font_atlas;
+mod gap_analysis;
 mod grouped_rects_to_place;
+mod into_bins;
+mod merge;
+mod mirrored_bins;
 
+mod pack_2d;
+mod pack_options;
+mod packed_bin;
 mod packed_location;
+mod packer;
+mod partial;
+mod payload;
+mod placement_heuristic;
+mod ranges;
 mod rect_to_insert;
+mod repack;
+#[cfg(feature = "rolling_atlas")]
+mod rolling_atlas;
 mod target_bin;
+mod verify;
 mod width_height_depth;
 
 mod box_size_heuristics;
@@ -120,6 +191,12 @@ mod box_size_heuristics;
 /// ).unwrap();
 /// ```
 ///
+/// ## Heuristics
+///
+/// The box size heuristic and the comparison of potential containers can be given as functions,
+/// such as [`volume_heuristic`] and [`contains_smallest_box`], as closures, or as
+/// [`BoxSizeHeuristicFn`] / [`ComparePotentialContainersFn`] trait objects.
+///
 /// ## Algorithm
 ///
 /// The algorithm was originally inspired by [rectpack2D] and then modified to work in 3D.
@@ -133,254 +210,1531 @@ pub fn pack_rects<
     RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
     BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
     GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
 >(
     rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
     target_bins: &mut BTreeMap<BinId, TargetBin>,
-    box_size_heuristic: &BoxSizeHeuristicFn,
-    more_suitable_containers_fn: &ComparePotentialContainersFn,
+    box_size_heuristic: &Heuristic,
+    more_suitable_containers_fn: &Compare,
 ) -> Result<RectanglePackOk<RectToPlaceId, BinId>, RectanglePackError> {
-    let mut packed_locations = KeyValMap::new();
-
-    let mut target_bins: Vec<(&BinId, &mut TargetBin)> = target_bins.iter_mut().collect();
-    sort_bins_smallest_to_largest(&mut target_bins, box_size_heuristic);
-
-    let mut group_id_to_inbound_ids: Vec<(&Group<GroupId, RectToPlaceId>, &Vec<RectToPlaceId>)> =
-        rects_to_place.group_id_to_inbound_ids.iter().collect();
-    sort_groups_largest_to_smallest(
-        &mut group_id_to_inbound_ids,
+    pack_rects_with_options(
         rects_to_place,
+        target_bins,
         box_size_heuristic,
-    );
-
-    'group: for (_group_id, rects_to_place_ids) in group_id_to_inbound_ids {
-        for (bin_id, bin) in target_bins.iter_mut() {
-            if !can_fit_entire_group_into_bin(
-                bin.clone(),
-                &rects_to_place_ids[..],
-                rects_to_place,
-                box_size_heuristic,
-                more_suitable_containers_fn,
-            ) {
-                continue;
-            }
-
-            'incoming: for rect_to_place_id in rects_to_place_ids.iter() {
-                if bin.available_bin_sections.len() == 0 {
-                    continue;
-                }
-
-                let _bin_clone = bin.clone();
-
-                let mut bin_sections = bin.available_bin_sections.clone();
+        more_suitable_containers_fn,
+        &PackOptions::default(),
+    )
+}
 
-                let last_section_idx = bin_sections.len() - 1;
-                let mut sections_tried = 0;
+/// Determine how to fit a set of incoming rectangles (2d or 3d) into a set of target bins, using
+/// [`PackOptions`] to control how the rectangles get packed.
+///
+/// See [`pack_rects`] for more details.
+///
+/// ## Example
+///
+/// ```
+/// use rectangle_pack::{
+///     GroupedRectsToPlace, RectToInsert, pack_rects_with_options, PackOptions, TargetBin,
+///     volume_heuristic, contains_smallest_box
+/// };
+/// use std::collections::BTreeMap;
+///
+/// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+/// rects_to_place.push_rect("some-rect", None, RectToInsert::new(10, 20, 1));
+///
+/// let mut target_bins = BTreeMap::new();
+/// target_bins.insert("some-bin", TargetBin::new(2048, 2048, 1));
+///
+/// let rectangle_placements = pack_rects_with_options(
+///     &rects_to_place,
+///     &mut target_bins,
+///     &volume_heuristic,
+///     &contains_smallest_box,
+///     &PackOptions::new().with_free_section_merging(true),
+/// ).unwrap();
+/// ```
+pub fn pack_rects_with_options<
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+>(
+    rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    target_bins: &mut BTreeMap<BinId, TargetBin>,
+    box_size_heuristic: &Heuristic,
+    more_suitable_containers_fn: &Compare,
+    options: &PackOptions,
+) -> Result<RectanglePackOk<RectToPlaceId, BinId>, RectanglePackError> {
+    let mut packed = RectanglePackOk::default();
+    pack_rects_keyed_by(
+        rects_to_place,
+        target_bins,
+        None,
+        PackContext::new(box_size_heuristic, more_suitable_containers_fn, options),
+        RectToPlaceId::clone,
+        &mut packed,
+    )?;
+
+    Ok(packed)
+}
 
-                'section: while let Some(remaining_section) = bin_sections.pop() {
-                    let rect_to_place = rects_to_place.rects[&rect_to_place_id];
+/// Pack rectangles the same way as [`pack_rects_with_options`], but write the placements into a
+/// [`RectanglePackOk`] from an earlier call instead of allocating a new one.
+///
+/// The previous placements are cleared first. Combined with [`TargetBin.clear`] this lets
+/// applications that pack every frame reuse the same buffers instead of allocating new ones each
+/// time.
+///
+/// Only `packed` and the bins are reused. The scratch space that is only needed while packing,
+/// such as the merged groups, the copy of a bin that groups are test placed into and the list of
+/// placements, is still allocated and freed again on every call. To reuse those as well, pack
+/// with [`Packer.pack_reusing`], which keeps them in the [`Packer`] in between calls.
+///
+/// If packing fails `packed` is left empty.
+///
+/// ## Example
+///
+/// ```
+/// use rectangle_pack::{
+///     GroupedRectsToPlace, RectToInsert, RectanglePackOk, pack_rects_reusing, PackOptions,
+///     TargetBin, volume_heuristic, contains_smallest_box
+/// };
+/// use std::collections::BTreeMap;
+///
+/// let mut target_bins = BTreeMap::new();
+/// target_bins.insert("some-bin", TargetBin::new(2048, 2048, 1));
+///
+/// let mut packed = RectanglePackOk::default();
+///
+/// for frame in 0..3 {
+///     let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+///     rects_to_place.push_rect(frame, None, RectToInsert::new(10, 20, 1));
+///
+///     for bin in target_bins.values_mut() {
+///         bin.clear();
+///     }
+///
+///     pack_rects_reusing(
+///         &rects_to_place,
+///         &mut target_bins,
+///         &volume_heuristic,
+///         &contains_smallest_box,
+///         &PackOptions::default(),
+///         &mut packed,
+///     ).unwrap();
+///
+///     assert_eq!(packed.packed_locations().len(), 1);
+/// }
+/// ```
+///
+/// [`Packer.pack_reusing`]: Packer::pack_reusing
+/// [`TargetBin.clear`]: TargetBin::clear
+pub fn pack_rects_reusing<
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+>(
+    rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    target_bins: &mut BTreeMap<BinId, TargetBin>,
+    box_size_heuristic: &Heuristic,
+    more_suitable_containers_fn: &Compare,
+    options: &PackOptions,
+    packed: &mut RectanglePackOk<RectToPlaceId, BinId>,
+) -> Result<(), RectanglePackError> {
+    pack_rects_keyed_by(
+        rects_to_place,
+        target_bins,
+        None,
+        PackContext::new(box_size_heuristic, more_suitable_containers_fn, options),
+        RectToPlaceId::clone,
+        packed,
+    )
+}
 
-                    let placement = remaining_section.try_place(
-                        &rect_to_place,
-                        more_suitable_containers_fn,
-                        box_size_heuristic,
-                    );
+/// Pack rectangles the same way as [`pack_rects_with_options`], but return a result that borrows
+/// the rectangle ids from the `rects_to_place` instead of cloning every one of them.
+///
+/// Useful when your ids are expensive to clone, such as a `PathBuf` or `String`.
+///
+/// ## Example
+///
+/// ```
+/// use rectangle_pack::{
+///     GroupedRectsToPlace, RectToInsert, pack_rects_borrowed, PackOptions, TargetBin,
+///     volume_heuristic, contains_smallest_box
+/// };
+/// use std::collections::BTreeMap;
+/// use std::path::PathBuf;
+///
+/// let mut rects_to_place: GroupedRectsToPlace<PathBuf, ()> = GroupedRectsToPlace::new();
+/// rects_to_place.push_rect(PathBuf::from("grass.png"), None, RectToInsert::new(10, 20, 1));
+///
+/// let mut target_bins = BTreeMap::new();
+/// target_bins.insert("some-bin", TargetBin::new(2048, 2048, 1));
+///
+/// let rectangle_placements = pack_rects_borrowed(
+///     &rects_to_place,
+///     &mut target_bins,
+///     &volume_heuristic,
+///     &contains_smallest_box,
+///     &PackOptions::default(),
+/// ).unwrap();
+///
+/// let grass: &PathBuf = rectangle_placements.packed_locations().keys().next().unwrap();
+/// assert_eq!(grass, &PathBuf::from("grass.png"));
+/// ```
+pub fn pack_rects_borrowed<
+    'a,
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+>(
+    rects_to_place: &'a GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    target_bins: &mut BTreeMap<BinId, TargetBin>,
+    box_size_heuristic: &Heuristic,
+    more_suitable_containers_fn: &Compare,
+    options: &PackOptions,
+) -> Result<RectanglePackOk<&'a RectToPlaceId, BinId>, RectanglePackError> {
+    let mut packed = RectanglePackOk::default();
+    pack_rects_keyed_by(
+        rects_to_place,
+        target_bins,
+        None,
+        PackContext::new(box_size_heuristic, more_suitable_containers_fn, options),
+        |rect_to_place_id| rect_to_place_id,
+        &mut packed,
+    )?;
+
+    Ok(packed)
+}
 
-                    if placement.is_err() {
-                        sections_tried += 1;
-                        continue 'section;
-                    }
+/// Pack the rectangles into `packed`, using the `key` function to create the key that each
+/// rectangle's placement gets stored under.
+///
+/// Anything already in `packed` is cleared first.
+fn pack_rects_keyed_by<'a, RectToPlaceId, BinId, GroupId, Key, Heuristic, Compare>(
+    rects_to_place: &'a GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    target_bins: &mut BTreeMap<BinId, TargetBin>,
+    allowed_bins: Option<&BTreeMap<RectToPlaceId, Vec<BinId>>>,
+    ctx: PackContext<Heuristic, Compare>,
+    key: impl Fn(&'a RectToPlaceId) -> Key,
+    packed: &mut RectanglePackOk<Key, BinId>,
+) -> Result<(), RectanglePackError>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Key: PartialEq + Eq + Hash + Ord,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+{
+    let sections_examined = Cell::new(0);
+    let ctx = PackContext {
+        sections_examined: Some(&sections_examined),
+        ..ctx
+    };
+
+    // Fail fast when the rectangles that must be placed can't possibly fit, no matter how they
+    // would be arranged.
+    if !ctx.options.best_effort && !ctx.options.maximize_value {
+        let required_volume: u128 = rects_to_place
+            .rects
+            .values()
+            .filter(|rect| !rect.optional)
+            .map(|rect| rect.whd.volume())
+            .sum();
+        let available_volume: u128 = target_bins
+            .values()
+            .filter(|bin| !bin.frozen)
+            .map(TargetBin::free_volume)
+            .sum();
+
+        if required_volume > available_volume {
+            packed.packed_locations.clear();
+            packed.omitted_optional_rects.clear();
+
+            return Err(RectanglePackError::NotEnoughBinSpace {
+                rect_to_place_id: None,
+                group_id: None,
+                required_volume,
+                available_volume,
+            });
+        }
+    }
 
-                    let (placement, mut new_sections) = placement.unwrap();
-                    sort_by_size_largest_to_smallest(&mut new_sections, box_size_heuristic);
+    packed.packed_locations.clear();
+    packed.omitted_optional_rects.clear();
+
+    let placed = if ctx.options.determinism_audit {
+        let mut shuffled_bins: BTreeMap<BinId, TargetBin> = target_bins
+            .iter()
+            .map(|(bin_id, bin)| (bin_id.clone(), bin.clone_without_metrics()))
+            .collect();
+        let mut shuffled_placements = vec![];
+        let shuffled_placed = place_groups(
+            rects_to_place,
+            &mut shuffled_bins,
+            allowed_bins,
+            &ctx,
+            Some(determinism_audit::SHUFFLE_SEED),
+            |rect_to_place_id, bin_id: &BinId, placement| {
+                shuffled_placements.push((rect_to_place_id, bin_id.clone(), placement))
+            },
+        );
 
-                    bin.remove_filled_section(last_section_idx - sections_tried);
-                    bin.add_new_sections(new_sections);
+        let mut placements = vec![];
+        let placed = place_groups(
+            rects_to_place,
+            target_bins,
+            allowed_bins,
+            &ctx,
+            None,
+            |rect_to_place_id, bin_id: &BinId, placement| {
+                placements.push((rect_to_place_id, bin_id.clone(), placement))
+            },
+        );
 
-                    packed_locations.insert(rect_to_place_id.clone(), (bin_id.clone(), placement));
+        let placements = placed.map(|()| placements);
+        determinism_audit::assert_identical(
+            &placements,
+            &shuffled_placed.map(|()| shuffled_placements),
+            target_bins,
+            &shuffled_bins,
+        );
 
-                    continue 'incoming;
-                }
+        placements.map(|placements| {
+            for (rect_to_place_id, bin_id, placement) in placements {
+                packed
+                    .packed_locations
+                    .insert(key(rect_to_place_id), (bin_id, placement));
             }
+        })
+    } else {
+        place_groups(
+            rects_to_place,
+            target_bins,
+            allowed_bins,
+            &ctx,
+            None,
+            |rect_to_place_id, bin_id: &BinId, placement| {
+                packed
+                    .packed_locations
+                    .insert(key(rect_to_place_id), (bin_id.clone(), placement));
+            },
+        )
+    };
+    if let Err(err) = placed {
+        packed.packed_locations.clear();
+        return Err(err);
+    }
 
-            continue 'group;
+    for rect_to_place_id in rects_to_place.rects.keys() {
+        let rect_key = key(rect_to_place_id);
+        if !packed.packed_locations.contains_key(&rect_key) {
+            packed.omitted_optional_rects.push(rect_key);
         }
-        return Err(RectanglePackError::NotEnoughBinSpace);
     }
+    packed.omitted_optional_rects.sort_unstable();
 
-    Ok(RectanglePackOk { packed_locations })
+    Ok(())
 }
 
-// TODO: This is duplicative of the code above
-fn can_fit_entire_group_into_bin<RectToPlaceId, GroupId>(
-    mut bin: TargetBin,
-    group: &[RectToPlaceId],
-    rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
-
-    box_size_heuristic: &BoxSizeHeuristicFn,
-    more_suitable_containers_fn: &ComparePotentialContainersFn,
-) -> bool
+/// Place every group of rectangles into the bins, passing where each rectangle was placed to
+/// `place` once every group has been placed.
+///
+/// If a `shuffle_seed` is provided the bins and groups are shuffled before being sorted, which
+/// should never change the outcome.
+fn place_groups<'a, RectToPlaceId, BinId, GroupId, Heuristic, Compare>(
+    rects_to_place: &'a GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    target_bins: &mut BTreeMap<BinId, TargetBin>,
+    allowed_bins: Option<&BTreeMap<RectToPlaceId, Vec<BinId>>>,
+    ctx: &PackContext<Heuristic, Compare>,
+    shuffle_seed: Option<u64>,
+    place: impl FnMut(&'a RectToPlaceId, &BinId, PackedLocation),
+) -> Result<(), RectanglePackError>
 where
     RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
     GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
 {
-    'incoming: for rect_to_place_id in group.iter() {
-        if bin.available_bin_sections.len() == 0 {
-            return false;
+    let local_scratch;
+    let scratch = match ctx.scratch {
+        Some(scratch) => scratch,
+        None => {
+            local_scratch = RefCell::new(PackScratch::default());
+            &local_scratch
         }
+    };
+    let mut scratch = scratch.borrow_mut();
 
-        let mut bin_sections = bin.available_bin_sections.clone();
+    let mut bins: Vec<(&BinId, &mut TargetBin)> = recycle(core::mem::take(&mut scratch.bins));
+    bins.extend(target_bins.iter_mut());
+    let mut groups = recycle(core::mem::take(&mut scratch.merged_groups));
+    rects_to_place.merged_groups_into(&mut groups);
+
+    let placed = place_merged_groups(
+        rects_to_place,
+        &mut bins,
+        &mut groups,
+        allowed_bins,
+        ctx,
+        shuffle_seed,
+        &mut scratch,
+        place,
+    );
 
-        let last_section_idx = bin_sections.len() - 1;
-        let mut sections_tried = 0;
+    scratch.bins = recycle(bins);
+    scratch.merged_groups = recycle(groups);
 
-        'section: while let Some(remaining_section) = bin_sections.pop() {
-            let rect_to_place = rects_to_place.rects[&rect_to_place_id];
+    placed
+}
 
-            let placement = remaining_section.try_place(
-                &rect_to_place,
-                more_suitable_containers_fn,
-                box_size_heuristic,
-            );
+/// Place the merged groups of rectangles into the bins, see [`place_groups`].
+#[allow(clippy::too_many_arguments)]
+fn place_merged_groups<'a, RectToPlaceId, BinId, GroupId, Heuristic, Compare>(
+    rects_to_place: &'a GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    target_bins: &mut [(&BinId, &mut TargetBin)],
+    group_id_to_inbound_ids: &mut [MergedGroup<'a, GroupId, RectToPlaceId>],
+    allowed_bins: Option<&BTreeMap<RectToPlaceId, Vec<BinId>>>,
+    ctx: &PackContext<Heuristic, Compare>,
+    shuffle_seed: Option<u64>,
+    scratch: &mut PackScratch,
+    mut place: impl FnMut(&'a RectToPlaceId, &BinId, PackedLocation),
+) -> Result<(), RectanglePackError>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+{
+    let box_size_heuristic = ctx.box_size_heuristic;
+    let options = ctx.options;
+
+    if let Some(distinct_bin_set) =
+        distinct_bin_set_within_a_group(group_id_to_inbound_ids, rects_to_place)
+    {
+        return Err(RectanglePackError::DistinctBinSetUnsatisfiable { distinct_bin_set });
+    }
+
+    if let Some(shuffle_seed) = shuffle_seed {
+        determinism_audit::shuffle(target_bins, shuffle_seed);
+        determinism_audit::shuffle(group_id_to_inbound_ids, shuffle_seed);
+    }
+
+    sort_bins_smallest_to_largest(target_bins, box_size_heuristic);
+    let is_optional_group = |rects_to_place_ids: &[RectToPlaceId]| {
+        options.best_effort
+            || options.maximize_value
+            || rects_to_place_ids
+                .iter()
+                .all(|rect_to_place_id| rects_to_place.rects[rect_to_place_id].optional)
+    };
+    // Required groups go first, then the highest priority ones, then those with the highest
+    // value density for a value maximizing pack, and then the largest ones. Every group is
+    // different so there are never any ties, which lets us sort without allocating.
+    group_id_to_inbound_ids.sort_unstable_by(|a, b| {
+        let a_order = GroupOrder::new(&a.1, rects_to_place, box_size_heuristic);
+        let b_order = GroupOrder::new(&b.1, rects_to_place, box_size_heuristic);
+
+        let is_optional = |order: &GroupOrder| {
+            options.best_effort || options.maximize_value || order.all_optional
+        };
+        let order = is_optional(&a_order)
+            .cmp(&is_optional(&b_order))
+            .then_with(|| b_order.priority.cmp(&a_order.priority));
+        let order = match options.maximize_value {
+            true => order.then_with(|| a_order.compare_value_density(&b_order)),
+            false => order,
+        };
+
+        order
+            .then_with(|| b_order.size.cmp(&a_order.size))
+            .then_with(|| a.0.cmp(b.0))
+    });
+
+    let max_backtracked = options.max_backtracked_placements;
+
+    let best_fit_options = PackOptions {
+        placement_policy: PlacementPolicy::BestFit,
+        ..options.clone()
+    };
+    let best_fit_ctx = PackContext {
+        options: &best_fit_options,
+        ..*ctx
+    };
+    let first_fit_options = PackOptions {
+        placement_policy: PlacementPolicy::FirstFit,
+        ..options.clone()
+    };
+    let first_fit_ctx = PackContext {
+        options: &first_fit_options,
+        more_suitable_containers_fn: ctx
+            .back_fill_containers_fn
+            .unwrap_or(ctx.more_suitable_containers_fn),
+        ..*ctx
+    };
+
+    scratch.reset(target_bins.len(), rects_to_place.distinct_bin_sets.len());
+    let PackScratch {
+        trial_bin,
+        alternatives,
+        groups_per_bin,
+        bins_per_distinct_bin_set,
+        placed_groups,
+        placements,
+        ..
+    } = scratch;
+
+    let mut retry_after = None;
+    let mut backtrack_floor = 0;
+    let conflicting_distinct_bin_set = |bins_per_distinct_bin_set: &[Vec<usize>],
+                                        rects_to_place_ids: &[RectToPlaceId],
+                                        bin_idx| {
+        rects_to_place_ids
+            .iter()
+            .flat_map(|rect_to_place_id| rects_to_place.distinct_bin_sets_of(rect_to_place_id))
+            .find(|distinct_bin_set| {
+                bins_per_distinct_bin_set[**distinct_bin_set].contains(&bin_idx)
+            })
+            .copied()
+    };
+    let is_allowed_bin =
+        |rects_to_place_ids: &[RectToPlaceId], bin_id: &BinId| match allowed_bins {
+            Some(allowed_bins) => rects_to_place_ids.iter().all(|rect_to_place_id| {
+                match allowed_bins.get(rect_to_place_id) {
+                    Some(allowed) => allowed.contains(bin_id),
+                    None => true,
+                }
+            }),
+            None => true,
+        };
+    // The number of placed groups in each bin is used to enforce the PackOptions.with_max_bins
+    // limit.
+    let bin_limit_reached = |groups_per_bin: &[usize], bin_idx: usize| match options.max_bins {
+        Some(max_bins) => {
+            groups_per_bin[bin_idx] == 0
+                && groups_per_bin.iter().filter(|groups| **groups > 0).count() >= max_bins
+        }
+        None => false,
+    };
+
+    while placed_groups.len() < group_id_to_inbound_ids.len() {
+        let rects_to_place_ids = &group_id_to_inbound_ids[placed_groups.len()].1;
+
+        let group_size: u128 = rects_to_place_ids
+            .iter()
+            .map(|rect_to_place_id| {
+                box_size_heuristic.box_size(rects_to_place.rects[rect_to_place_id].whd)
+            })
+            .sum();
+        let is_large_group = match options.two_phase_threshold {
+            Some(threshold) => group_size > threshold,
+            None => false,
+        };
+        let ctx = match options.two_phase_threshold {
+            None => ctx,
+            Some(_) if is_large_group => &best_fit_ctx,
+            Some(_) => &first_fit_ctx,
+        };
+
+        let is_retry = retry_after.is_some();
+        placement_alternatives(target_bins.len(), retry_after.take(), alternatives);
+        if is_large_group && !is_retry {
+            alternatives.sort_by_cached_key(|(bin_idx, _)| {
+                tightest_fit(
+                    target_bins[*bin_idx].1,
+                    trial_bin,
+                    &rects_to_place_ids[..],
+                    rects_to_place,
+                    ctx,
+                )
+                .unwrap_or(u128::MAX)
+            });
+        }
+        if options.minimize_bin_cost && !is_retry {
+            alternatives.sort_by_key(|(bin_idx, _)| {
+                let bin = &target_bins[*bin_idx].1;
+                (core::cmp::Reverse(bin.priority), bin.marginal_cost())
+            });
+        }
 
-            if placement.is_err() {
-                sections_tried += 1;
-                continue 'section;
+        let group = group_id_to_inbound_ids[placed_groups.len()].0;
+        let chosen = alternatives.iter().copied().find(|(bin_idx, split_rank)| {
+            if let Some(distinct_bin_set) = conflicting_distinct_bin_set(
+                bins_per_distinct_bin_set,
+                rects_to_place_ids,
+                *bin_idx,
+            ) {
+                trace!(
+                    "group would share a bin with its distinct bin set; group={:?} bin={:?} distinct_bin_set={}",
+                    group,
+                    target_bins[*bin_idx].0,
+                    distinct_bin_set
+                );
+                return false;
+            }
+            if bin_limit_reached(groups_per_bin, *bin_idx) {
+                return false;
+            }
+            if !is_allowed_bin(rects_to_place_ids, target_bins[*bin_idx].0) {
+                return false;
             }
 
-            let (_placement, mut new_sections) = placement.unwrap();
-            sort_by_size_largest_to_smallest(&mut new_sections, box_size_heuristic);
+            let fits = can_fit_entire_group_into_bin(
+                target_bins[*bin_idx].1,
+                trial_bin,
+                &rects_to_place_ids[..],
+                rects_to_place,
+                *split_rank,
+                ctx,
+                placements,
+            );
+            trace!(
+                "checked whether group fits into bin; group={:?} bin={:?} split_rank={} fits={}",
+                group,
+                target_bins[*bin_idx].0,
+                split_rank,
+                fits
+            );
 
-            bin.remove_filled_section(last_section_idx - sections_tried);
-            bin.add_new_sections(new_sections);
+            fits
+        });
 
-            continue 'incoming;
+        if ctx.search_limit_exceeded() {
+            return Err(RectanglePackError::SearchLimitExceeded);
         }
 
-        return false;
-    }
+        let (bin_idx, split_rank) = match chosen {
+            Some(chosen) => chosen,
+            None if is_optional_group(rects_to_place_ids) => {
+                debug!("omitting optional group; group={:?}", group);
+                placed_groups.push(PlacedGroup {
+                    bin_idx: 0,
+                    split_rank: 0,
+                    bin_before_placing: None,
+                    first_placement: placements.len(),
+                });
 
-    true
-}
+                continue;
+            }
+            None => {
+                backtrack_floor =
+                    backtrack_floor.max(placed_groups.len().saturating_sub(max_backtracked));
+                if placed_groups.len() <= backtrack_floor {
+                    debug!("group does not fit into any bin; group={:?}", group);
+
+                    let unplaced_volume = group_id_to_inbound_ids[placed_groups.len()..]
+                        .iter()
+                        .filter(|(_, ids)| !is_optional_group(ids))
+                        .flat_map(|(_, ids)| ids.iter())
+                        .map(|rect_to_place_id| rects_to_place.rects[rect_to_place_id].whd.volume())
+                        .sum();
+
+                    // Only blame a constraint if the group would have fit without it.
+                    let blocking_constraint = (0..target_bins.len()).find_map(|bin_idx| {
+                        let error = match conflicting_distinct_bin_set(
+                            bins_per_distinct_bin_set,
+                            rects_to_place_ids,
+                            bin_idx,
+                        ) {
+                            Some(distinct_bin_set) => {
+                                RectanglePackError::DistinctBinSetUnsatisfiable { distinct_bin_set }
+                            }
+                            None if bin_limit_reached(groups_per_bin, bin_idx) => {
+                                RectanglePackError::BinLimitExceeded {
+                                    max_bins: options.max_bins.unwrap_or(0),
+                                    unplaced_volume,
+                                }
+                            }
+                            None => return None,
+                        };
+                        let fits = can_fit_entire_group_into_bin(
+                            target_bins[bin_idx].1,
+                            trial_bin,
+                            &rects_to_place_ids[..],
+                            rects_to_place,
+                            0,
+                            ctx,
+                            placements,
+                        );
+
+                        if fits {
+                            Some(error)
+                        } else {
+                            None
+                        }
+                    });
+                    if let Some(blocking_constraint) = blocking_constraint {
+                        return Err(blocking_constraint);
+                    }
 
-/// Information about successfully packed rectangles.
-#[derive(Debug, PartialEq)]
-pub struct RectanglePackOk<RectToPlaceId: PartialEq + Eq + Hash, BinId: PartialEq + Eq + Hash> {
-    packed_locations: KeyValMap<RectToPlaceId, (BinId, PackedLocation)>,
-    // TODO: Other information such as information about how the bins were packed
-    // (perhaps percentage filled)
-}
+                    // Blame the first rectangle that doesn't even fit into a bin on its own.
+                    let mut fits_on_its_own = |rect_to_place_id: &RectToPlaceId| {
+                        target_bins.iter().any(|(_, bin)| {
+                            can_fit_entire_group_into_bin(
+                                bin,
+                                trial_bin,
+                                core::slice::from_ref(rect_to_place_id),
+                                rects_to_place,
+                                0,
+                                ctx,
+                                placements,
+                            )
+                        })
+                    };
+                    let rect_to_place_id = rects_to_place_ids
+                        .iter()
+                        .find(|rect_to_place_id| !fits_on_its_own(rect_to_place_id))
+                        .or_else(|| rects_to_place_ids.first());
+
+                    return Err(RectanglePackError::NotEnoughBinSpace {
+                        rect_to_place_id: rect_to_place_id
+                            .map(|rect_to_place_id| format!("{:?}", rect_to_place_id)),
+                        group_id: match group {
+                            Group::Grouped(group_id) => Some(format!("{:?}", group_id)),
+                            Group::Ungrouped(_) => None,
+                        },
+                        required_volume: unplaced_volume,
+                        available_volume: target_bins
+                            .iter()
+                            .filter(|(_, bin)| !bin.frozen)
+                            .map(|(_, bin)| bin.free_volume())
+                            .sum(),
+                    });
+                }
 
-impl<RectToPlaceId: PartialEq + Eq + Hash, BinId: PartialEq + Eq + Hash>
-    RectanglePackOk<RectToPlaceId, BinId>
-{
-    /// Indicates where every incoming rectangle was placed
-    pub fn packed_locations(&self) -> &KeyValMap<RectToPlaceId, (BinId, PackedLocation)> {
-        &self.packed_locations
-    }
-}
+                let undone = placed_groups.pop().unwrap();
+                groups_per_bin[undone.bin_idx] -= 1;
+                placements.truncate(undone.first_placement);
+                for rect_to_place_id in group_id_to_inbound_ids[placed_groups.len()].1.iter() {
+                    for distinct_bin_set in rects_to_place.distinct_bin_sets_of(rect_to_place_id) {
+                        let bins = &mut bins_per_distinct_bin_set[*distinct_bin_set];
+                        if let Some(idx) = bins.iter().position(|b| *b == undone.bin_idx) {
+                            bins.swap_remove(idx);
+                        }
+                    }
+                }
+                debug!(
+                    "group does not fit into any bin, undoing the previous group; group={:?} undone_bin={:?}",
+                    group, target_bins[undone.bin_idx].0
+                );
+                *target_bins[undone.bin_idx].1 = undone.bin_before_placing.unwrap();
+                retry_after = Some((undone.bin_idx, undone.split_rank));
 
-/// An error while attempting to pack rectangles into bins.
-#[derive(Debug, PartialEq)]
-pub enum RectanglePackError {
-    /// The rectangles can't be placed into the bins. More bin space needs to be provided.
-    NotEnoughBinSpace,
-}
+                continue;
+            }
+        };
 
-#[cfg(std)]
-impl std::error::Error for RectanglePackError {}
+        let split_rank = if options.lookahead > 0
+            && split_rank == 0
+            && !options.reached_target_fill(target_bins[bin_idx].1)
+        {
+            let upcoming: Vec<&RectToInsert> = group_id_to_inbound_ids[placed_groups.len() + 1..]
+                .iter()
+                .flat_map(|(_, ids)| ids.iter())
+                .take(options.lookahead)
+                .map(|rect_to_place_id| &rects_to_place.rects[rect_to_place_id])
+                .collect();
+
+            split_rank_with_best_lookahead(
+                target_bins[bin_idx].1,
+                trial_bin,
+                &rects_to_place_ids[..],
+                &upcoming,
+                rects_to_place,
+                ctx,
+                placements,
+            )
+        } else {
+            split_rank
+        };
 
-impl Display for RectanglePackError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
-        match self {
-            RectanglePackError::NotEnoughBinSpace => {
-                f.write_str("Not enough space to place all of the rectangles.")
+        debug!(
+            "placing group into bin; group={:?} bin={:?} split_rank={}",
+            group, target_bins[bin_idx].0, split_rank
+        );
+
+        let bin = &mut target_bins[bin_idx].1;
+        let bin_before_placing = if max_backtracked > 0 {
+            Some(bin.clone())
+        } else {
+            None
+        };
+
+        let first_placement = placements.len();
+        let placed = place_group_into_bin(
+            bin,
+            rects_to_place_ids,
+            rects_to_place,
+            split_rank,
+            ctx,
+            placements,
+        );
+
+        if ctx.search_limit_exceeded() {
+            return Err(RectanglePackError::SearchLimitExceeded);
+        }
+
+        if placed {
+            for rect_to_place_id in rects_to_place_ids.iter() {
+                for distinct_bin_set in rects_to_place.distinct_bin_sets_of(rect_to_place_id) {
+                    bins_per_distinct_bin_set[*distinct_bin_set].push(bin_idx);
+                }
             }
         }
+        groups_per_bin[bin_idx] += 1;
+
+        placed_groups.push(PlacedGroup {
+            bin_idx,
+            split_rank,
+            bin_before_placing,
+            first_placement,
+        });
+
+        if options.reached_target_fill(target_bins[bin_idx].1) {
+            debug!(
+                "bin reached the target fill, keeping the layout so far; bin={:?}",
+                target_bins[bin_idx].0
+            );
+            backtrack_floor = placed_groups.len();
+        }
+
+        // Groups this far back can never be undone, so there's no need to hold onto their bins.
+        if let Some(too_old) = placed_groups.len().checked_sub(max_backtracked + 1) {
+            placed_groups[too_old].bin_before_placing = None;
+        }
+    }
+
+    for (group_idx, placed_group) in placed_groups.iter().enumerate() {
+        let last_placement = placed_groups
+            .get(group_idx + 1)
+            .map_or(placements.len(), |next| next.first_placement);
+        let group_placements = &placements[placed_group.first_placement..last_placement];
+
+        for (rect_to_place_id, placement) in group_id_to_inbound_ids[group_idx]
+            .1
+            .iter()
+            .zip(group_placements)
+        {
+            // Merged groups own their ids, so borrow the id from the rectangles instead.
+            let (rect_to_place_id, _) = rects_to_place
+                .rects
+                .get_key_value(rect_to_place_id)
+                .unwrap();
+            place(
+                rect_to_place_id,
+                target_bins[placed_group.bin_idx].0,
+                *placement,
+            );
+        }
     }
+
+    Ok(())
 }
 
-fn sort_bins_smallest_to_largest<BinId>(
-    bins: &mut Vec<(&BinId, &mut TargetBin)>,
-    box_size_heuristic: &BoxSizeHeuristicFn,
-) where
-    BinId: Debug + Hash + PartialEq + Eq + Clone,
+/// A group of rectangles that was placed into one of the bins, or an optional group without any
+/// placements that was left out.
+#[derive(Debug, Clone)]
+struct PlacedGroup {
+    bin_idx: usize,
+    split_rank: usize,
+    /// Kept around while the placement could still be undone by backtracking.
+    bin_before_placing: Option<TargetBin>,
+    /// The index into the placements of the location of the group's first rectangle. The group's
+    /// rectangles are placed one after another, in the same order as the group.
+    first_placement: usize,
+}
+
+/// A distinct bin set that two rectangles of the same (merged) group belong to. Such a set can
+/// never be satisfied since a group's rectangles are always placed into the same bin.
+fn distinct_bin_set_within_a_group<RectToPlaceId, GroupId>(
+    groups: &[MergedGroup<GroupId, RectToPlaceId>],
+    rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+) -> Option<usize>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
 {
-    bins.sort_by(|a, b| {
-        box_size_heuristic(WidthHeightDepth {
-            width: a.1.max_width,
-            height: a.1.max_height,
-            depth: a.1.max_depth,
+    if rects_to_place.distinct_bin_sets.is_empty() {
+        return None;
+    }
+
+    groups.iter().find_map(|(_, rects_to_place_ids)| {
+        rects_to_place_ids.iter().enumerate().find_map(|(idx, a)| {
+            rects_to_place_ids[idx + 1..]
+                .iter()
+                .find_map(|b| rects_to_place.shared_distinct_bin_set(a, b))
         })
-        .cmp(&box_size_heuristic(WidthHeightDepth {
-            width: b.1.max_width,
-            height: b.1.max_height,
-            depth: b.1.max_depth,
-        }))
-    });
+    })
 }
 
-fn sort_by_size_largest_to_smallest(
-    items: &mut [BinSection; 3],
-    box_size_heuristic: &BoxSizeHeuristicFn,
+/// The `(bin index, split rank)` pairs to try placing a group with, in order.
+///
+/// Normally every bin is tried with its best split. When retrying a group after backtracking we
+/// first try the remaining splits in the bin that the group was previously placed in, followed by
+/// the bins after it.
+fn placement_alternatives(
+    bin_count: usize,
+    retry_after: Option<(usize, usize)>,
+    alternatives: &mut Vec<(usize, usize)>,
 ) {
-    items.sort_by(|a, b| box_size_heuristic(b.whd).cmp(&box_size_heuristic(a.whd)));
+    alternatives.clear();
+
+    let first_bin = match retry_after {
+        None => 0,
+        Some((bin_idx, split_rank)) => {
+            alternatives.extend(
+                (split_rank + 1..SPLIT_CONFIGURATION_COUNT).map(|split_rank| (bin_idx, split_rank)),
+            );
+            bin_idx + 1
+        }
+    };
+    alternatives.extend((first_bin..bin_count).map(|bin_idx| (bin_idx, 0)));
 }
 
-fn sort_groups_largest_to_smallest<GroupId, RectToPlaceId>(
-    group_id_to_inbound_ids: &mut Vec<(&Group<GroupId, RectToPlaceId>, &Vec<RectToPlaceId>)>,
-    incoming_groups: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
-    box_size_heuristic: &BoxSizeHeuristicFn,
-) where
+/// Whether the group fits into the bin, checked by placing it into the `trial_bin` copy of the
+/// bin.
+///
+/// The `placements` are left the way they were.
+#[allow(clippy::too_many_arguments)]
+fn can_fit_entire_group_into_bin<RectToPlaceId, GroupId, Heuristic, Compare>(
+    bin: &TargetBin,
+    trial_bin: &mut TargetBin,
+    group: &[RectToPlaceId],
+    rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    split_rank: usize,
+    ctx: &PackContext<Heuristic, Compare>,
+    placements: &mut Vec<PackedLocation>,
+) -> bool
+where
     RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
     GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
 {
-    group_id_to_inbound_ids.sort_by(|a, b| {
-        let a_heuristic =
-            a.1.iter()
-                .map(|inbound| {
-                    let rect = incoming_groups.rects[inbound];
-                    box_size_heuristic(rect.whd)
-                })
-                .sum();
+    bin.clone_without_metrics_into(trial_bin);
 
-        let b_heuristic: u128 =
-            b.1.iter()
-                .map(|inbound| {
-                    let rect = incoming_groups.rects[inbound];
-                    box_size_heuristic(rect.whd)
-                })
-                .sum();
+    let first_placement = placements.len();
+    let fits = place_group_into_bin(
+        trial_bin,
+        group,
+        rects_to_place,
+        split_rank,
+        ctx,
+        placements,
+    );
+    placements.truncate(first_placement);
 
-        b_heuristic.cmp(&a_heuristic)
-    });
+    fits
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::{pack_rects, volume_heuristic, RectToInsert, RectanglePackError, TargetBin};
-
-    use super::*;
-    use crate::packed_location::RotatedBy;
-
-    /// If the provided rectangles can't fit into the provided bins.
-    #[test]
-    fn error_if_the_rectangles_cannot_fit_into_target_bins() {
-        let mut targets = BTreeMap::new();
-        targets.insert(BinId::Three, TargetBin::new(2, 100, 1));
-
-        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
-        groups.push_rect(RectToPlaceId::One, None, RectToInsert::new(3, 1, 1));
+/// Place every rectangle of the group into the bin, one after another, or all within one region
+/// for a [`PackOptions.with_contiguous_groups`] pack, pushing their locations onto the
+/// `placements`.
+///
+/// Returns `false`, leaving the `placements` the way they were, if any of them don't fit.
+///
+/// [`PackOptions.with_contiguous_groups`]: PackOptions::with_contiguous_groups
+fn place_group_into_bin<RectToPlaceId, GroupId, Heuristic, Compare>(
+    bin: &mut TargetBin,
+    group: &[RectToPlaceId],
+    rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    split_rank: usize,
+    ctx: &PackContext<Heuristic, Compare>,
+    placements: &mut Vec<PackedLocation>,
+) -> bool
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+{
+    if ctx.options.contiguous_groups && group.len() > 1 {
+        let rects: Vec<&RectToInsert> = group
+            .iter()
+            .map(|rect_to_place_id| &rects_to_place.rects[rect_to_place_id])
+            .collect();
+
+        return match bin.place_contiguous_group(&rects, ctx, split_rank) {
+            Some(locations) => {
+                placements.extend(locations);
+                true
+            }
+            None => false,
+        };
+    }
 
-        match pack_rects(
-            &groups,
-            &mut targets,
-            &volume_heuristic,
-            &contains_smallest_box,
-        )
-        .unwrap_err()
-        {
-            RectanglePackError::NotEnoughBinSpace => {}
+    let first_placement = placements.len();
+    let placed = group.iter().all(|rect_to_place_id| {
+        match bin.place_with_split_rank(&rects_to_place.rects[rect_to_place_id], ctx, split_rank) {
+            Some(placement) => {
+                placements.push(placement);
+                true
+            }
+            None => false,
+        }
+    });
+    if !placed {
+        placements.truncate(first_placement);
+    }
+
+    placed
+}
+
+/// The sum of the sizes of the smallest sections that each of the group's rectangles can fit in,
+/// as they get placed into the bin one after another.
+///
+/// Lower means a tighter fit. Returns `None` if the group does not fit.
+fn tightest_fit<RectToPlaceId, GroupId, Heuristic, Compare>(
+    bin: &TargetBin,
+    trial_bin: &mut TargetBin,
+    group: &[RectToPlaceId],
+    rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    ctx: &PackContext<Heuristic, Compare>,
+) -> Option<u128>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+{
+    bin.clone_without_metrics_into(trial_bin);
+
+    group
+        .iter()
+        .map(|rect_to_place_id| {
+            let rect = &rects_to_place.rects[rect_to_place_id];
+
+            let section_size = trial_bin.smallest_section_that_fits(rect, ctx)?;
+            trial_bin.place(rect, ctx)?;
+
+            Some(section_size)
+        })
+        .sum()
+}
+
+/// The split rank to place the group with so that the most of the upcoming rectangles can fit
+/// into the bin afterwards, measured using the box size heuristic.
+///
+/// Ties go to the better ranked split.
+#[allow(clippy::too_many_arguments)]
+fn split_rank_with_best_lookahead<RectToPlaceId, GroupId, Heuristic, Compare>(
+    bin: &TargetBin,
+    trial_bin: &mut TargetBin,
+    group: &[RectToPlaceId],
+    upcoming: &[&RectToInsert],
+    rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    ctx: &PackContext<Heuristic, Compare>,
+    placements: &mut Vec<PackedLocation>,
+) -> usize
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+{
+    (0..SPLIT_CONFIGURATION_COUNT)
+        .filter_map(|split_rank| {
+            bin.clone_without_metrics_into(trial_bin);
+
+            let first_placement = placements.len();
+            let placed = place_group_into_bin(
+                trial_bin,
+                group,
+                rects_to_place,
+                split_rank,
+                ctx,
+                placements,
+            );
+            placements.truncate(first_placement);
+            if !placed {
+                return None;
+            }
+
+            let utilization: u128 = upcoming
+                .iter()
+                .filter(|rect| trial_bin.place(rect, ctx).is_some())
+                .map(|rect| ctx.box_size_heuristic.box_size(rect.whd))
+                .sum();
+
+            Some((split_rank, utilization))
+        })
+        .max_by_key(|(split_rank, utilization)| (*utilization, core::cmp::Reverse(*split_rank)))
+        .map(|(split_rank, _)| split_rank)
+        .unwrap_or(0)
+}
+
+/// Information about successfully packed rectangles.
+#[derive(Debug, PartialEq)]
+pub struct RectanglePackOk<RectToPlaceId: PartialEq + Eq + Hash, BinId: PartialEq + Eq + Hash> {
+    packed_locations: KeyValMap<RectToPlaceId, (BinId, PackedLocation)>,
+    omitted_optional_rects: Vec<RectToPlaceId>,
+    // TODO: Other information such as information about how the bins were packed
+    // (perhaps percentage filled)
+}
+
+impl<RectToPlaceId: PartialEq + Eq + Hash, BinId: PartialEq + Eq + Hash>
+    RectanglePackOk<RectToPlaceId, BinId>
+{
+    /// Indicates where every incoming rectangle was placed
+    pub fn packed_locations(&self) -> &KeyValMap<RectToPlaceId, (BinId, PackedLocation)> {
+        &self.packed_locations
+    }
+
+    /// The bin and location that the rectangle was placed at, if it was placed.
+    ///
+    /// Also available by indexing, as `packed[&rect_to_place_id]`, which panics if the rectangle
+    /// wasn't placed.
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace,
+    ///     RectToInsert, TargetBin,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    /// rects_to_place.push_rect("grass", None, RectToInsert::new(8, 8, 1));
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert("atlas", TargetBin::new(16, 16, 1));
+    ///
+    /// let packed = pack_rects(
+    ///     &rects_to_place,
+    ///     &mut target_bins,
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(packed.len(), 1);
+    /// assert!(packed.contains(&"grass"));
+    /// assert_eq!(packed.get(&"grass"), Some(&packed[&"grass"]));
+    /// assert_eq!(packed.get(&"stone"), None);
+    /// ```
+    pub fn get(&self, rect_to_place_id: &RectToPlaceId) -> Option<&(BinId, PackedLocation)>
+    where
+        RectToPlaceId: Ord,
+    {
+        self.packed_locations.get(rect_to_place_id)
+    }
+
+    /// Whether the rectangle was placed.
+    pub fn contains(&self, rect_to_place_id: &RectToPlaceId) -> bool
+    where
+        RectToPlaceId: Ord,
+    {
+        self.packed_locations.contains_key(rect_to_place_id)
+    }
+
+    /// The number of rectangles that were placed.
+    pub fn len(&self) -> usize {
+        self.packed_locations.len()
+    }
+
+    /// Whether no rectangles were placed.
+    pub fn is_empty(&self) -> bool {
+        self.packed_locations.is_empty()
+    }
+
+    /// The placements of [`RectanglePackOk.packed_locations`], taken by value so that they can be
+    /// stored without cloning them.
+    ///
+    /// [`RectanglePackOk.packed_locations`]: RectanglePackOk::packed_locations
+    pub fn into_locations(self) -> KeyValMap<RectToPlaceId, (BinId, PackedLocation)> {
+        self.packed_locations
+    }
+
+    /// The [`PackedLocation.uv_rect`] of every placement, within the bin that it was placed
+    /// into.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a placement's bin isn't in the `target_bins`.
+    ///
+    /// [`PackedLocation.uv_rect`]: PackedLocation::uv_rect
+    pub fn uv_rects(
+        &self,
+        target_bins: &BTreeMap<BinId, TargetBin>,
+    ) -> KeyValMap<&RectToPlaceId, [f32; 4]>
+    where
+        RectToPlaceId: Ord,
+        BinId: Ord,
+    {
+        self.packed_locations
+            .iter()
+            .map(|(rect_to_place_id, (bin_id, location))| {
+                let bin = target_bins[bin_id].dimensions();
+                (rect_to_place_id, location.uv_rect(bin.width, bin.height))
+            })
+            .collect()
+    }
+
+    /// The [`RectToInsert.with_optional`] rectangles that were left out because there wasn't
+    /// enough space left for them, in ascending order.
+    ///
+    /// [`RectToInsert.with_optional`]: RectToInsert::with_optional
+    pub fn omitted_optional_rects(&self) -> &[RectToPlaceId] {
+        &self.omitted_optional_rects
+    }
+
+    /// Where every rectangle that was placed into the given bin was placed, in no particular
+    /// order, such as for drawing one texture per bin.
+    pub fn locations_in_bin<'a>(
+        &'a self,
+        bin_id: &'a BinId,
+    ) -> impl Iterator<Item = (&'a RectToPlaceId, &'a PackedLocation)> + 'a {
+        self.packed_locations
+            .iter()
+            .filter(move |(_, (placed_bin_id, _))| placed_bin_id == bin_id)
+            .map(|(rect_to_place_id, (_, location))| (rect_to_place_id, location))
+    }
+
+    /// The ids of the bins that at least one rectangle was placed into, in ascending order and
+    /// without duplicates, such as for deciding which of the bins need a texture allocated.
+    pub fn bins_used(&self) -> Vec<&BinId>
+    where
+        BinId: Ord,
+    {
+        let mut bins_used: Vec<&BinId> = self
+            .packed_locations
+            .values()
+            .map(|(bin_id, _)| bin_id)
+            .collect();
+        bins_used.sort();
+        bins_used.dedup();
+
+        bins_used
+    }
+}
+
+impl<RectToPlaceId: PartialEq + Eq + Hash + Ord, BinId: PartialEq + Eq + Hash> Index<&RectToPlaceId>
+    for RectanglePackOk<RectToPlaceId, BinId>
+{
+    type Output = (BinId, PackedLocation);
+
+    /// The bin and location that the rectangle was placed at, see [`RectanglePackOk.get`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rectangle wasn't placed.
+    ///
+    /// [`RectanglePackOk.get`]: RectanglePackOk::get
+    fn index(&self, rect_to_place_id: &RectToPlaceId) -> &Self::Output {
+        &self.packed_locations[rect_to_place_id]
+    }
+}
+
+impl<RectToPlaceId: PartialEq + Eq + Hash, BinId: PartialEq + Eq + Hash> IntoIterator
+    for RectanglePackOk<RectToPlaceId, BinId>
+{
+    type Item = (RectToPlaceId, (BinId, PackedLocation));
+    type IntoIter = KeyValMapIntoIter<RectToPlaceId, (BinId, PackedLocation)>;
+
+    /// Iterate over the placements by value, see [`RectanglePackOk.into_locations`].
+    ///
+    /// [`RectanglePackOk.into_locations`]: RectanglePackOk::into_locations
+    fn into_iter(self) -> Self::IntoIter {
+        self.packed_locations.into_iter()
+    }
+}
+
+impl<RectToPlaceId: PartialEq + Eq + Hash, BinId: PartialEq + Eq + Hash> Default
+    for RectanglePackOk<RectToPlaceId, BinId>
+{
+    /// A result with no placements, for passing to [`pack_rects_reusing`].
+    fn default() -> Self {
+        RectanglePackOk {
+            packed_locations: KeyValMap::default(),
+            omitted_optional_rects: Vec::new(),
+        }
+    }
+}
+
+/// An error while attempting to pack rectangles into bins.
+///
+/// New kinds of errors may be added in minor releases, so matching on this needs a wildcard arm.
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub enum RectanglePackError {
+    /// The rectangles can't be placed into the bins. More bin space needs to be provided.
+    ///
+    /// The ids are formatted using their [`Debug`] implementations, since errors aren't generic
+    /// over them. They are `None` when they aren't known, such as for a rectangle that was pushed
+    /// without any groups, or when the rectangles' combined volume is larger than the free volume
+    /// of the bins, in which case nothing is placed.
+    NotEnoughBinSpace {
+        /// The rectangle that couldn't be placed. For a group, this is the first of its
+        /// rectangles that doesn't fit into any bin even on its own, or else its first rectangle.
+        rect_to_place_id: Option<String>,
+        /// The group that couldn't be placed.
+        group_id: Option<String>,
+        /// The combined volume of the rectangles that weren't placed, not counting optional
+        /// rectangles (see [`RectToInsert.with_optional`]).
+        ///
+        /// [`RectToInsert.with_optional`]: RectToInsert::with_optional
+        required_volume: u128,
+        /// The combined free volume (see [`TargetBin.free_volume`]) of the bins that aren't
+        /// frozen (see [`TargetBin.freeze`]) when packing gave up. Frozen bins don't take any
+        /// new rectangles, so their free volume isn't counted. Free space is often split into
+        /// pieces that are each too small, so this can be larger than the required volume.
+        ///
+        /// [`TargetBin.free_volume`]: TargetBin::free_volume
+        /// [`TargetBin.freeze`]: TargetBin::freeze
+        available_volume: u128,
+    },
+    /// More free sections would need to be examined than
+    /// [`PackOptions.with_max_sections_examined`] allows.
+    ///
+    /// [`PackOptions.with_max_sections_examined`]: PackOptions::with_max_sections_examined
+    SearchLimitExceeded,
+    /// The rectangles of a distinct bin set can't each be placed into a different bin.
+    ///
+    /// Either there aren't enough bins with space for them, or two of them share a group (or are
+    /// in groups that are tied together by a rectangle that was pushed with both group ids).
+    DistinctBinSetUnsatisfiable {
+        /// The index that [`GroupedRectsToPlace.push_distinct_bin_set`] returned for the set.
+        ///
+        /// [`GroupedRectsToPlace.push_distinct_bin_set`]: GroupedRectsToPlace::push_distinct_bin_set
+        distinct_bin_set: usize,
+    },
+    /// The rectangles would only fit by placing them into more bins than
+    /// [`PackOptions.with_max_bins`] allows.
+    ///
+    /// [`PackOptions.with_max_bins`]: PackOptions::with_max_bins
+    BinLimitExceeded {
+        /// The maximum number of bins that rectangles could be placed into.
+        max_bins: usize,
+        /// The combined volume of the rectangles that weren't placed, not counting optional
+        /// rectangles (see [`RectToInsert.with_optional`]). At least this much more space would
+        /// be needed within the allowed bins.
+        ///
+        /// [`RectToInsert.with_optional`]: RectToInsert::with_optional
+        unplaced_volume: u128,
+    },
+    /// A rectangle or bin that was passed into [`pack_rects_2d`] has a depth other than `1`.
+    ///
+    /// The ids are formatted using their [`Debug`] implementations. Only one of them is set.
+    ///
+    /// [`pack_rects_2d`]: crate::pack_rects_2d
+    NotFlat {
+        /// The rectangle that has a depth.
+        rect_to_place_id: Option<String>,
+        /// The bin that has a depth.
+        bin_id: Option<String>,
+    },
+    /// Two of the bins that were passed into [`pack_rects_into`] have the same id.
+    ///
+    /// [`pack_rects_into`]: crate::pack_rects_into
+    DuplicateBinId {
+        /// The id that more than one bin has, formatted using its [`Debug`] implementation.
+        bin_id: String,
+    },
+    /// A bin that was passed into [`pack_rects_with_mirrored_bins`] and one of its mirrors don't
+    /// have the same size, configuration and free sections.
+    ///
+    /// The ids are formatted using their [`Debug`] implementations.
+    ///
+    /// [`pack_rects_with_mirrored_bins`]: crate::pack_rects_with_mirrored_bins
+    MirrorLayoutMismatch {
+        /// The bin that is mirrored.
+        bin_id: String,
+        /// The mirror whose layout is different.
+        mirror_id: String,
+    },
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RectanglePackError {}
+
+impl Display for RectanglePackError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            RectanglePackError::NotEnoughBinSpace {
+                rect_to_place_id,
+                group_id,
+                required_volume,
+                available_volume,
+            } => {
+                f.write_str("Not enough space to place all of the rectangles.")?;
+                if let Some(rect_to_place_id) = rect_to_place_id {
+                    write!(f, " Rectangle {} doesn't fit", rect_to_place_id)?;
+                    if let Some(group_id) = group_id {
+                        write!(f, " along with the rest of group {}", group_id)?;
+                    }
+                    f.write_str(".")?;
+                }
+                write!(
+                    f,
+                    " Rectangles with a volume of {} were left over, with {} free.",
+                    required_volume, available_volume
+                )
+            }
+            RectanglePackError::SearchLimitExceeded => {
+                f.write_str("Examined too many free sections while placing the rectangles.")
+            }
+            RectanglePackError::DistinctBinSetUnsatisfiable { distinct_bin_set } => write!(
+                f,
+                "The rectangles in distinct bin set {} can't each be placed into a different bin.",
+                distinct_bin_set
+            ),
+            RectanglePackError::BinLimitExceeded {
+                max_bins,
+                unplaced_volume,
+            } => write!(
+                f,
+                "The rectangles don't fit into {} bins. Rectangles with a volume of {} were left over.",
+                max_bins, unplaced_volume
+            ),
+            RectanglePackError::NotFlat {
+                rect_to_place_id,
+                bin_id,
+            } => match (rect_to_place_id, bin_id) {
+                (Some(rect_to_place_id), _) => write!(
+                    f,
+                    "Rectangle {} has a depth, but only flat rectangles can be packed in 2D.",
+                    rect_to_place_id
+                ),
+                (None, Some(bin_id)) => write!(
+                    f,
+                    "Bin {} has a depth, but only flat bins can be packed into in 2D.",
+                    bin_id
+                ),
+                (None, None) => f.write_str("Only flat rectangles and bins can be packed in 2D."),
+            },
+            RectanglePackError::DuplicateBinId { bin_id } => {
+                write!(f, "More than one bin has the id {}.", bin_id)
+            }
+            RectanglePackError::MirrorLayoutMismatch { bin_id, mirror_id } => write!(
+                f,
+                "Bin {} and its mirror {} don't have the same layout.",
+                bin_id, mirror_id
+            ),
+        }
+    }
+}
+
+fn sort_bins_smallest_to_largest<BinId, Heuristic>(
+    bins: &mut [(&BinId, &mut TargetBin)],
+    box_size_heuristic: &Heuristic,
+) where
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+{
+    bins.sort_unstable_by_key(|bin| {
+        let size = box_size_heuristic.box_size(bin.1.dimensions());
+
+        (core::cmp::Reverse(bin.1.priority), size, bin.0)
+    });
+}
+
+fn sort_by_size_largest_to_smallest<Heuristic: BoxSizeHeuristic + ?Sized>(
+    items: &mut [BinSection],
+    box_size_heuristic: &Heuristic,
+) {
+    items.sort_by_key(|item| core::cmp::Reverse(box_size_heuristic.box_size(item.whd)));
+}
+
+/// What decides the order that the groups are placed in, gathered in a single pass over the
+/// rectangles of a group.
+struct GroupOrder {
+    /// Whether every rectangle in the group is optional.
+    all_optional: bool,
+    /// The highest priority of the rectangles in the group.
+    priority: u32,
+    /// The total value of the rectangles in the group.
+    value: u128,
+    /// The total size of the rectangles in the group, according to the box size heuristic.
+    size: u128,
+}
+
+impl GroupOrder {
+    fn new<GroupId, RectToPlaceId, Heuristic>(
+        inbound_ids: &[RectToPlaceId],
+        incoming_groups: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+        box_size_heuristic: &Heuristic,
+    ) -> Self
+    where
+        RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+        GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+        Heuristic: BoxSizeHeuristic + ?Sized,
+    {
+        let mut order = GroupOrder {
+            all_optional: true,
+            priority: 0,
+            value: 0,
+            size: 0,
         };
+
+        for inbound in inbound_ids {
+            let rect = &incoming_groups.rects[inbound];
+            order.all_optional &= rect.optional;
+            order.priority = order.priority.max(rect.priority);
+            order.value = order.value.saturating_add(rect.value as u128);
+            order.size = order
+                .size
+                .saturating_add(box_size_heuristic.box_size(rect.whd));
+        }
+
+        order
+    }
+
+    /// Compare the groups so that the group with the highest value per unit of size comes first,
+    /// for [`PackOptions.with_value_maximization`].
+    ///
+    /// [`PackOptions.with_value_maximization`]: crate::PackOptions::with_value_maximization
+    fn compare_value_density(&self, other: &GroupOrder) -> Ordering {
+        // self.value / self.size > other.value / other.size, without dividing.
+        other
+            .value
+            .saturating_mul(self.size)
+            .cmp(&self.value.saturating_mul(other.size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{pack_rects, volume_heuristic, RectToInsert, RectanglePackError, TargetBin};
+
+    use super::*;
+    use crate::packed_location::RotatedBy;
+    use alloc::sync::Arc;
+    use core::cmp::Ordering;
+
+    /// If the provided rectangles can't fit into the provided bins.
+    #[test]
+    fn error_if_the_rectangles_cannot_fit_into_target_bins() {
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, TargetBin::new(2, 100, 1));
+
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(RectToPlaceId::One, None, RectToInsert::new(3, 1, 1));
+
+        assert_eq!(
+            pack_rects(
+                &groups,
+                &mut targets,
+                &volume_heuristic,
+                &contains_smallest_box,
+            )
+            .unwrap_err(),
+            RectanglePackError::NotEnoughBinSpace {
+                rect_to_place_id: Some(String::from("One")),
+                group_id: None,
+                required_volume: 3,
+                available_volume: 200,
+            }
+        );
     }
 
     /// Rectangles in the same group need to be placed in the same bin.
@@ -407,16 +1761,48 @@ mod tests {
             RectToInsert::new(100, 100, 1),
         );
 
-        match pack_rects(
+        assert_eq!(
+            pack_rects(
+                &groups,
+                &mut targets,
+                &volume_heuristic,
+                &contains_smallest_box,
+            )
+            .unwrap_err(),
+            RectanglePackError::NotEnoughBinSpace {
+                rect_to_place_id: Some(String::from("One")),
+                group_id: Some(String::from(r#""A Group""#)),
+                required_volume: 20_000,
+                available_volume: 20_000,
+            }
+        );
+    }
+
+    /// Verify that the heuristics can be given as trait objects and boxed closures as well as
+    /// functions.
+    #[test]
+    fn heuristics_can_be_trait_objects() {
+        use alloc::boxed::Box;
+
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(RectToPlaceId::One, None, RectToInsert::new(2, 2, 1));
+
+        let box_size_heuristic: &BoxSizeHeuristicFn = &volume_heuristic;
+        let more_suitable_containers_fn: Box<ComparePotentialContainersFn> =
+            Box::new(|a, b, heuristic: &BoxSizeHeuristicFn| contains_smallest_box(a, b, heuristic));
+
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, TargetBin::new(5, 5, 1));
+
+        let packed = pack_rects(
             &groups,
             &mut targets,
-            &volume_heuristic,
-            &contains_smallest_box,
+            box_size_heuristic,
+            &more_suitable_containers_fn,
         )
-        .unwrap_err()
-        {
-            RectanglePackError::NotEnoughBinSpace => {}
-        };
+        .unwrap();
+
+        assert_eq!(packed.packed_locations.len(), 1);
     }
 
     /// If we provide a single inbound rectangle and a single bin - it should be placed into that
@@ -452,9 +1838,15 @@ mod tests {
                     height: 2,
                     depth: 1
                 },
+                allocated_whd: WidthHeightDepth::new(1, 2, 1),
                 x_axis_rotation: RotatedBy::ZeroDegrees,
                 y_axis_rotation: RotatedBy::ZeroDegrees,
                 z_axis_rotation: RotatedBy::ZeroDegrees,
+                flipped_horizontally: false,
+                flipped_vertically: false,
+                padding: 0,
+                content_hash: None,
+                origin: [0, 0, 0],
             }
         )
     }
@@ -492,9 +1884,15 @@ mod tests {
                     height: 2,
                     depth: 1
                 },
+                allocated_whd: WidthHeightDepth::new(2, 2, 1),
                 x_axis_rotation: RotatedBy::ZeroDegrees,
                 y_axis_rotation: RotatedBy::ZeroDegrees,
                 z_axis_rotation: RotatedBy::ZeroDegrees,
+                flipped_horizontally: false,
+                flipped_vertically: false,
+                padding: 0,
+                content_hash: None,
+                origin: [0, 0, 0],
             }
         )
     }
@@ -534,9 +1932,15 @@ mod tests {
                     height: 10,
                     depth: 1
                 },
+                allocated_whd: WidthHeightDepth::new(10, 10, 1),
                 x_axis_rotation: RotatedBy::ZeroDegrees,
                 y_axis_rotation: RotatedBy::ZeroDegrees,
                 z_axis_rotation: RotatedBy::ZeroDegrees,
+                flipped_horizontally: false,
+                flipped_vertically: false,
+                padding: 0,
+                content_hash: None,
+                origin: [0, 0, 0],
             }
         );
         assert_eq!(
@@ -550,9 +1954,15 @@ mod tests {
                     height: 5,
                     depth: 1
                 },
+                allocated_whd: WidthHeightDepth::new(5, 5, 1),
                 x_axis_rotation: RotatedBy::ZeroDegrees,
                 y_axis_rotation: RotatedBy::ZeroDegrees,
                 z_axis_rotation: RotatedBy::ZeroDegrees,
+                flipped_horizontally: false,
+                flipped_vertically: false,
+                padding: 0,
+                content_hash: None,
+                origin: [0, 0, 0],
             }
         )
     }
@@ -597,9 +2007,15 @@ mod tests {
                     height: 15,
                     depth: 1
                 },
+                allocated_whd: WidthHeightDepth::new(15, 15, 1),
                 x_axis_rotation: RotatedBy::ZeroDegrees,
                 y_axis_rotation: RotatedBy::ZeroDegrees,
                 z_axis_rotation: RotatedBy::ZeroDegrees,
+                flipped_horizontally: false,
+                flipped_vertically: false,
+                padding: 0,
+                content_hash: None,
+                origin: [0, 0, 0],
             }
         );
         assert_eq!(
@@ -613,9 +2029,15 @@ mod tests {
                     height: 20,
                     depth: 1
                 },
+                allocated_whd: WidthHeightDepth::new(20, 20, 1),
                 x_axis_rotation: RotatedBy::ZeroDegrees,
                 y_axis_rotation: RotatedBy::ZeroDegrees,
                 z_axis_rotation: RotatedBy::ZeroDegrees,
+                flipped_horizontally: false,
+                flipped_vertically: false,
+                padding: 0,
+                content_hash: None,
+                origin: [0, 0, 0],
             }
         )
     }
@@ -673,9 +2095,15 @@ mod tests {
                     height: 90,
                     depth: 1
                 },
+                allocated_whd: WidthHeightDepth::new(50, 90, 1),
                 x_axis_rotation: RotatedBy::ZeroDegrees,
                 y_axis_rotation: RotatedBy::ZeroDegrees,
                 z_axis_rotation: RotatedBy::ZeroDegrees,
+                flipped_horizontally: false,
+                flipped_vertically: false,
+                padding: 0,
+                content_hash: None,
+                origin: [0, 0, 0],
             }
         );
         assert_eq!(
@@ -689,9 +2117,15 @@ mod tests {
                     height: 1,
                     depth: 1
                 },
+                allocated_whd: WidthHeightDepth::new(1, 1, 1),
                 x_axis_rotation: RotatedBy::ZeroDegrees,
                 y_axis_rotation: RotatedBy::ZeroDegrees,
                 z_axis_rotation: RotatedBy::ZeroDegrees,
+                flipped_horizontally: false,
+                flipped_vertically: false,
+                padding: 0,
+                content_hash: None,
+                origin: [0, 0, 0],
             }
         );
     }
@@ -751,9 +2185,15 @@ mod tests {
                     height: 95,
                     depth: 1
                 },
+                allocated_whd: WidthHeightDepth::new(60, 95, 1),
                 x_axis_rotation: RotatedBy::ZeroDegrees,
                 y_axis_rotation: RotatedBy::ZeroDegrees,
                 z_axis_rotation: RotatedBy::ZeroDegrees,
+                flipped_horizontally: false,
+                flipped_vertically: false,
+                padding: 0,
+                content_hash: None,
+                origin: [0, 0, 0],
             }
         );
         assert_eq!(
@@ -767,9 +2207,15 @@ mod tests {
                     height: 10,
                     depth: 1
                 },
+                allocated_whd: WidthHeightDepth::new(40, 10, 1),
                 x_axis_rotation: RotatedBy::ZeroDegrees,
                 y_axis_rotation: RotatedBy::ZeroDegrees,
                 z_axis_rotation: RotatedBy::ZeroDegrees,
+                flipped_horizontally: false,
+                flipped_vertically: false,
+                padding: 0,
+                content_hash: None,
+                origin: [0, 0, 0],
             }
         );
         assert_eq!(
@@ -783,55 +2229,1335 @@ mod tests {
                     height: 3,
                     depth: 1
                 },
+                allocated_whd: WidthHeightDepth::new(60, 3, 1),
                 x_axis_rotation: RotatedBy::ZeroDegrees,
                 y_axis_rotation: RotatedBy::ZeroDegrees,
                 z_axis_rotation: RotatedBy::ZeroDegrees,
+                flipped_horizontally: false,
+                flipped_vertically: false,
+                padding: 0,
+                content_hash: None,
+                origin: [0, 0, 0],
             }
         );
     }
 
-    /// Create a handful of rectangles that need to be placed, with two of them in the same group
-    /// and the rest ungrouped.
-    /// Try placing them many times and verify that each time they are placed the exact same way.
+    /// Verify that a rectangle doesn't fit into neighboring free sections that are each too small
+    /// for it when free sections aren't merged.
     #[test]
-    fn deterministic_packing() {
-        let mut previous_packed = None;
+    fn free_sections_not_merged_by_default() {
+        let packed = pack_into_split_free_space(&PackOptions::new());
 
-        for _ in 0..5 {
-            let mut rects_to_place: GroupedRectsToPlace<&'static str, &str> =
-                GroupedRectsToPlace::new();
+        assert!(packed.is_err());
+    }
 
-            let mut target_bins = BTreeMap::new();
-            for bin_id in 0..5 {
-                target_bins.insert(bin_id, TargetBin::new(8, 8, 1));
-            }
+    /// If a bin's free space is split between neighboring sections that are each too small for a
+    /// rectangle, merging free sections allows the rectangle to be placed into the combined space.
+    #[test]
+    fn merges_free_sections_before_placing() {
+        let packed =
+            pack_into_split_free_space(&PackOptions::new().with_free_section_merging(true));
 
-            let rectangles = vec![
-                "some-rectangle-0",
-                "some-rectangle-1",
-                "some-rectangle-2",
-                "some-rectangle-3",
-                "some-rectangle-4",
-            ];
+        assert!(packed.is_ok());
+    }
 
-            for rect_id in rectangles.iter() {
-                rects_to_place.push_rect(rect_id, None, RectToInsert::new(4, 4, 1));
-            }
+    /// Verify that borrowing the rectangle ids places every rectangle in the same location as
+    /// cloning them.
+    #[test]
+    fn borrowed_result_matches_owned_result() {
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(RectToPlaceId::One, None, RectToInsert::new(10, 10, 1));
+        groups.push_rect(RectToPlaceId::Two, None, RectToInsert::new(5, 5, 1));
 
-            let packed = pack_rects(
-                &rects_to_place,
-                &mut target_bins.clone(),
-                &volume_heuristic,
-                &contains_smallest_box,
-            )
-            .unwrap();
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, TargetBin::new(20, 20, 1));
+
+        let owned = pack_rects(
+            &groups,
+            &mut targets.clone(),
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+        let borrowed = pack_rects_borrowed(
+            &groups,
+            &mut targets,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(borrowed.packed_locations.len(), 2);
+        for (rect_id, location) in borrowed.packed_locations.iter() {
+            assert_eq!(&owned.packed_locations[*rect_id], location);
+        }
+    }
+
+    /// Verify that the bottom left placement policy picks the lowest section, even if another
+    /// section would have been tried first.
+    #[test]
+    fn bottom_left_placement_policy() {
+        let mut bin = TargetBin::new(10, 10, 1);
+        bin.available_bin_sections = vec![
+            BinSection::new(5, 0, 0, WidthHeightDepth::new(5, 5, 1)),
+            BinSection::new(0, 5, 0, WidthHeightDepth::new(10, 5, 1)),
+        ];
+
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(RectToPlaceId::One, None, RectToInsert::new(3, 3, 1));
+
+        for (policy, expected) in [
+            (PlacementPolicy::FirstFit, (0, 5)),
+            (PlacementPolicy::BottomLeft, (5, 0)),
+            (PlacementPolicy::BestFit, (5, 0)),
+        ]
+        .iter()
+        {
+            let mut targets = BTreeMap::new();
+            targets.insert(BinId::Three, bin.clone());
+
+            let packed = pack_rects_with_options(
+                &groups,
+                &mut targets,
+                &volume_heuristic,
+                &contains_smallest_box,
+                &PackOptions::new().with_placement_policy(*policy),
+            )
+            .unwrap();
+
+            let location = packed.packed_locations[&RectToPlaceId::One].1;
+            assert_eq!((location.x, location.y), *expected);
+        }
+    }
+
+    /// Verify that elongated rectangles are placed into a section along the bin's edge even when a
+    /// section in the middle of the bin would have been tried first, while other rectangles are
+    /// not.
+    #[test]
+    fn edge_aligned_elongated_rects() {
+        let mut bin = TargetBin::new(10, 10, 1);
+        bin.available_bin_sections = vec![
+            BinSection::new(0, 0, 0, WidthHeightDepth::new(10, 2, 1)),
+            BinSection::new(2, 3, 0, WidthHeightDepth::new(6, 6, 1)),
+        ];
+
+        for (rect, min_aspect_ratio, expected) in [
+            (RectToInsert::new(6, 1, 1), None, (2, 3)),
+            (RectToInsert::new(6, 1, 1), Some(4), (0, 0)),
+            (RectToInsert::new(6, 2, 1), Some(4), (2, 3)),
+        ]
+        .iter()
+        {
+            let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+            groups.push_rect(RectToPlaceId::One, None, *rect);
+
+            let mut targets = BTreeMap::new();
+            targets.insert(BinId::Three, bin.clone());
+
+            let mut options = PackOptions::new();
+            if let Some(min_aspect_ratio) = min_aspect_ratio {
+                options = options.with_edge_aligned_elongated_rects(*min_aspect_ratio);
+            }
+
+            let packed = pack_rects_with_options(
+                &groups,
+                &mut targets,
+                &volume_heuristic,
+                &contains_smallest_box,
+                &options,
+            )
+            .unwrap();
+
+            let location = packed.packed_locations[&RectToPlaceId::One].1;
+            assert_eq!((location.x, location.y), *expected);
+        }
+    }
+
+    /// Verify that without backtracking or lookahead the 2x2 rectangle's best split leaves no
+    /// room for the 1x3 rectangle.
+    ///
+    /// The best split leaves a 1x2 section to the right of the 2x2 rectangle and a 3x1 section
+    /// above it, neither of which can hold the 1x3 rectangle.
+    #[test]
+    fn unlucky_split_leaves_no_room() {
+        let packed = pack_after_unlucky_split(&PackOptions::new());
+
+        assert!(packed.is_err());
+    }
+
+    /// Verify that backtracking undoes an unlucky split so that the next rectangle can fit.
+    ///
+    /// See `unlucky_split_leaves_no_room` for the layout.
+    #[test]
+    fn backtracking_retries_alternative_splits() {
+        let packed = pack_after_unlucky_split(&PackOptions::new().with_backtracking(1)).unwrap();
+
+        assert_eq!(packed.packed_locations.len(), 2);
+        assert_eq!(packed.packed_locations[&RectToPlaceId::Two].1.x, 2);
+    }
+
+    /// Verify that once a bin reaches the target fill its layout is no longer undone by
+    /// backtracking.
+    ///
+    /// See `unlucky_split_leaves_no_room` for the layout. The 2x2 rectangle fills 44% of the bin.
+    #[test]
+    fn target_fill_stops_backtracking() {
+        let options = PackOptions::new().with_backtracking(1).with_target_fill(40);
+
+        let packed = pack_after_unlucky_split(&options);
+
+        assert!(packed.is_err());
+    }
+
+    /// Verify that a bin that hasn't reached the target fill can still be backtracked into.
+    #[test]
+    fn backtracking_below_target_fill() {
+        let options = PackOptions::new().with_backtracking(1).with_target_fill(50);
+
+        let packed = pack_after_unlucky_split(&options);
+
+        assert!(packed.is_ok());
+    }
+
+    /// Verify that looking ahead at the next rectangle avoids a split that would leave no room
+    /// for it.
+    ///
+    /// See `unlucky_split_leaves_no_room` for the layout.
+    #[test]
+    fn lookahead_picks_split_that_fits_upcoming_rect() {
+        let packed = pack_after_unlucky_split(&PackOptions::new().with_lookahead(1)).unwrap();
+
+        assert_eq!(packed.packed_locations[&RectToPlaceId::Two].1.x, 2);
+    }
+
+    /// Verify that two-phase packing puts large rectangles into whichever bin fits them most
+    /// tightly, while small rectangles still go into the first bin that fits them.
+    #[test]
+    fn two_phase_packing() {
+        let mut larger_bin = TargetBin::new(20, 20, 1);
+        larger_bin.available_bin_sections =
+            vec![BinSection::new(0, 0, 0, WidthHeightDepth::new(6, 6, 1))];
+
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(RectToPlaceId::One, None, RectToInsert::new(6, 6, 1));
+        groups.push_rect(RectToPlaceId::Two, None, RectToInsert::new(2, 2, 1));
+
+        for (options, expected_bin_for_large) in [
+            (PackOptions::new(), BinId::Three),
+            (PackOptions::new().with_two_phase(10), BinId::Four),
+        ]
+        .iter()
+        {
+            let mut targets = BTreeMap::new();
+            targets.insert(BinId::Three, TargetBin::new(10, 10, 1));
+            targets.insert(BinId::Four, larger_bin.clone());
+
+            let packed = pack_rects_with_options(
+                &groups,
+                &mut targets,
+                &volume_heuristic,
+                &contains_smallest_box,
+                options,
+            )
+            .unwrap();
+
+            assert_eq!(
+                packed.packed_locations[&RectToPlaceId::One].0,
+                *expected_bin_for_large
+            );
+            assert_eq!(packed.packed_locations[&RectToPlaceId::Two].0, BinId::Three);
+        }
+    }
+
+    /// Verify that packing stops with an error once too many free sections have been examined.
+    #[test]
+    fn error_if_search_limit_exceeded() {
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        for rect_id in 0..10 {
+            groups.push_rect(rect_id, None, RectToInsert::new(1, 1, 1));
+        }
+
+        for (max_sections_examined, expected) in [
+            (5, Err(RectanglePackError::SearchLimitExceeded)),
+            (1_000, Ok(10)),
+        ]
+        .iter()
+        {
+            let mut targets = BTreeMap::new();
+            targets.insert(BinId::Three, TargetBin::new(10, 10, 1));
+
+            let packed = pack_rects_with_options(
+                &groups,
+                &mut targets,
+                &volume_heuristic,
+                &contains_smallest_box,
+                &PackOptions::new().with_max_sections_examined(*max_sections_examined),
+            );
+
+            assert_eq!(
+                &packed.map(|packed| packed.packed_locations.len()),
+                expected
+            );
+        }
+    }
+
+    /// Verify that a rectangle that is allowed to rotate gets placed sideways when it only fits
+    /// that way.
+    #[test]
+    fn rotates_rect_about_z_axis_when_allowed() {
+        for (allow_rotation, expected) in
+            [(false, None), (true, Some(RotatedBy::NinetyDegrees))].iter()
+        {
+            let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+            groups.push_rect(
+                RectToPlaceId::One,
+                None,
+                RectToInsert::new(2, 4, 1).with_global_z_axis_rotation(*allow_rotation),
+            );
+
+            let mut targets = BTreeMap::new();
+            targets.insert(BinId::Three, TargetBin::new(4, 2, 1));
+
+            let packed = pack_rects(
+                &groups,
+                &mut targets,
+                &volume_heuristic,
+                &contains_smallest_box,
+            );
+
+            let rotation = packed
+                .ok()
+                .map(|packed| packed.packed_locations[&RectToPlaceId::One].1);
+            assert_eq!(
+                rotation.map(|location| location.z_axis_rotation()),
+                *expected
+            );
+
+            if let Some(location) = rotation {
+                assert_eq!((location.width(), location.height()), (2, 4));
+                assert!(location.is_rotated());
+                assert_eq!(location.effective_whd(), WidthHeightDepth::new(4, 2, 1));
+                assert!(targets[&BinId::Three].available_bin_sections.is_empty());
+            }
+        }
+    }
+
+    /// Verify that a box that only fits after rotating it about more than one axis gets placed,
+    /// and that the rotations are recorded.
+    #[test]
+    fn rotates_box_about_multiple_axes_when_allowed() {
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(
+            RectToPlaceId::One,
+            None,
+            RectToInsert::new(1, 2, 3)
+                .with_global_x_axis_rotation(true)
+                .with_global_y_axis_rotation(true),
+        );
+
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, TargetBin::new(2, 3, 1));
+
+        let packed = pack_rects(
+            &groups,
+            &mut targets,
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+        let location = packed.packed_locations[&RectToPlaceId::One].1;
+
+        assert_eq!(
+            (
+                location.x_axis_rotation(),
+                location.y_axis_rotation(),
+                location.z_axis_rotation()
+            ),
+            (
+                RotatedBy::NinetyDegrees,
+                RotatedBy::NinetyDegrees,
+                RotatedBy::ZeroDegrees
+            )
+        );
+        assert_eq!(
+            (location.width(), location.height(), location.depth()),
+            (1, 2, 3)
+        );
+        assert!(targets[&BinId::Three].available_bin_sections.is_empty());
+    }
+
+    /// Verify that the orientation comparator can choose a rotated orientation even though the
+    /// unrotated one fits.
+    #[test]
+    fn orientation_comparator_picks_orientation() {
+        #[derive(Debug)]
+        struct PreferRotated;
+
+        impl CompareOrientations for PreferRotated {
+            fn compare(
+                &self,
+                a: &OrientationCandidate,
+                b: &OrientationCandidate,
+                _box_size_heuristic: &BoxSizeHeuristicFn,
+            ) -> Ordering {
+                let is_rotated = |candidate: &OrientationCandidate| {
+                    candidate.location().z_axis_rotation() == RotatedBy::NinetyDegrees
+                };
+
+                is_rotated(b).cmp(&is_rotated(a))
+            }
+        }
+
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(
+            RectToPlaceId::One,
+            None,
+            RectToInsert::new(2, 3, 1).with_global_z_axis_rotation(true),
+        );
+
+        for (options, expected) in [
+            (PackOptions::new(), RotatedBy::ZeroDegrees),
+            (
+                PackOptions::new().with_orientation_comparator(Arc::new(PreferRotated)),
+                RotatedBy::NinetyDegrees,
+            ),
+        ]
+        .iter()
+        {
+            let mut targets = BTreeMap::new();
+            targets.insert(BinId::Three, TargetBin::new(4, 4, 1));
+
+            let packed = pack_rects_with_options(
+                &groups,
+                &mut targets,
+                &volume_heuristic,
+                &contains_smallest_box,
+                options,
+            )
+            .unwrap();
+
+            assert_eq!(
+                packed.packed_locations[&RectToPlaceId::One]
+                    .1
+                    .z_axis_rotation(),
+                *expected
+            );
+        }
+    }
+
+    /// Verify that rectangles are only flipped when allowed to be and when the orientation
+    /// comparator prefers it.
+    #[test]
+    fn orientation_comparator_picks_flip() {
+        #[derive(Debug)]
+        struct PreferFlipped;
+
+        impl CompareOrientations for PreferFlipped {
+            fn compare(
+                &self,
+                a: &OrientationCandidate,
+                b: &OrientationCandidate,
+                _box_size_heuristic: &BoxSizeHeuristicFn,
+            ) -> Ordering {
+                let flips = |candidate: &OrientationCandidate| {
+                    candidate.location().flipped_horizontally() as u8
+                        + candidate.location().flipped_vertically() as u8
+                };
+
+                flips(b).cmp(&flips(a))
+            }
+        }
+
+        for (allow_flip, options, expected) in [
+            (true, PackOptions::new(), false),
+            (
+                false,
+                PackOptions::new().with_orientation_comparator(Arc::new(PreferFlipped)),
+                false,
+            ),
+            (
+                true,
+                PackOptions::new().with_orientation_comparator(Arc::new(PreferFlipped)),
+                true,
+            ),
+        ]
+        .iter()
+        {
+            let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+            groups.push_rect(
+                RectToPlaceId::One,
+                None,
+                RectToInsert::new(2, 3, 1).with_vertical_flip(*allow_flip),
+            );
+
+            let mut targets = BTreeMap::new();
+            targets.insert(BinId::Three, TargetBin::new(4, 4, 1));
+
+            let packed = pack_rects_with_options(
+                &groups,
+                &mut targets,
+                &volume_heuristic,
+                &contains_smallest_box,
+                options,
+            )
+            .unwrap();
+            let location = packed.packed_locations[&RectToPlaceId::One].1;
+
+            assert_eq!(location.flipped_vertically(), *expected);
+            assert!(!location.flipped_horizontally());
+        }
+    }
+
+    /// Verify that padding is reserved around rectangles, that a rectangle's own padding overrides
+    /// the global padding and that the reported location leaves out the padding, even when
+    /// rotated.
+    #[test]
+    fn reserves_padding_around_rects() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect("padded", None, RectToInsert::new(3, 4, 1));
+        rects_to_place.push_rect("unpadded", None, RectToInsert::new(5, 6, 1).with_padding(0));
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert(0, TargetBin::new(10, 6, 1));
+
+        let packed = pack_rects_with_options(
+            &rects_to_place,
+            &mut target_bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::new().with_padding(1),
+        )
+        .unwrap();
+
+        let (_, padded) = packed.packed_locations()["padded"];
+        assert_eq!((padded.x(), padded.y()), (6, 1));
+        assert_eq!(
+            (padded.width(), padded.height(), padded.padding()),
+            (3, 4, 1)
+        );
+        assert_eq!(
+            target_bins[&0].section_occupied_by(&padded),
+            BinSection::new(5, 0, 0, WidthHeightDepth::new(5, 6, 1))
+        );
+        assert_eq!(packed.packed_locations()["unpadded"].1.x(), 0);
+
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect(
+            "rotated",
+            None,
+            RectToInsert::new(4, 2, 1)
+                .with_padding(1)
+                .with_global_z_axis_rotation(true),
+        );
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert(0, TargetBin::new(4, 6, 1));
+
+        let packed = pack_rects(
+            &rects_to_place,
+            &mut target_bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+
+        let (_, rotated) = packed.packed_locations()["rotated"];
+        assert_eq!((rotated.x(), rotated.y()), (1, 1));
+        assert_eq!(rotated.effective_whd(), WidthHeightDepth::new(2, 4, 1));
+    }
+
+    /// Verify that sizes are rounded up before the padding is added, and that the location reports
+    /// both the requested and the allocated size.
+    #[test]
+    fn rounds_sizes_up_before_padding() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect("icon", None, RectToInsert::new(10, 7, 1));
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert(0, TargetBin::new(14, 10, 1));
+
+        let packed = pack_rects_with_options(
+            &rects_to_place,
+            &mut target_bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::new()
+                .with_size_rounding(4, 4, 1)
+                .with_padding(1),
+        )
+        .unwrap();
+
+        let (_, location) = packed.packed_locations()["icon"];
+        assert_eq!((location.x(), location.y()), (1, 1));
+        assert_eq!((location.width(), location.height()), (10, 7));
+        assert_eq!(location.allocated_whd(), WidthHeightDepth::new(12, 8, 1));
+        assert_eq!(target_bins[&0].available_bin_sections().len(), 0);
+        assert_eq!(
+            target_bins[&0].section_occupied_by(&location),
+            BinSection::new(0, 0, 0, WidthHeightDepth::new(14, 10, 1))
+        );
+    }
+
+    /// Verify that packing into a cleared bin and a previous result gives the same placements as
+    /// packing from scratch, without keeping any of the previous placements.
+    #[test]
+    fn pack_reusing_cleared_bins_and_result() {
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert(
+            0,
+            TargetBin::new(8, 8, 1).with_size_classes(SizeClasses::new(vec![16])),
+        );
+
+        let mut packed = RectanglePackOk::default();
+
+        for rect_ids in [[0, 1], [2, 3]].iter() {
+            let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+            for rect_id in rect_ids.iter() {
+                rects_to_place.push_rect(*rect_id, None, RectToInsert::new(4, 8, 1));
+            }
+
+            let mut fresh_bins = target_bins.clone();
+            for bin in fresh_bins.values_mut() {
+                *bin = bin.emptied();
+            }
+            let expected = pack_rects(
+                &rects_to_place,
+                &mut fresh_bins,
+                &volume_heuristic,
+                &contains_smallest_box,
+            )
+            .unwrap();
+
+            target_bins.get_mut(&0).unwrap().clear();
+            pack_rects_reusing(
+                &rects_to_place,
+                &mut target_bins,
+                &volume_heuristic,
+                &contains_smallest_box,
+                &PackOptions::default(),
+                &mut packed,
+            )
+            .unwrap();
+
+            assert_eq!(packed, expected);
+            assert_eq!(
+                target_bins[&0].available_bin_sections,
+                fresh_bins[&0].available_bin_sections
+            );
+        }
+    }
+
+    /// Create a handful of rectangles that need to be placed, with two of them in the same group
+    /// and the rest ungrouped.
+    /// Try placing them many times and verify that each time they are placed the exact same way.
+    #[test]
+    #[allow(clippy::useless_vec)]
+    fn deterministic_packing() {
+        let mut previous_packed = None;
+
+        for _ in 0..5 {
+            let mut rects_to_place: GroupedRectsToPlace<&'static str, &str> =
+                GroupedRectsToPlace::new();
+
+            let mut target_bins = BTreeMap::new();
+            for bin_id in 0..5 {
+                target_bins.insert(bin_id, TargetBin::new(8, 8, 1));
+            }
+
+            let rectangles = vec![
+                "some-rectangle-0",
+                "some-rectangle-1",
+                "some-rectangle-2",
+                "some-rectangle-3",
+                "some-rectangle-4",
+            ];
+
+            for rect_id in rectangles.iter() {
+                rects_to_place.push_rect(rect_id, None, RectToInsert::new(4, 4, 1));
+            }
+
+            let packed = pack_rects(
+                &rects_to_place,
+                &mut target_bins.clone(),
+                &volume_heuristic,
+                &contains_smallest_box,
+            )
+            .unwrap();
 
             if let Some(previous_packed) = previous_packed.as_ref() {
                 assert_eq!(&packed, previous_packed);
             }
 
-            previous_packed = Some(packed);
+            previous_packed = Some(packed);
+        }
+    }
+
+    /// Verify that a larger optional rectangle is placed after the required rectangles, so that
+    /// it gets left out instead of taking the space that a required rectangle needs.
+    #[test]
+    fn optional_rects_are_placed_last_and_omitted_when_they_do_not_fit() {
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(
+            RectToPlaceId::One,
+            None,
+            RectToInsert::new(6, 6, 1).with_optional(true),
+        );
+        groups.push_rect(RectToPlaceId::Two, None, RectToInsert::new(5, 5, 1));
+
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, TargetBin::new(6, 6, 1));
+
+        let packed = pack_rects(
+            &groups,
+            &mut targets,
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+
+        assert!(packed.packed_locations.contains_key(&RectToPlaceId::Two));
+        assert!(!packed.packed_locations.contains_key(&RectToPlaceId::One));
+        assert_eq!(packed.omitted_optional_rects(), &[RectToPlaceId::One]);
+    }
+
+    /// Verify that when maximizing value, two small rectangles that are worth more together are
+    /// placed instead of a larger one that would have been placed first by size.
+    #[test]
+    fn value_maximization_leaves_out_least_valuable_per_size() {
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(
+            RectToPlaceId::One,
+            None,
+            RectToInsert::new(6, 6, 1).with_value(10),
+        );
+        groups.push_rect(
+            RectToPlaceId::Two,
+            None,
+            RectToInsert::new(3, 3, 1).with_value(10),
+        );
+        groups.push_rect(
+            RectToPlaceId::Three,
+            None,
+            RectToInsert::new(3, 3, 1).with_value(10),
+        );
+
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, TargetBin::new(6, 6, 1));
+
+        let packed = pack_rects_with_options(
+            &groups,
+            &mut targets,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::new().with_value_maximization(true),
+        )
+        .unwrap();
+
+        assert!(packed.packed_locations.contains_key(&RectToPlaceId::Two));
+        assert!(packed.packed_locations.contains_key(&RectToPlaceId::Three));
+        assert_eq!(packed.omitted_optional_rects(), &[RectToPlaceId::One]);
+    }
+
+    /// Verify that when minimizing bin cost the cheapest bin is used even though it is larger,
+    /// and that it keeps being used instead of starting on another bin.
+    #[test]
+    fn bin_cost_minimization_prefers_cheap_and_used_bins() {
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(RectToPlaceId::One, None, RectToInsert::new(5, 5, 1));
+        groups.push_rect(RectToPlaceId::Two, None, RectToInsert::new(5, 5, 1));
+
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, TargetBin::new(10, 10, 1).with_cost(100));
+        targets.insert(BinId::Four, TargetBin::new(20, 20, 1).with_cost(1));
+
+        let packed = pack_rects_with_options(
+            &groups,
+            &mut targets,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::new().with_bin_cost_minimization(true),
+        )
+        .unwrap();
+
+        assert_eq!(packed.packed_locations[&RectToPlaceId::One].0, BinId::Four);
+        assert_eq!(packed.packed_locations[&RectToPlaceId::Two].0, BinId::Four);
+    }
+
+    /// Verify that a small rectangle with a higher priority gets placed before a larger one,
+    /// taking the only space that both of them fit into.
+    #[test]
+    fn higher_priority_rects_are_placed_first() {
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(RectToPlaceId::One, None, RectToInsert::new(6, 6, 1));
+        groups.push_rect(
+            RectToPlaceId::Two,
+            None,
+            RectToInsert::new(5, 5, 1).with_priority(1),
+        );
+
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, TargetBin::new(6, 6, 1));
+        targets.insert(BinId::Four, TargetBin::new(7, 7, 1));
+
+        let packed = pack_rects(
+            &groups,
+            &mut targets,
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+
+        assert_eq!(packed.packed_locations[&RectToPlaceId::Two].0, BinId::Three);
+        assert_eq!(packed.packed_locations[&RectToPlaceId::One].0, BinId::Four);
+    }
+
+    /// Verify that a rectangle that is pushed with two groups is only placed once and pulls both
+    /// groups into the same bin.
+    #[test]
+    fn rect_in_two_groups_ties_the_groups_together() {
+        let mut groups = GroupedRectsToPlace::new();
+        groups.push_rect(
+            RectToPlaceId::One,
+            Some(vec![0]),
+            RectToInsert::new(4, 4, 1),
+        );
+        groups.push_rect(
+            RectToPlaceId::Two,
+            Some(vec![1]),
+            RectToInsert::new(4, 4, 1),
+        );
+        groups.push_rect(
+            RectToPlaceId::Three,
+            Some(vec![0, 1]),
+            RectToInsert::new(4, 4, 1),
+        );
+
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, TargetBin::new(8, 8, 1));
+        targets.insert(BinId::Four, TargetBin::new(8, 8, 1));
+
+        let packed = pack_rects(
+            &groups,
+            &mut targets,
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+
+        for rect_id in [RectToPlaceId::One, RectToPlaceId::Two, RectToPlaceId::Three].iter() {
+            assert_eq!(packed.packed_locations[rect_id].0, BinId::Three);
+        }
+        assert_eq!(targets[&BinId::Three].placed_rect_count(), 3);
+
+        let distinct_bin_set =
+            groups.push_distinct_bin_set(vec![RectToPlaceId::One, RectToPlaceId::Two]);
+        assert_eq!(
+            pack_rects(
+                &groups,
+                &mut targets,
+                &volume_heuristic,
+                &contains_smallest_box,
+            ),
+            Err(RectanglePackError::DistinctBinSetUnsatisfiable { distinct_bin_set })
+        );
+    }
+
+    /// Verify that rectangles that only fit by using more bins than allowed fail with the volume
+    /// that was left over.
+    #[test]
+    fn max_bins_limits_the_bins_that_get_used() {
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(RectToPlaceId::One, None, RectToInsert::new(4, 4, 1));
+        groups.push_rect(RectToPlaceId::Two, None, RectToInsert::new(3, 3, 1));
+
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, TargetBin::new(4, 4, 1));
+        targets.insert(BinId::Four, TargetBin::new(4, 4, 1));
+
+        let pack = |options: &PackOptions| {
+            pack_rects_with_options(
+                &groups,
+                &mut targets.clone(),
+                &volume_heuristic,
+                &contains_smallest_box,
+                options,
+            )
+        };
+
+        assert!(pack(&PackOptions::new().with_max_bins(2)).is_ok());
+        assert_eq!(
+            pack(&PackOptions::new().with_max_bins(1)),
+            Err(RectanglePackError::BinLimitExceeded {
+                max_bins: 1,
+                unplaced_volume: 9
+            })
+        );
+    }
+
+    /// Verify that the rectangles of a distinct bin set are spread across the bins, and that a
+    /// set whose rectangles share a group is reported as unsatisfiable.
+    #[test]
+    fn distinct_bin_sets() {
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, TargetBin::new(10, 10, 1));
+        targets.insert(BinId::Four, TargetBin::new(10, 10, 1));
+
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(RectToPlaceId::One, None, RectToInsert::new(2, 2, 1));
+        groups.push_rect(RectToPlaceId::Two, None, RectToInsert::new(2, 2, 1));
+        groups.push_distinct_bin_set(vec![RectToPlaceId::One, RectToPlaceId::Two]);
+
+        let packed = pack_rects(
+            &groups,
+            &mut targets.clone(),
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+        assert_eq!(packed.packed_locations[&RectToPlaceId::One].0, BinId::Three);
+        assert_eq!(packed.packed_locations[&RectToPlaceId::Two].0, BinId::Four);
+
+        let mut groups = GroupedRectsToPlace::new();
+        groups.push_rect(
+            RectToPlaceId::One,
+            Some(vec![0]),
+            RectToInsert::new(2, 2, 1),
+        );
+        groups.push_rect(
+            RectToPlaceId::Two,
+            Some(vec![0]),
+            RectToInsert::new(2, 2, 1),
+        );
+        groups.push_distinct_bin_set(vec![RectToPlaceId::Three]);
+        let distinct_bin_set =
+            groups.push_distinct_bin_set(vec![RectToPlaceId::One, RectToPlaceId::Two]);
+
+        assert_eq!(
+            pack_rects(
+                &groups,
+                &mut targets,
+                &volume_heuristic,
+                &contains_smallest_box,
+            ),
+            Err(RectanglePackError::DistinctBinSetUnsatisfiable { distinct_bin_set })
+        );
+    }
+
+    /// Verify that the placements can be looked up by the bin that they were placed into.
+    #[test]
+    fn locations_in_bin() {
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, TargetBin::new(2, 2, 1));
+        targets.insert(BinId::Four, TargetBin::new(2, 2, 1));
+
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(RectToPlaceId::One, None, RectToInsert::new(2, 2, 1));
+        groups.push_rect(RectToPlaceId::Two, None, RectToInsert::new(2, 1, 1));
+        groups.push_rect(RectToPlaceId::Three, None, RectToInsert::new(2, 1, 1));
+
+        let packed = pack_rects(
+            &groups,
+            &mut targets,
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+
+        for bin_id in [BinId::Three, BinId::Four].iter() {
+            for (rect_to_place_id, location) in packed.locations_in_bin(bin_id) {
+                assert_eq!(
+                    packed.packed_locations()[rect_to_place_id],
+                    (*bin_id, *location)
+                );
+            }
+        }
+        assert_eq!(packed.locations_in_bin(&BinId::Three).count(), 1);
+        assert_eq!(packed.locations_in_bin(&BinId::Four).count(), 2);
+    }
+
+    /// Verify that the placements can be taken by value.
+    #[test]
+    fn into_locations() {
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, TargetBin::new(4, 4, 1));
+
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(String::from("a"), None, RectToInsert::new(2, 2, 1));
+        groups.push_rect(String::from("b"), None, RectToInsert::new(2, 2, 1));
+
+        let packed = pack_rects(
+            &groups,
+            &mut targets,
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+        let a = packed.packed_locations()["a"];
+
+        let mut owned: Vec<(String, (BinId, PackedLocation))> = packed.into_iter().collect();
+        owned.sort_by(|x, y| x.0.cmp(&y.0));
+
+        assert_eq!(owned.len(), 2);
+        assert_eq!(owned[0], (String::from("a"), a));
+    }
+
+    /// Verify that the texture coordinates are computed within each placement's own bin.
+    #[test]
+    fn uv_rects() {
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, TargetBin::new(4, 4, 1));
+        targets.insert(BinId::Four, TargetBin::new(8, 8, 1));
+
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(RectToPlaceId::One, None, RectToInsert::new(4, 4, 1));
+        groups.push_rect(RectToPlaceId::Two, None, RectToInsert::new(4, 4, 1));
+
+        let packed = pack_rects(
+            &groups,
+            &mut targets,
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+        let uv_rects = packed.uv_rects(&targets);
+
+        for (rect_to_place_id, (bin_id, _)) in packed.packed_locations().iter() {
+            let expected = match bin_id {
+                BinId::Three => [0., 0., 1., 1.],
+                BinId::Four => [0., 0., 0.5, 0.5],
+            };
+            assert_eq!(uv_rects[rect_to_place_id], expected);
+        }
+    }
+
+    /// Verify that only the bins that received a rectangle are reported as used.
+    #[test]
+    fn bins_used() {
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, TargetBin::new(2, 2, 1));
+        targets.insert(BinId::Four, TargetBin::new(20, 20, 1));
+
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(RectToPlaceId::One, None, RectToInsert::new(2, 2, 1));
+        groups.push_rect(RectToPlaceId::Two, None, RectToInsert::new(1, 1, 1));
+
+        let packed = pack_rects(
+            &groups,
+            &mut targets,
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+
+        assert_eq!(packed.bins_used(), vec![&BinId::Three, &BinId::Four]);
+
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(RectToPlaceId::One, None, RectToInsert::new(10, 10, 1));
+        groups.push_rect(RectToPlaceId::Two, None, RectToInsert::new(10, 10, 1));
+
+        let packed = pack_rects(
+            &groups,
+            &mut targets,
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+
+        assert_eq!(packed.bins_used(), vec![&BinId::Four]);
+    }
+
+    /// Verify that we fail without placing anything when the rectangles have more volume than the
+    /// bins have free, while optional rectangles don't count.
+    #[test]
+    fn error_if_total_volume_exceeds_free_volume() {
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, TargetBin::new(10, 10, 1));
+
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(RectToPlaceId::One, None, RectToInsert::new(8, 8, 1));
+        groups.push_rect(
+            RectToPlaceId::Two,
+            None,
+            RectToInsert::new(8, 8, 1).with_optional(true),
+        );
+        assert!(pack_rects(
+            &groups,
+            &mut targets.clone(),
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .is_ok());
+
+        groups.push_rect(RectToPlaceId::Three, None, RectToInsert::new(8, 8, 1));
+        assert_eq!(
+            pack_rects(
+                &groups,
+                &mut targets,
+                &volume_heuristic,
+                &contains_smallest_box,
+            )
+            .unwrap_err(),
+            RectanglePackError::NotEnoughBinSpace {
+                rect_to_place_id: None,
+                group_id: None,
+                required_volume: 128,
+                available_volume: 100,
+            }
+        );
+        assert_eq!(targets[&BinId::Three].free_volume(), 100);
+    }
+
+    /// Verify that the free volume of frozen bins isn't counted as available when a group fails
+    /// to be placed.
+    #[test]
+    fn frozen_bins_not_counted_as_available_volume() {
+        let mut frozen = TargetBin::new(10, 10, 1);
+        frozen.freeze();
+
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, TargetBin::new(4, 4, 1));
+        targets.insert(BinId::Four, frozen);
+
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(RectToPlaceId::One, None, RectToInsert::new(3, 3, 1));
+        groups.push_rect(RectToPlaceId::Two, None, RectToInsert::new(2, 2, 1));
+
+        assert_eq!(
+            pack_rects(
+                &groups,
+                &mut targets,
+                &volume_heuristic,
+                &contains_smallest_box,
+            )
+            .unwrap_err(),
+            RectanglePackError::NotEnoughBinSpace {
+                rect_to_place_id: Some(String::from("Two")),
+                group_id: None,
+                required_volume: 4,
+                available_volume: 7,
+            }
+        );
+    }
+
+    /// Verify that indexing by a rectangle that wasn't placed panics.
+    #[test]
+    #[should_panic]
+    fn index_panics_if_rect_not_placed() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect("placed", None, RectToInsert::new(2, 2, 1));
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert("bin", TargetBin::new(4, 4, 1));
+
+        let packed = pack_rects(
+            &rects_to_place,
+            &mut target_bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+
+        let _ = &packed[&"missing"];
+    }
+
+    /// Verify that empty rectangles are placed at the origin even into a full bin, without
+    /// changing its free sections.
+    #[test]
+    fn empty_rects_take_up_no_space() {
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, TargetBin::new(10, 10, 1));
+
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(RectToPlaceId::One, None, RectToInsert::new(10, 10, 1));
+        groups.push_rect(
+            RectToPlaceId::Two,
+            None,
+            RectToInsert::new(0, 12, 1).with_padding(1),
+        );
+
+        let packed = pack_rects(
+            &groups,
+            &mut targets,
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+
+        let (_, empty) = packed.packed_locations()[&RectToPlaceId::Two];
+        assert_eq!(
+            (empty.x(), empty.y(), empty.width(), empty.height()),
+            (0, 0, 0, 12)
+        );
+        assert!(targets[&BinId::Three].available_bin_sections().is_empty());
+        assert_eq!(targets[&BinId::Three].placed_rect_count(), 2);
+    }
+
+    /// Verify that empty rectangles are placed onto their layer and against their edge.
+    #[test]
+    fn empty_rects_placed_onto_layer_and_edge() {
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, TargetBin::new(10, 10, 4));
+
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(
+            RectToPlaceId::One,
+            None,
+            RectToInsert::new(0, 3, 1)
+                .with_layer(2)
+                .with_edge(BinEdge::Top),
+        );
+        groups.push_rect(
+            RectToPlaceId::Two,
+            None,
+            RectToInsert::new(4, 0, 1).with_edge(BinEdge::Right),
+        );
+
+        let packed = pack_rects(
+            &groups,
+            &mut targets,
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+
+        let (_, on_layer) = packed.packed_locations()[&RectToPlaceId::One];
+        assert_eq!((on_layer.x(), on_layer.y(), on_layer.z()), (0, 7, 2));
+        let (_, on_edge) = packed.packed_locations()[&RectToPlaceId::Two];
+        assert_eq!((on_edge.x(), on_edge.y(), on_edge.z()), (6, 0, 0));
+    }
+
+    /// Verify that empty rectangles are left out when their layer is outside of the bin, or the
+    /// position that they would be placed at is past their max x or max y.
+    #[test]
+    fn empty_rects_outside_of_constraints_not_placed() {
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, TargetBin::new(10, 10, 4));
+
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(
+            "past max x",
+            None,
+            RectToInsert::new(4, 0, 1)
+                .with_edge(BinEdge::Right)
+                .with_max_x(8)
+                .with_optional(true),
+        );
+        groups.push_rect(
+            "past max y",
+            None,
+            RectToInsert::new(0, 3, 1).with_max_y(2).with_optional(true),
+        );
+        groups.push_rect(
+            "outside of bin",
+            None,
+            RectToInsert::new(0, 3, 1).with_layer(4).with_optional(true),
+        );
+        groups.push_rect(
+            "within max x",
+            None,
+            RectToInsert::new(4, 0, 1).with_max_x(4),
+        );
+
+        let packed = pack_rects(
+            &groups,
+            &mut targets,
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+
+        assert_eq!(
+            packed.omitted_optional_rects(),
+            &["outside of bin", "past max x", "past max y"]
+        );
+        assert!(packed.packed_locations().contains_key("within max x"));
+    }
+
+    /// Verify that empty rectangles are only placed where the placement validator accepts them.
+    #[test]
+    fn empty_rects_checked_by_placement_validator() {
+        #[derive(Debug)]
+        struct AwayFromOrigin;
+
+        impl ValidatePlacement for AwayFromOrigin {
+            fn is_valid(&self, location: &PackedLocation) -> bool {
+                location.x() > 0
+            }
         }
+
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, TargetBin::new(10, 10, 1));
+
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(
+            RectToPlaceId::One,
+            None,
+            RectToInsert::new(0, 3, 1).with_optional(true),
+        );
+        groups.push_rect(
+            RectToPlaceId::Two,
+            None,
+            RectToInsert::new(0, 3, 1).with_edge(BinEdge::Right),
+        );
+
+        let packed = pack_rects_with_options(
+            &groups,
+            &mut targets,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::new().with_placement_validator(Arc::new(AwayFromOrigin)),
+        )
+        .unwrap();
+
+        assert_eq!(packed.omitted_optional_rects(), &[RectToPlaceId::One]);
+        assert_eq!(packed.packed_locations()[&RectToPlaceId::Two].1.x(), 10);
+    }
+
+    /// Pack a 10x10 rectangle into a 10x10 bin whose free space is split into two 5x10 halves.
+    fn pack_into_split_free_space(
+        options: &PackOptions,
+    ) -> Result<RectanglePackOk<RectToPlaceId, BinId>, RectanglePackError> {
+        let mut bin = TargetBin::new(10, 10, 1);
+        bin.available_bin_sections = vec![
+            BinSection::new(0, 0, 0, WidthHeightDepth::new(5, 10, 1)),
+            BinSection::new(5, 0, 0, WidthHeightDepth::new(5, 10, 1)),
+        ];
+
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(RectToPlaceId::One, None, RectToInsert::new(10, 10, 1));
+
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, bin);
+
+        pack_rects_with_options(
+            &groups,
+            &mut targets,
+            &volume_heuristic,
+            &contains_smallest_box,
+            options,
+        )
+    }
+
+    /// Pack a 2x2 and then a 1x3 rectangle into a 3x3 bin, where the 2x2 rectangle's best split
+    /// leaves no room for the 1x3 one.
+    fn pack_after_unlucky_split(
+        options: &PackOptions,
+    ) -> Result<RectanglePackOk<RectToPlaceId, BinId>, RectanglePackError> {
+        let mut groups: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        groups.push_rect(RectToPlaceId::One, None, RectToInsert::new(2, 2, 1));
+        groups.push_rect(RectToPlaceId::Two, None, RectToInsert::new(1, 3, 1));
+
+        let mut targets = BTreeMap::new();
+        targets.insert(BinId::Three, TargetBin::new(3, 3, 1));
+
+        pack_rects_with_options(
+            &groups,
+            &mut targets,
+            &volume_heuristic,
+            &contains_smallest_box,
+            options,
+        )
     }
 
     #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
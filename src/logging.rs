@@ -0,0 +1,122 @@
+//! Events about packing decisions, forwarded to the [`log`] crate when the `log` feature is
+//! enabled.
+//!
+//! When the feature is disabled the messages are still type checked, but never formatted.
+//!
+//! [`log`]: https://docs.rs/log
+
+/// An event about which bin a group went into.
+#[cfg(feature = "log")]
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        ::log::debug!($($arg)+)
+    };
+}
+
+#[cfg(not(feature = "log"))]
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        if false {
+            let _ = core::format_args!($($arg)+);
+        }
+    };
+}
+
+/// An event about a single check or placement, of which there can be very many.
+#[cfg(feature = "log")]
+macro_rules! trace {
+    ($($arg:tt)+) => {
+        ::log::trace!($($arg)+)
+    };
+}
+
+#[cfg(not(feature = "log"))]
+macro_rules! trace {
+    ($($arg:tt)+) => {
+        if false {
+            let _ = core::format_args!($($arg)+);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    /// Verify that without the `log` feature the messages' arguments are never evaluated, so
+    /// logging costs nothing.
+    #[cfg(not(feature = "log"))]
+    #[test]
+    fn arguments_not_evaluated_without_log_feature() {
+        let mut evaluated = 0;
+        let mut evaluate = || {
+            evaluated += 1;
+            evaluated
+        };
+
+        debug!("group={}", evaluate());
+        trace!("rect={}", evaluate());
+
+        assert_eq!(evaluated, 0);
+    }
+
+    /// Verify that with the `log` feature the bin that a group goes into and the sections that
+    /// its rectangles are placed into are logged.
+    #[cfg(all(feature = "log", feature = "std"))]
+    #[test]
+    fn packing_decisions_are_logged() {
+        use crate::{
+            contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace, RectToInsert,
+            TargetBin,
+        };
+        use alloc::{collections::BTreeMap, string::String, vec::Vec};
+        use core::cell::RefCell;
+        use log::{Level, LevelFilter, Log, Metadata, Record};
+
+        std::thread_local! {
+            static MESSAGES: RefCell<Vec<(Level, String)>> = const { RefCell::new(Vec::new()) };
+        }
+
+        /// Keeps the messages of each test thread apart, since tests run in parallel.
+        struct ThreadLogger;
+
+        impl Log for ThreadLogger {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn log(&self, record: &Record<'_>) {
+                let message = (record.level(), alloc::format!("{}", record.args()));
+                MESSAGES.with(|messages| messages.borrow_mut().push(message));
+            }
+
+            fn flush(&self) {}
+        }
+
+        let _ = log::set_logger(&ThreadLogger);
+        log::set_max_level(LevelFilter::Trace);
+
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect("grass", None, RectToInsert::new(5, 5, 1));
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert("atlas", TargetBin::new(10, 10, 1));
+
+        pack_rects(
+            &rects_to_place,
+            &mut target_bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+
+        let messages = MESSAGES.with(|messages| messages.take());
+        assert!(messages
+            .iter()
+            .any(|(level, message)| *level == Level::Debug
+                && message.starts_with("placing group into bin;")
+                && message.contains("bin=\"atlas\"")));
+        assert!(messages
+            .iter()
+            .any(|(level, message)| *level == Level::Trace
+                && message.starts_with("placed rect into section;")));
+    }
+}
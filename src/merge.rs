@@ -0,0 +1,157 @@
+//! Combining the results of packs that were run independently.
+
+use crate::RectanglePackOk;
+
+use alloc::collections::BTreeSet;
+use core::{
+    fmt::{Debug, Display, Error as FmtError, Formatter},
+    hash::Hash,
+};
+
+impl<RectToPlaceId, BinId> RectanglePackOk<RectToPlaceId, BinId>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+{
+    /// Add the placements of another pack to this one, such as when different categories of
+    /// assets are packed into their own bins in parallel and then combined into one lookup
+    /// table.
+    ///
+    /// The packs must not share any rectangles or bins. If they do, the smallest one that they
+    /// share is returned and this pack is left unchanged.
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace, MergeError,
+    ///     RectToInsert, TargetBin,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let pack = |rect_id: &'static str, bin_id: &'static str| {
+    ///     let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    ///     rects_to_place.push_rect(rect_id, None, RectToInsert::new(4, 4, 1));
+    ///
+    ///     let mut target_bins = BTreeMap::new();
+    ///     target_bins.insert(bin_id, TargetBin::new(16, 16, 1));
+    ///
+    ///     pack_rects(
+    ///         &rects_to_place,
+    ///         &mut target_bins,
+    ///         &volume_heuristic,
+    ///         &contains_smallest_box,
+    ///     )
+    ///     .unwrap()
+    /// };
+    ///
+    /// let mut packed = pack("grass", "terrain");
+    /// packed.merge(pack("sword", "items")).unwrap();
+    /// assert_eq!(packed.packed_locations()["sword"].0, "items");
+    ///
+    /// assert_eq!(
+    ///     packed.merge(pack("shield", "items")),
+    ///     Err(MergeError::DuplicateBin("items"))
+    /// );
+    /// ```
+    pub fn merge(&mut self, other: Self) -> Result<(), MergeError<RectToPlaceId, BinId>> {
+        let duplicate_rect = other
+            .packed_locations
+            .keys()
+            .chain(other.omitted_optional_rects.iter())
+            .filter(|rect_id| {
+                self.packed_locations.contains_key(rect_id)
+                    || self.omitted_optional_rects.binary_search(rect_id).is_ok()
+            })
+            .min();
+        if let Some(rect_id) = duplicate_rect {
+            return Err(MergeError::DuplicateRect(rect_id.clone()));
+        }
+
+        let bins: BTreeSet<&BinId> = self
+            .packed_locations
+            .values()
+            .map(|(bin_id, _)| bin_id)
+            .collect();
+        let duplicate_bin = other
+            .packed_locations
+            .values()
+            .map(|(bin_id, _)| bin_id)
+            .filter(|bin_id| bins.contains(bin_id))
+            .min();
+        if let Some(bin_id) = duplicate_bin {
+            return Err(MergeError::DuplicateBin(bin_id.clone()));
+        }
+
+        self.packed_locations.extend(other.packed_locations);
+        self.omitted_optional_rects
+            .extend(other.omitted_optional_rects);
+        self.omitted_optional_rects.sort();
+
+        Ok(())
+    }
+}
+
+/// An error while merging one [`RectanglePackOk`] into another.
+///
+/// See [`RectanglePackOk.merge`].
+///
+/// [`RectanglePackOk.merge`]: RectanglePackOk::merge
+#[derive(Debug, PartialEq)]
+pub enum MergeError<RectToPlaceId, BinId> {
+    /// Both packs contain this rectangle, either placed or omitted.
+    DuplicateRect(RectToPlaceId),
+    /// Both packs placed rectangles into this bin.
+    DuplicateBin(BinId),
+}
+
+#[cfg(feature = "std")]
+impl<RectToPlaceId: Debug, BinId: Debug> std::error::Error for MergeError<RectToPlaceId, BinId> {}
+
+impl<RectToPlaceId: Debug, BinId: Debug> Display for MergeError<RectToPlaceId, BinId> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            MergeError::DuplicateRect(rect_id) => {
+                write!(f, "Both packs contain the rectangle {:?}.", rect_id)
+            }
+            MergeError::DuplicateBin(bin_id) => {
+                write!(f, "Both packs placed rectangles into the bin {:?}.", bin_id)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::width_height_depth::WidthHeightDepth;
+    use crate::PackedLocation;
+
+    /// Verify that a failed merge reports the shared rectangle and leaves the pack unchanged.
+    #[test]
+    fn duplicate_rect_is_reported_without_merging() {
+        let mut packed = packed_into(&[(1, 0), (2, 0)]);
+
+        assert_eq!(
+            packed.merge(packed_into(&[(3, 1), (2, 1)])),
+            Err(MergeError::DuplicateRect(2))
+        );
+        assert_eq!(packed, packed_into(&[(1, 0), (2, 0)]));
+
+        packed.merge(packed_into(&[(3, 1)])).unwrap();
+        assert_eq!(packed.packed_locations.len(), 3);
+    }
+
+    fn packed_into(rects: &[(u8, u8)]) -> RectanglePackOk<u8, u8> {
+        let mut packed = RectanglePackOk::default();
+        for (rect_id, bin_id) in rects {
+            packed
+                .packed_locations
+                .insert(*rect_id, (*bin_id, location()));
+        }
+
+        packed
+    }
+
+    fn location() -> PackedLocation {
+        PackedLocation::new(0, 0, 0, WidthHeightDepth::new(1, 1, 1))
+    }
+}
@@ -0,0 +1,250 @@
+//! Packing bins whose layouts must stay identical, such as the albedo and normal map atlases of
+//! the same set of materials.
+
+use crate::{
+    pack_rects_with_options, BoxSizeHeuristic, ComparePotentialContainers, GroupedRectsToPlace,
+    PackOptions, RectanglePackError, RectanglePackOk, TargetBin,
+};
+
+use alloc::{collections::BTreeMap, format, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+/// Pack rectangles the same way as [`pack_rects_with_options`], placing every rectangle that goes
+/// into one of the bins in `mirrors` at the exact same location in each of that bin's mirror
+/// bins.
+///
+/// Mirroring applies to whole bins, not to individual groups. Everything that is placed into a
+/// mirrored bin is mirrored, so rectangles that shouldn't be mirrored have to go into other bins,
+/// such as by using [`pack_rects_with_allowed_bins`].
+///
+/// The layout is solved once, using only the bins that are not a mirror, and then copied to the
+/// mirror bins, whose free sections end up identical to those of the bin that they mirror. The
+/// returned placements only name the mirrored bin, since the location is the same in each of
+/// its mirrors.
+///
+/// If any of a bin's mirrors is frozen (see [`TargetBin.freeze`]) nothing is placed into the
+/// bin.
+///
+/// Returns [`RectanglePackError::MirrorLayoutMismatch`] without packing anything if a bin and one
+/// of its mirrors do not have the same size, configuration and free sections, since then the same
+/// layout can't be applied to both of them.
+///
+/// ```
+/// use rectangle_pack::{
+///     contains_smallest_box, pack_rects_with_mirrored_bins, volume_heuristic,
+///     GroupedRectsToPlace, PackOptions, RectToInsert, TargetBin,
+/// };
+/// use std::collections::BTreeMap;
+///
+/// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+/// rects_to_place.push_rect("brick", None, RectToInsert::new(32, 32, 1));
+///
+/// let mut target_bins = BTreeMap::new();
+/// target_bins.insert("albedo", TargetBin::new(256, 256, 1));
+/// target_bins.insert("normal", TargetBin::new(256, 256, 1));
+///
+/// let mut mirrors = BTreeMap::new();
+/// mirrors.insert("albedo", vec!["normal"]);
+///
+/// let packed = pack_rects_with_mirrored_bins(
+///     &rects_to_place,
+///     &mut target_bins,
+///     &mirrors,
+///     &volume_heuristic,
+///     &contains_smallest_box,
+///     &PackOptions::default(),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(packed.packed_locations()["brick"].0, "albedo");
+/// assert_eq!(
+///     target_bins["albedo"].available_bin_sections(),
+///     target_bins["normal"].available_bin_sections()
+/// );
+/// ```
+///
+/// [`pack_rects_with_options`]: crate::pack_rects_with_options
+/// [`pack_rects_with_allowed_bins`]: crate::pack_rects_with_allowed_bins
+/// [`TargetBin.freeze`]: crate::TargetBin::freeze
+pub fn pack_rects_with_mirrored_bins<RectToPlaceId, BinId, GroupId, Heuristic, Compare>(
+    rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    target_bins: &mut BTreeMap<BinId, TargetBin>,
+    mirrors: &BTreeMap<BinId, Vec<BinId>>,
+    box_size_heuristic: &Heuristic,
+    more_suitable_containers_fn: &Compare,
+    options: &PackOptions,
+) -> Result<RectanglePackOk<RectToPlaceId, BinId>, RectanglePackError>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+{
+    // Checked up front so that no mirror has been taken out of the bins yet when returning the
+    // error.
+    for (bin_id, mirror_ids) in mirrors.iter() {
+        let bin = match target_bins.get(bin_id) {
+            Some(bin) => bin,
+            None => continue,
+        };
+
+        for mirror_id in mirror_ids.iter() {
+            if let Some(mirror) = target_bins.get(mirror_id) {
+                if !has_same_layout(bin, mirror) {
+                    return Err(RectanglePackError::MirrorLayoutMismatch {
+                        bin_id: format!("{:?}", bin_id),
+                        mirror_id: format!("{:?}", mirror_id),
+                    });
+                }
+            }
+        }
+    }
+
+    let mut mirror_bins = BTreeMap::new();
+    let mut temporarily_frozen = vec![];
+
+    for (bin_id, mirror_ids) in mirrors.iter() {
+        for mirror_id in mirror_ids.iter() {
+            let mirror = match target_bins.remove(mirror_id) {
+                Some(mirror) => mirror,
+                None => continue,
+            };
+
+            if let Some(bin) = target_bins.get_mut(bin_id) {
+                if mirror.frozen && !bin.frozen {
+                    bin.freeze();
+                    temporarily_frozen.push(bin_id.clone());
+                }
+            }
+
+            mirror_bins.insert(mirror_id.clone(), mirror);
+        }
+    }
+
+    let packed = pack_rects_with_options(
+        rects_to_place,
+        target_bins,
+        box_size_heuristic,
+        more_suitable_containers_fn,
+        options,
+    );
+
+    for bin_id in temporarily_frozen {
+        target_bins.get_mut(&bin_id).unwrap().unfreeze();
+    }
+
+    if packed.is_ok() {
+        for (bin_id, mirror_ids) in mirrors.iter() {
+            let bin = match target_bins.get(bin_id) {
+                Some(bin) => bin,
+                None => continue,
+            };
+
+            for mirror_id in mirror_ids.iter() {
+                if let Some(mirror) = mirror_bins.get_mut(mirror_id) {
+                    mirror.available_bin_sections = bin.available_bin_sections.clone();
+                    mirror.size_classes = bin.size_classes.clone();
+                    mirror.free_section_volume = bin.free_section_volume;
+                    mirror.placed_rect_count = bin.placed_rect_count;
+                    mirror.placed_weight = bin.placed_weight;
+                    if mirror.used_sections.is_some() {
+                        mirror.used_sections = bin.used_sections.clone();
+                    }
+                }
+            }
+        }
+    }
+
+    target_bins.extend(mirror_bins);
+
+    packed
+}
+
+/// Whether the same placements can be applied to both bins.
+fn has_same_layout(bin: &TargetBin, mirror: &TargetBin) -> bool {
+    bin.dimensions() == mirror.dimensions()
+        && bin.block_size == mirror.block_size
+        && bin.layered_depth == mirror.layered_depth
+        && bin.available_bin_sections == mirror.available_bin_sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{contains_smallest_box, volume_heuristic, RectToInsert};
+
+    /// Verify that nothing is placed into the mirror bins directly, and that a bin whose mirror
+    /// is frozen is skipped.
+    #[test]
+    fn mirror_bins_follow_their_bin() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect("brick", None, RectToInsert::new(8, 8, 1));
+        rects_to_place.push_rect("moss", None, RectToInsert::new(4, 4, 1));
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert("albedo-0", TargetBin::new(8, 8, 1));
+        target_bins.insert("normal-0", TargetBin::new(8, 8, 1));
+        target_bins.insert("albedo-1", TargetBin::new(16, 16, 1));
+        target_bins.insert("normal-1", TargetBin::new(16, 16, 1));
+        target_bins.get_mut("normal-0").unwrap().freeze();
+
+        let mut mirrors = BTreeMap::new();
+        mirrors.insert("albedo-0", vec!["normal-0"]);
+        mirrors.insert("albedo-1", vec!["normal-1"]);
+
+        let packed = pack_rects_with_mirrored_bins(
+            &rects_to_place,
+            &mut target_bins,
+            &mirrors,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(packed.packed_locations["brick"].0, "albedo-1");
+        assert_eq!(packed.packed_locations["moss"].0, "albedo-1");
+        assert_eq!(target_bins.len(), 4);
+        assert!(!target_bins["albedo-0"].is_frozen());
+        assert_eq!(
+            target_bins["albedo-1"].available_bin_sections,
+            target_bins["normal-1"].available_bin_sections
+        );
+        assert_eq!(target_bins["normal-0"].available_bin_sections.len(), 1);
+    }
+
+    /// Verify that a mirror with a different layout is reported without packing anything or
+    /// taking any bins away.
+    #[test]
+    fn error_if_mirror_has_different_layout() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect("brick", None, RectToInsert::new(8, 8, 1));
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert("albedo", TargetBin::new(16, 16, 1));
+        target_bins.insert("normal", TargetBin::new(32, 32, 1));
+
+        let mut mirrors = BTreeMap::new();
+        mirrors.insert("albedo", vec!["normal"]);
+
+        let packed = pack_rects_with_mirrored_bins(
+            &rects_to_place,
+            &mut target_bins,
+            &mirrors,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::default(),
+        );
+
+        assert_eq!(
+            packed.unwrap_err(),
+            RectanglePackError::MirrorLayoutMismatch {
+                bin_id: "\"albedo\"".into(),
+                mirror_id: "\"normal\"".into(),
+            }
+        );
+        assert_eq!(target_bins.len(), 2);
+        assert!(target_bins.values().all(|bin| bin.placed_rect_count() == 0));
+    }
+}
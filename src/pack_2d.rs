@@ -0,0 +1,338 @@
+//! Packing flat rectangles, such as sprites and glyphs, without having to think about depth.
+
+use crate::pack_options::PackContext;
+use crate::{
+    contains_smallest_box, pack_rects, pack_rects_keyed_by, volume_heuristic, BoxSizeHeuristic,
+    ComparePotentialContainers, GroupedRectsToPlace, KeyValMap, PackOptions, PackedLocation,
+    RectToInsert, RectanglePackError, RectanglePackOk, RotatedBy, TargetBin,
+};
+
+use alloc::{collections::BTreeMap, format, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+/// Pack flat rectangles into flat bins, such as sprites into a texture atlas.
+///
+/// This is the same as [`pack_rects_with_options`], for rectangles created using
+/// [`RectToInsert::new_2d`] and bins created using [`TargetBin::new_2d`], but every placement is
+/// a [`PackedLocation2d`] that leaves out the depth.
+///
+/// Returns [`RectanglePackError::NotFlat`] without packing anything if any of the rectangles or
+/// bins has a depth other than `1`.
+///
+/// ```
+/// use rectangle_pack::{
+///     contains_smallest_box, pack_rects_2d, volume_heuristic, GroupedRectsToPlace, PackOptions,
+///     RectToInsert, TargetBin,
+/// };
+/// use std::collections::BTreeMap;
+///
+/// let mut sprites: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+/// sprites.push_rect("player", None, RectToInsert::new_2d(32, 48));
+/// sprites.push_rect("coin", None, RectToInsert::new_2d(16, 16));
+///
+/// let mut target_bins = BTreeMap::new();
+/// target_bins.insert("atlas", TargetBin::new_2d(64, 64));
+///
+/// let packed = pack_rects_2d(
+///     &sprites,
+///     &mut target_bins,
+///     &volume_heuristic,
+///     &contains_smallest_box,
+///     &PackOptions::new(),
+/// )
+/// .unwrap();
+///
+/// let (_, player) = packed.packed_locations()["player"];
+/// assert_eq!((player.width(), player.height()), (32, 48));
+/// ```
+///
+/// [`pack_rects_with_options`]: crate::pack_rects_with_options
+/// [`RectToInsert::new_2d`]: crate::RectToInsert::new_2d
+pub fn pack_rects_2d<RectToPlaceId, BinId, GroupId, Heuristic, Compare>(
+    rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    target_bins: &mut BTreeMap<BinId, TargetBin>,
+    box_size_heuristic: &Heuristic,
+    more_suitable_containers_fn: &Compare,
+    options: &PackOptions,
+) -> Result<RectanglePack2dOk<RectToPlaceId, BinId>, RectanglePackError>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+{
+    let deep_rect = rects_to_place
+        .rects
+        .iter()
+        .filter(|(_, rect)| rect.whd.depth != 1)
+        .map(|(rect_id, _)| rect_id)
+        .min();
+    if let Some(rect_id) = deep_rect {
+        return Err(RectanglePackError::NotFlat {
+            rect_to_place_id: Some(format!("{:?}", rect_id)),
+            bin_id: None,
+        });
+    }
+    if let Some((bin_id, _)) = target_bins
+        .iter()
+        .find(|(_, bin)| bin.dimensions().depth != 1)
+    {
+        return Err(RectanglePackError::NotFlat {
+            rect_to_place_id: None,
+            bin_id: Some(format!("{:?}", bin_id)),
+        });
+    }
+
+    let mut packed = RectanglePackOk::default();
+    pack_rects_keyed_by(
+        rects_to_place,
+        target_bins,
+        None,
+        PackContext::new(box_size_heuristic, more_suitable_containers_fn, options),
+        RectToPlaceId::clone,
+        &mut packed,
+    )?;
+
+    let omitted_optional_rects = packed.omitted_optional_rects().to_vec();
+    Ok(RectanglePack2dOk {
+        packed_locations: packed
+            .into_locations()
+            .into_iter()
+            .map(|(rect_id, (bin_id, location))| {
+                (rect_id, (bin_id, PackedLocation2d::from_flat(&location)))
+            })
+            .collect(),
+        omitted_optional_rects,
+    })
+}
+
+/// The flat rectangles that [`pack_rects_2d`] placed.
+#[derive(Debug, PartialEq)]
+pub struct RectanglePack2dOk<RectToPlaceId: PartialEq + Eq + Hash, BinId: PartialEq + Eq + Hash> {
+    packed_locations: KeyValMap<RectToPlaceId, (BinId, PackedLocation2d)>,
+    omitted_optional_rects: Vec<RectToPlaceId>,
+}
+
+impl<RectToPlaceId: PartialEq + Eq + Hash, BinId: PartialEq + Eq + Hash>
+    RectanglePack2dOk<RectToPlaceId, BinId>
+{
+    /// Indicates where every incoming rectangle was placed
+    pub fn packed_locations(&self) -> &KeyValMap<RectToPlaceId, (BinId, PackedLocation2d)> {
+        &self.packed_locations
+    }
+
+    /// The optional rectangles that were left out, see [`RectanglePackOk.omitted_optional_rects`].
+    ///
+    /// [`RectanglePackOk.omitted_optional_rects`]: RectanglePackOk::omitted_optional_rects
+    pub fn omitted_optional_rects(&self) -> &[RectToPlaceId] {
+        &self.omitted_optional_rects
+    }
+
+    /// The placements of [`RectanglePack2dOk.packed_locations`], taken by value so that they can
+    /// be stored without cloning them.
+    ///
+    /// [`RectanglePack2dOk.packed_locations`]: RectanglePack2dOk::packed_locations
+    pub fn into_locations(self) -> KeyValMap<RectToPlaceId, (BinId, PackedLocation2d)> {
+        self.packed_locations
+    }
+}
+
+/// Where a flat rectangle was placed within a flat bin, see [`pack_rects_2d`].
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct PackedLocation2d {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    rotation: RotatedBy,
+    flipped_horizontally: bool,
+    flipped_vertically: bool,
+}
+
+impl PackedLocation2d {
+    /// The placement of a rectangle that has a depth of `1`, at the front of a bin that has a
+    /// depth of `1`.
+    fn from_flat(location: &PackedLocation) -> Self {
+        PackedLocation2d {
+            x: location.x(),
+            y: location.y(),
+            width: location.width(),
+            height: location.height(),
+            rotation: location.z_axis_rotation(),
+            flipped_horizontally: location.flipped_horizontally(),
+            flipped_vertically: location.flipped_vertically(),
+        }
+    }
+
+    #[allow(missing_docs)]
+    pub fn x(&self) -> u32 {
+        self.x
+    }
+
+    #[allow(missing_docs)]
+    pub fn y(&self) -> u32 {
+        self.y
+    }
+
+    /// The width of the rectangle before it was rotated, see [`PackedLocation2d.rotation`].
+    ///
+    /// [`PackedLocation2d.rotation`]: PackedLocation2d::rotation
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the rectangle before it was rotated, see [`PackedLocation2d.rotation`].
+    ///
+    /// [`PackedLocation2d.rotation`]: PackedLocation2d::rotation
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// How far the rectangle was rotated, see [`PackedLocation.z_axis_rotation`].
+    ///
+    /// [`PackedLocation.z_axis_rotation`]: PackedLocation::z_axis_rotation
+    pub fn rotation(&self) -> RotatedBy {
+        self.rotation
+    }
+
+    /// See [`PackedLocation.flipped_horizontally`].
+    ///
+    /// [`PackedLocation.flipped_horizontally`]: PackedLocation::flipped_horizontally
+    pub fn flipped_horizontally(&self) -> bool {
+        self.flipped_horizontally
+    }
+
+    /// See [`PackedLocation.flipped_vertically`].
+    ///
+    /// [`PackedLocation.flipped_vertically`]: PackedLocation::flipped_vertically
+    pub fn flipped_vertically(&self) -> bool {
+        self.flipped_vertically
+    }
+}
+
+/// Pack `(id, width, height)` rectangles into a single flat bin of the given size, for the
+/// common case of one atlas without any groups.
+///
+/// Returns the location of every rectangle, in the order that they were given. If an id is given
+/// more than once the last of its sizes is placed and it is returned once, in the position of its
+/// first occurrence.
+///
+/// Use [`pack_rects_2d`] for more than one bin, for groups, or for choosing the heuristics and
+/// [`PackOptions`].
+///
+/// ```
+/// use rectangle_pack::pack_into_bin;
+///
+/// let placements = pack_into_bin(64, 64, vec![("player", 32, 48), ("coin", 16, 16)]).unwrap();
+///
+/// assert_eq!(placements[0].0, "player");
+/// assert_eq!((placements[1].1.width(), placements[1].1.height()), (16, 16));
+/// ```
+pub fn pack_into_bin<RectToPlaceId>(
+    width: u32,
+    height: u32,
+    rects: impl IntoIterator<Item = (RectToPlaceId, u32, u32)>,
+) -> Result<Vec<(RectToPlaceId, PackedLocation)>, RectanglePackError>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+{
+    let mut ids = Vec::new();
+    let mut rects_to_place: GroupedRectsToPlace<RectToPlaceId, ()> = GroupedRectsToPlace::new();
+    for (rect_id, rect_width, rect_height) in rects {
+        rects_to_place.push_rect(
+            rect_id.clone(),
+            None,
+            RectToInsert::new_2d(rect_width, rect_height),
+        );
+        ids.push(rect_id);
+    }
+
+    let mut target_bins = BTreeMap::new();
+    target_bins.insert((), TargetBin::new_2d(width, height));
+
+    let mut locations = pack_rects(
+        &rects_to_place,
+        &mut target_bins,
+        &volume_heuristic,
+        &contains_smallest_box,
+    )?
+    .into_locations();
+
+    Ok(ids
+        .into_iter()
+        .filter_map(|rect_id| {
+            let (_, location) = locations.remove(&rect_id)?;
+            Some((rect_id, location))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that rectangles and bins with a depth are rejected.
+    #[test]
+    fn error_if_rect_or_bin_has_depth() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect("box", None, RectToInsert::new(4, 4, 2));
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert("atlas", TargetBin::new_2d(16, 16));
+
+        let pack = |rects_to_place: &GroupedRectsToPlace<_, ()>, target_bins: &mut _| {
+            pack_rects_2d(
+                rects_to_place,
+                target_bins,
+                &volume_heuristic,
+                &contains_smallest_box,
+                &PackOptions::new(),
+            )
+        };
+
+        assert_eq!(
+            pack(&rects_to_place, &mut target_bins).unwrap_err(),
+            RectanglePackError::NotFlat {
+                rect_to_place_id: Some("\"box\"".into()),
+                bin_id: None,
+            }
+        );
+
+        rects_to_place.push_rect("box", None, RectToInsert::new_2d(4, 4));
+        target_bins.insert("array", TargetBin::new(16, 16, 4));
+        assert_eq!(
+            pack(&rects_to_place, &mut target_bins).unwrap_err(),
+            RectanglePackError::NotFlat {
+                rect_to_place_id: None,
+                bin_id: Some("\"array\"".into()),
+            }
+        );
+
+        target_bins.remove("array");
+        let packed = pack(&rects_to_place, &mut target_bins).unwrap();
+        let (_, location) = packed.packed_locations()["box"];
+        assert_eq!(
+            (
+                location.x(),
+                location.y(),
+                location.width(),
+                location.height()
+            ),
+            (0, 0, 4, 4)
+        );
+    }
+
+    /// Verify that repeated ids are placed once, with their last size, in the position of their
+    /// first occurrence.
+    #[test]
+    fn pack_into_bin_repeated_ids() {
+        let placements = pack_into_bin(10, 10, vec![(1, 2, 2), (2, 3, 3), (1, 4, 4)]).unwrap();
+
+        let placements: Vec<(u32, u32)> = placements
+            .iter()
+            .map(|(rect_id, location)| (*rect_id, location.width()))
+            .collect();
+        assert_eq!(placements, vec![(1, 4), (2, 3)]);
+    }
+}
@@ -0,0 +1,579 @@
+use crate::packer::PackScratch;
+use crate::width_height_depth::WidthHeightDepth;
+use crate::{
+    BoxSizeHeuristic, BoxSizeHeuristicFn, ComparePotentialContainers, ComparePotentialContainersFn,
+    TargetBin,
+};
+
+use alloc::sync::Arc;
+use core::cell::{Cell, RefCell};
+
+pub use self::compare_orientations::{CompareOrientations, OrientationCandidate};
+pub use self::placement_policy::PlacementPolicy;
+pub(crate) use self::split_strategy::is_valid_split;
+pub use self::split_strategy::SplitStrategy;
+pub use self::validate_placement::ValidatePlacement;
+
+mod compare_orientations;
+mod placement_policy;
+mod split_strategy;
+mod validate_placement;
+
+/// Options that control how [`pack_rects_with_options`] packs rectangles.
+///
+/// The default options pack exactly the same way as [`pack_rects`].
+///
+/// [`pack_rects`]: crate::pack_rects
+/// [`pack_rects_with_options`]: crate::pack_rects_with_options
+#[derive(Debug, Clone, Default)]
+pub struct PackOptions {
+    pub(crate) merge_free_sections: bool,
+    pub(crate) placement_policy: PlacementPolicy,
+    pub(crate) max_backtracked_placements: usize,
+    pub(crate) lookahead: usize,
+    pub(crate) determinism_audit: bool,
+    pub(crate) two_phase_threshold: Option<u128>,
+    pub(crate) max_sections_examined: Option<usize>,
+    pub(crate) orientation_comparator: Option<Arc<dyn CompareOrientations>>,
+    pub(crate) split_strategy: Option<Arc<dyn SplitStrategy>>,
+    pub(crate) placement_validator: Option<Arc<dyn ValidatePlacement>>,
+    pub(crate) padding: u32,
+    pub(crate) size_rounding: Option<WidthHeightDepth>,
+    pub(crate) elongated_aspect_ratio: Option<u32>,
+    pub(crate) contiguous_groups: bool,
+    pub(crate) max_bins: Option<usize>,
+    pub(crate) min_support_percent: Option<u32>,
+    pub(crate) maximize_value: bool,
+    pub(crate) minimize_bin_cost: bool,
+    pub(crate) target_fill_percent: Option<u32>,
+    /// Treat every rectangle as optional, see [`RectToInsert.with_optional`].
+    ///
+    /// [`RectToInsert.with_optional`]: crate::RectToInsert::with_optional
+    pub(crate) best_effort: bool,
+    /// Set by [`pack_ranges`] to place every rectangle using the one dimensional fast path.
+    ///
+    /// [`pack_ranges`]: crate::pack_ranges
+    pub(crate) one_dimensional: bool,
+}
+
+impl PackOptions {
+    /// Create the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Before every attempt to place a rectangle into a bin, combine that bin's neighboring free
+    /// sections into larger sections.
+    ///
+    /// Splitting a section every time that a rectangle is placed means that free space that was
+    /// once contiguous can end up divided between several sections, none of which are large
+    /// enough for an incoming rectangle. Merging them back together reclaims that space, at the
+    /// cost of an `O(n^2)` pass over the free sections before every placement.
+    ///
+    /// See [`TargetBin.coalesce_all_available_sections`].
+    ///
+    /// [`TargetBin.coalesce_all_available_sections`]: crate::TargetBin::coalesce_all_available_sections
+    pub fn with_free_section_merging(mut self, merge_free_sections: bool) -> Self {
+        self.merge_free_sections = merge_free_sections;
+        self
+    }
+
+    /// Decide which of a bin's sections a rectangle gets placed into when several can fit it.
+    ///
+    /// Defaults to [`PlacementPolicy::FirstFit`].
+    pub fn with_placement_policy(mut self, placement_policy: PlacementPolicy) -> Self {
+        self.placement_policy = placement_policy;
+        self
+    }
+
+    /// When a group of rectangles can't be placed into any bin, undo up to this many of the most
+    /// recently placed groups and retry them using the alternative ways of splitting their
+    /// sections before giving up with [`RectanglePackError::NotEnoughBinSpace`].
+    ///
+    /// Every rectangle that was pushed without a group counts as its own group.
+    ///
+    /// The search is exhaustive within the undone placements, so the worst case cost grows
+    /// exponentially with `max_backtracked_placements`. Small values such as `2` or `3` are
+    /// usually enough to recover from an unlucky split.
+    ///
+    /// Defaults to `0`, which never backtracks.
+    ///
+    /// [`RectanglePackError::NotEnoughBinSpace`]: crate::RectanglePackError::NotEnoughBinSpace
+    pub fn with_backtracking(mut self, max_backtracked_placements: usize) -> Self {
+        self.max_backtracked_placements = max_backtracked_placements;
+        self
+    }
+
+    /// Before placing a group of rectangles, simulate placing it using each of the ways that its
+    /// sections could be split, followed by the next `lookahead` rectangles, and use the split
+    /// that lets those upcoming rectangles fill the most of the bin.
+    ///
+    /// Without lookahead the split is chosen using only the [`ComparePotentialContainersFn`],
+    /// which doesn't know anything about the rectangles that are still to come.
+    ///
+    /// Every placement costs up to six extra simulations of `lookahead` placements.
+    ///
+    /// Defaults to `0`, which disables lookahead.
+    ///
+    /// [`ComparePotentialContainersFn`]: crate::ComparePotentialContainersFn
+    pub fn with_lookahead(mut self, lookahead: usize) -> Self {
+        self.lookahead = lookahead;
+        self
+    }
+
+    /// Pack a second time, into copies of the bins, with the bins and groups shuffled before they
+    /// get sorted, and panic if the two packs don't place every rectangle in exactly the same way.
+    ///
+    /// This catches packing that accidentally depends on the iteration order of an unordered
+    /// container, before it reaches anyone relying on reproducible output. It doubles the cost of
+    /// packing, so it is meant for tests and debug builds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shuffled pack has a different outcome.
+    pub fn with_determinism_audit(mut self, determinism_audit: bool) -> Self {
+        self.determinism_audit = determinism_audit;
+        self
+    }
+
+    /// Pack in two phases, the way that many texture atlas bakers do.
+    ///
+    /// First every group whose total size, according to the box size heuristic, is larger than
+    /// the threshold is placed using [`PlacementPolicy::BestFit`], into whichever bin can fit the
+    /// group most tightly. Then the remaining smaller groups fill in the holes that are left over
+    /// using the cheaper [`PlacementPolicy::FirstFit`], trying the bins smallest to largest as
+    /// usual.
+    ///
+    /// This overrides [`PackOptions.with_placement_policy`].
+    ///
+    /// [`PackOptions.with_placement_policy`]: PackOptions::with_placement_policy
+    pub fn with_two_phase(mut self, large_group_threshold: u128) -> Self {
+        self.two_phase_threshold = Some(large_group_threshold);
+        self
+    }
+
+    /// Stop packing with [`RectanglePackError::SearchLimitExceeded`] once this many free sections
+    /// have been examined in total.
+    ///
+    /// Every attempt to place a rectangle examines some number of free sections, including the
+    /// attempts made while checking whether a group fits, backtracking and looking ahead. Tens of
+    /// thousands of tiny rectangles can lead to a quadratic number of these, so capping them
+    /// gives a hard upper bound on how long a pack can take.
+    ///
+    /// Defaults to no limit.
+    ///
+    /// [`RectanglePackError::SearchLimitExceeded`]: crate::RectanglePackError::SearchLimitExceeded
+    pub fn with_max_sections_examined(mut self, max_sections_examined: usize) -> Self {
+        self.max_sections_examined = Some(max_sections_examined);
+        self
+    }
+
+    /// Decide which orientation a rectangle gets placed in when it fits into a section in more
+    /// than one of the orientations that it is allowed to be rotated into.
+    ///
+    /// Defaults to the first orientation that fits, trying the unrotated orientation first.
+    pub fn with_orientation_comparator(
+        mut self,
+        orientation_comparator: Arc<dyn CompareOrientations>,
+    ) -> Self {
+        self.orientation_comparator = Some(orientation_comparator);
+        self
+    }
+
+    /// Decide which free sections are left behind when a rectangle is placed into a free
+    /// section, instead of splitting it into up to three sections.
+    ///
+    /// Doesn't apply to [`pack_ranges`], which always splits a range into the part before and the
+    /// part after the rectangle.
+    ///
+    /// [`pack_ranges`]: crate::pack_ranges
+    pub fn with_split_strategy(mut self, split_strategy: Arc<dyn SplitStrategy>) -> Self {
+        self.split_strategy = Some(split_strategy);
+        self
+    }
+
+    /// Only place rectangles at locations that the validator accepts.
+    ///
+    /// Defaults to accepting every location.
+    pub fn with_placement_validator(
+        mut self,
+        placement_validator: Arc<dyn ValidatePlacement>,
+    ) -> Self {
+        self.placement_validator = Some(placement_validator);
+        self
+    }
+
+    /// Reserve this many extra texels on every side of every rectangle that doesn't set its own
+    /// [`RectToInsert.with_padding`].
+    ///
+    /// Defaults to `0`.
+    ///
+    /// [`RectToInsert.with_padding`]: crate::RectToInsert::with_padding
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Round the width, height and depth of every rectangle up to the next multiple of the given
+    /// sizes before placing it, such as to the 4x4 blocks of a BC compressed texture.
+    ///
+    /// The [`PackedLocation`] reports both the size that was requested and the
+    /// [`PackedLocation.allocated_whd`].
+    ///
+    /// Unlike [`TargetBin.with_block_size`], the rectangles can still be placed at any position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if width, height or depth is 0.
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects_with_options, volume_heuristic, GroupedRectsToPlace,
+    ///     PackOptions, RectToInsert, TargetBin, WidthHeightDepth,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    /// rects_to_place.push_rect("icon", None, RectToInsert::new(10, 7, 1));
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert(0, TargetBin::new(64, 64, 1));
+    ///
+    /// let packed = pack_rects_with_options(
+    ///     &rects_to_place,
+    ///     &mut target_bins,
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    ///     &PackOptions::new().with_size_rounding(4, 4, 1),
+    /// )
+    /// .unwrap();
+    ///
+    /// let (_, location) = packed.packed_locations()["icon"];
+    /// assert_eq!((location.width(), location.height()), (10, 7));
+    /// assert_eq!(location.allocated_whd(), WidthHeightDepth::new(12, 8, 1));
+    /// ```
+    ///
+    /// [`PackedLocation`]: crate::PackedLocation
+    /// [`PackedLocation.allocated_whd`]: crate::PackedLocation::allocated_whd
+    /// [`TargetBin.with_block_size`]: crate::TargetBin::with_block_size
+    pub fn with_size_rounding(mut self, width: u32, height: u32, depth: u32) -> Self {
+        self.size_rounding = Some(WidthHeightDepth::new(width, height, depth));
+        self
+    }
+
+    /// Place long, thin rectangles, whose longer side is at least `min_aspect_ratio` times their
+    /// shorter side, into sections that run along the left, right, top or bottom edge of a bin
+    /// whenever one can fit them.
+    ///
+    /// A thin strip that gets placed into the middle of a bin splits the space around it into
+    /// narrow sections that little else fits into. Along an edge it only cuts off the space on
+    /// one side.
+    ///
+    /// The [`PackOptions.with_placement_policy`] decides between the sections that touch an edge.
+    ///
+    /// Defaults to treating every rectangle the same.
+    ///
+    /// [`PackOptions.with_placement_policy`]: PackOptions::with_placement_policy
+    pub fn with_edge_aligned_elongated_rects(mut self, min_aspect_ratio: u32) -> Self {
+        self.elongated_aspect_ratio = Some(min_aspect_ratio);
+        self
+    }
+
+    /// Place the rectangles of every group within a single region of their bin that no
+    /// rectangles from outside of the group are placed into, such as for the frames of a
+    /// flipbook animation or the levels of a mip chain that should sit next to each other.
+    ///
+    /// The group is first laid out on its own and the bounding box of that layout is then placed
+    /// into the bin as if it were one rectangle, so the region can't use any free space that is
+    /// left between the group's rectangles.
+    ///
+    /// Rectangles that were pushed without a group aren't affected. Since [`repack`] and
+    /// [`compact_lightest_bin`] move rectangles one at a time, they may split a group's region up.
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects_with_options, volume_heuristic, GroupedRectsToPlace,
+    ///     PackOptions, RectToInsert, TargetBin,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut rects_to_place = GroupedRectsToPlace::new();
+    /// for frame in 0..4 {
+    ///     rects_to_place.push_rect(frame, Some(vec!["walk"]), RectToInsert::new(16, 16, 1));
+    /// }
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert(0, TargetBin::new(256, 256, 1));
+    ///
+    /// let packed = pack_rects_with_options(
+    ///     &rects_to_place,
+    ///     &mut target_bins,
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    ///     &PackOptions::new().with_contiguous_groups(true),
+    /// )
+    /// .unwrap();
+    /// # assert_eq!(packed.packed_locations().len(), 4);
+    /// ```
+    ///
+    /// [`compact_lightest_bin`]: crate::compact_lightest_bin
+    /// [`repack`]: crate::repack
+    pub fn with_contiguous_groups(mut self, contiguous_groups: bool) -> Self {
+        self.contiguous_groups = contiguous_groups;
+        self
+    }
+
+    /// Only place rectangles into up to this many of the bins, failing with
+    /// [`RectanglePackError::BinLimitExceeded`] if they don't fit, such as when the remaining
+    /// bins only exist as an emergency overflow that a second pack may use.
+    ///
+    /// Bins are still tried in the usual order, so the limit is filled by the first bins that
+    /// rectangles get placed into.
+    ///
+    /// Defaults to no limit.
+    ///
+    /// [`RectanglePackError::BinLimitExceeded`]: crate::RectanglePackError::BinLimitExceeded
+    pub fn with_max_bins(mut self, max_bins: usize) -> Self {
+        self.max_bins = Some(max_bins);
+        self
+    }
+
+    /// Only place a box where at least this percentage of its bottom face rests on the floor of
+    /// the bin (`y = 0`) or on the top faces of the boxes below it, such as for loading pallets
+    /// where nothing may float in mid air.
+    ///
+    /// `100` requires every box to be fully supported. The boxes below are only known for bins
+    /// with [`TargetBin.with_used_section_tracking`], so in other bins every box has to be placed
+    /// on the floor. Padding counts as part of the box.
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects_with_options, volume_heuristic, GroupedRectsToPlace,
+    ///     PackOptions, RectToInsert, TargetBin,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    /// rects_to_place.push_rect("pallet", None, RectToInsert::new(4, 1, 4));
+    /// rects_to_place.push_rect("crate", None, RectToInsert::new(2, 2, 2));
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert(0, TargetBin::new(4, 10, 4).with_used_section_tracking(true));
+    ///
+    /// let packed = pack_rects_with_options(
+    ///     &rects_to_place,
+    ///     &mut target_bins,
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    ///     &PackOptions::new().with_min_support(100),
+    /// )
+    /// .unwrap();
+    ///
+    /// let (_, crate_location) = packed.packed_locations()["crate"];
+    /// assert_eq!(crate_location.y(), 1);
+    /// ```
+    ///
+    /// Defaults to no requirement.
+    ///
+    /// [`TargetBin.with_used_section_tracking`]: crate::TargetBin::with_used_section_tracking
+    pub fn with_min_support(mut self, min_support_percent: u32) -> Self {
+        self.min_support_percent = Some(min_support_percent.min(100));
+        self
+    }
+
+    /// When the bins can't hold every rectangle, leave some of them out so that the total value
+    /// of the rectangles that do get placed (see [`RectToInsert.with_value`]) is as high as
+    /// possible, instead of failing.
+    ///
+    /// Every group is treated as optional (see [`RectToInsert.with_optional`]). Groups are
+    /// placed in order of their value per unit of size, as given by the box size heuristic, so
+    /// the result is a greedy approximation rather than the best possible selection. The groups
+    /// that were left out are listed in [`RectanglePackOk.omitted_optional_rects`].
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects_with_options, volume_heuristic, GroupedRectsToPlace,
+    ///     PackOptions, RectToInsert, TargetBin,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    /// rects_to_place.push_rect("background", None, RectToInsert::new(10, 10, 1).with_value(1));
+    /// rects_to_place.push_rect("logo", None, RectToInsert::new(5, 5, 1).with_value(50));
+    /// rects_to_place.push_rect("button", None, RectToInsert::new(5, 5, 1).with_value(20));
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert(0, TargetBin::new(10, 10, 1));
+    ///
+    /// let packed = pack_rects_with_options(
+    ///     &rects_to_place,
+    ///     &mut target_bins,
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    ///     &PackOptions::new().with_value_maximization(true),
+    /// )
+    /// .unwrap();
+    ///
+    /// assert!(packed.packed_locations().contains_key("logo"));
+    /// assert!(packed.packed_locations().contains_key("button"));
+    /// assert_eq!(packed.omitted_optional_rects(), &["background"]);
+    /// ```
+    ///
+    /// [`RectToInsert.with_value`]: crate::RectToInsert::with_value
+    /// [`RectToInsert.with_optional`]: crate::RectToInsert::with_optional
+    /// [`RectanglePackOk.omitted_optional_rects`]: crate::RectanglePackOk::omitted_optional_rects
+    pub fn with_value_maximization(mut self, maximize_value: bool) -> Self {
+        self.maximize_value = maximize_value;
+        self
+    }
+
+    /// Place every rectangle while keeping the summed [`TargetBin.with_cost`] of the bins that
+    /// end up being used low, such as preferring to reuse an existing atlas over allocating a
+    /// new 4K texture.
+    ///
+    /// Each group is tried in the bins that it costs nothing to use first, meaning the bins that
+    /// already hold rectangles, followed by the empty bins from cheapest to most expensive.
+    /// Bin priorities (see [`TargetBin.with_priority`]) still come first.
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects, pack_rects_with_options, volume_heuristic,
+    ///     GroupedRectsToPlace, PackOptions, RectToInsert, TargetBin,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert("existing", TargetBin::new(4096, 4096, 1).with_cost(4096 * 4096));
+    ///
+    /// let mut glyphs: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    /// glyphs.push_rect("glyph", None, RectToInsert::new(16, 16, 1));
+    /// pack_rects(&glyphs, &mut target_bins, &volume_heuristic, &contains_smallest_box).unwrap();
+    ///
+    /// target_bins.insert("new", TargetBin::new(64, 64, 1).with_cost(64 * 64));
+    ///
+    /// let mut sprites: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    /// sprites.push_rect("sprite", None, RectToInsert::new(4, 4, 1));
+    ///
+    /// let packed = pack_rects_with_options(
+    ///     &sprites,
+    ///     &mut target_bins,
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    ///     &PackOptions::new().with_bin_cost_minimization(true),
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(packed.packed_locations()["sprite"].0, "existing");
+    /// ```
+    ///
+    /// [`TargetBin.with_cost`]: crate::TargetBin::with_cost
+    /// [`TargetBin.with_priority`]: crate::TargetBin::with_priority
+    pub fn with_bin_cost_minimization(mut self, minimize_bin_cost: bool) -> Self {
+        self.minimize_bin_cost = minimize_bin_cost;
+        self
+    }
+
+    /// Stop spending time improving the layout once a bin is at least this percent full (see
+    /// [`TargetBin.used_volume`]), such as for interactive tools where a good enough layout now
+    /// beats a better one later.
+    ///
+    /// As soon as a placement fills its bin up to the target, none of the groups placed so far
+    /// are undone by [`PackOptions.with_backtracking`] anymore, and groups placed into a bin that
+    /// has reached the target skip [`PackOptions.with_lookahead`].
+    ///
+    /// Defaults to no target.
+    ///
+    /// [`TargetBin.used_volume`]: crate::TargetBin::used_volume
+    /// [`PackOptions.with_backtracking`]: PackOptions::with_backtracking
+    /// [`PackOptions.with_lookahead`]: PackOptions::with_lookahead
+    pub fn with_target_fill(mut self, target_fill_percent: u32) -> Self {
+        self.target_fill_percent = Some(target_fill_percent.min(100));
+        self
+    }
+
+    /// Whether the bin is full enough that its layout no longer needs improving, see
+    /// [`PackOptions.with_target_fill`].
+    pub(crate) fn reached_target_fill(&self, bin: &TargetBin) -> bool {
+        match self.target_fill_percent {
+            Some(percent) => bin.used_volume() * 100 >= bin.dimensions().volume() * percent as u128,
+            None => false,
+        }
+    }
+}
+
+/// Everything needed in order to decide where a rectangle should be placed.
+///
+/// Generic over the heuristic and the comparison so that functions and closures get called
+/// directly, without going through their trait objects.
+pub(crate) struct PackContext<
+    'a,
+    Heuristic: ?Sized = BoxSizeHeuristicFn<'a>,
+    Compare: ?Sized = ComparePotentialContainersFn<'a>,
+> {
+    pub(crate) box_size_heuristic: &'a Heuristic,
+    pub(crate) more_suitable_containers_fn: &'a Compare,
+    /// Used instead of the `more_suitable_containers_fn` for the smaller groups of a
+    /// [`PackOptions.with_two_phase`] pack.
+    ///
+    /// [`PackOptions.with_two_phase`]: PackOptions::with_two_phase
+    pub(crate) back_fill_containers_fn: Option<&'a Compare>,
+    pub(crate) options: &'a PackOptions,
+    /// The number of free sections examined so far, if they are being counted.
+    pub(crate) sections_examined: Option<&'a Cell<usize>>,
+    /// Buffers to reuse instead of allocating new ones, such as those of a [`Packer`].
+    ///
+    /// [`Packer`]: crate::Packer
+    pub(crate) scratch: Option<&'a RefCell<PackScratch>>,
+}
+
+impl<Heuristic: ?Sized, Compare: ?Sized> Clone for PackContext<'_, Heuristic, Compare> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Heuristic: ?Sized, Compare: ?Sized> Copy for PackContext<'_, Heuristic, Compare> {}
+
+impl<'a, Heuristic, Compare> PackContext<'a, Heuristic, Compare>
+where
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+{
+    /// A context that does not count the free sections that get examined.
+    pub(crate) fn new(
+        box_size_heuristic: &'a Heuristic,
+        more_suitable_containers_fn: &'a Compare,
+        options: &'a PackOptions,
+    ) -> Self {
+        PackContext {
+            box_size_heuristic,
+            more_suitable_containers_fn,
+            back_fill_containers_fn: None,
+            options,
+            sections_examined: None,
+            scratch: None,
+        }
+    }
+
+    /// Count another examined free section, returning `false` if doing so exceeds the
+    /// [`PackOptions.with_max_sections_examined`] limit.
+    ///
+    /// [`PackOptions.with_max_sections_examined`]: PackOptions::with_max_sections_examined
+    pub(crate) fn examine_section(&self) -> bool {
+        let sections_examined = match self.sections_examined {
+            Some(sections_examined) => sections_examined,
+            None => return true,
+        };
+        sections_examined.set(sections_examined.get() + 1);
+
+        !self.search_limit_exceeded()
+    }
+
+    /// Whether an attempt was made to examine more free sections than the limit allows.
+    pub(crate) fn search_limit_exceeded(&self) -> bool {
+        match (self.sections_examined, self.options.max_sections_examined) {
+            (Some(sections_examined), Some(limit)) => sections_examined.get() > limit,
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use crate::{BoxSizeHeuristicFn, PackedLocation, RemainingSections};
+
+use core::cmp::Ordering;
+use core::fmt::Debug;
+
+/// Decides which orientation a rectangle gets placed in when it fits into a section in more than
+/// one of the orientations that it is allowed to be rotated into.
+///
+/// Each orientation is also offered once for every way that the rectangle is allowed to be
+/// flipped (see [`RectToInsert.with_horizontal_flip`]).
+///
+/// Without one, the rectangle is placed in the first orientation that fits, trying the unrotated
+/// orientation first, and is never flipped.
+///
+/// Useful for penalizing rotations, such as for text glyphs that are expensive to rotate at
+/// render time, only accepting them when they leave a much better split behind.
+///
+/// See [`PackOptions.with_orientation_comparator`].
+///
+/// ```
+/// use rectangle_pack::{
+///     BoxSizeHeuristicFn, CompareOrientations, OrientationCandidate, PackOptions,
+/// };
+/// use std::cmp::Ordering;
+/// use std::sync::Arc;
+///
+/// /// Only rotate when it leaves a larger section behind than not rotating would.
+/// #[derive(Debug)]
+/// struct PreferLargestRemainder;
+///
+/// impl CompareOrientations for PreferLargestRemainder {
+///     fn compare(
+///         &self,
+///         a: &OrientationCandidate,
+///         b: &OrientationCandidate,
+///         _box_size_heuristic: &BoxSizeHeuristicFn,
+///     ) -> Ordering {
+///         let largest = |candidate: &OrientationCandidate| {
+///             candidate
+///                 .remaining_sections()
+///                 .sections()
+///                 .iter()
+///                 .map(|section| section.width() as u64 * section.height() as u64)
+///                 .max()
+///                 .unwrap_or(0)
+///         };
+///
+///         largest(b).cmp(&largest(a))
+///     }
+/// }
+///
+/// let options = PackOptions::new().with_orientation_comparator(Arc::new(PreferLargestRemainder));
+/// ```
+///
+/// [`PackOptions.with_orientation_comparator`]: crate::PackOptions::with_orientation_comparator
+/// [`RectToInsert.with_horizontal_flip`]: crate::RectToInsert::with_horizontal_flip
+pub trait CompareOrientations: Debug + Send + Sync {
+    /// Return [`Ordering::Less`] if `a` should be used instead of `b`.
+    ///
+    /// Candidates are compared in the order that their orientations are tried, starting with the
+    /// unrotated orientation, and `a` is always the later one, so returning [`Ordering::Equal`]
+    /// keeps the earlier orientation.
+    fn compare(
+        &self,
+        a: &OrientationCandidate,
+        b: &OrientationCandidate,
+        box_size_heuristic: &BoxSizeHeuristicFn,
+    ) -> Ordering;
+}
+
+/// One of the orientations that a rectangle fits into a section in.
+///
+/// For a bin with a [`TargetBin.with_block_size`] the location and sections are measured in
+/// blocks. The location includes the rectangle's padding (see [`RectToInsert.with_padding`]).
+///
+/// [`RectToInsert.with_padding`]: crate::RectToInsert::with_padding
+/// [`TargetBin.with_block_size`]: crate::TargetBin::with_block_size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrientationCandidate {
+    pub(crate) location: PackedLocation,
+    pub(crate) remaining_sections: RemainingSections,
+}
+
+impl OrientationCandidate {
+    /// Where the rectangle would be placed, including how it would be rotated.
+    pub fn location(&self) -> &PackedLocation {
+        &self.location
+    }
+
+    /// The sections that would be left over after placing the rectangle.
+    pub fn remaining_sections(&self) -> &RemainingSections {
+        &self.remaining_sections
+    }
+}
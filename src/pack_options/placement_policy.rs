@@ -0,0 +1,28 @@
+/// Decides which of a bin's available sections an incoming rectangle gets placed into when more
+/// than one section can fit it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlacementPolicy {
+    /// Use the first section that can fit the rectangle.
+    ///
+    /// Sections are tried starting from the most recently created ones, which are the smaller
+    /// sections from the most recent split, so rectangles tend to fill in the space next to the
+    /// previously placed rectangle.
+    #[default]
+    FirstFit,
+    /// Use the section with the lowest, then leftmost, position (smallest `y`, then smallest `x`).
+    ///
+    /// Remaining ties are broken by the smallest `z` and then by the smallest section according
+    /// to the box size heuristic.
+    ///
+    /// This fills bins from the bottom up instead of along a diagonal, which leads to more
+    /// visually predictable layouts (such as a texture atlas) at the cost of checking every
+    /// available section for every rectangle.
+    BottomLeft,
+    /// Use the smallest section, according to the box size heuristic, that can fit the rectangle.
+    ///
+    /// Ties go to the section that would have been tried first by [`PlacementPolicy::FirstFit`].
+    ///
+    /// This leaves the larger sections intact for larger rectangles, at the cost of checking every
+    /// available section for every rectangle.
+    BestFit,
+}
@@ -0,0 +1,194 @@
+use crate::BinSection;
+
+use alloc::vec::Vec;
+use core::fmt::Debug;
+
+/// Decides which free sections are left behind when a rectangle is placed into a free section.
+///
+/// Without one, the free section is split into up to three sections using the split that the
+/// [`ComparePotentialContainersFn`] prefers, guillotine style.
+///
+/// Useful for plugging in other splitting schemes, such as two way splits or shelves that never
+/// split the rest of a row.
+///
+/// For a bin with a [`TargetBin.with_block_size`] the sections are measured in blocks.
+///
+/// See [`PackOptions.with_split_strategy`].
+///
+/// ```
+/// use rectangle_pack::{BinSection, PackOptions, SplitStrategy, WidthHeightDepth};
+/// use std::sync::Arc;
+///
+/// /// Leave one section to the right of the rectangle that is as tall as the free section, and
+/// /// one above the rectangle that is as wide as the rectangle.
+/// #[derive(Debug)]
+/// struct TwoWaySplit;
+///
+/// impl SplitStrategy for TwoWaySplit {
+///     fn split(&self, section: &BinSection, placed: &BinSection) -> Vec<BinSection> {
+///         let right = BinSection::new(
+///             section.x() + placed.width(),
+///             section.y(),
+///             section.z(),
+///             WidthHeightDepth::new(
+///                 section.width() - placed.width(),
+///                 section.height(),
+///                 section.depth(),
+///             ),
+///         );
+///         let above = BinSection::new(
+///             section.x(),
+///             section.y() + placed.height(),
+///             section.z(),
+///             WidthHeightDepth::new(
+///                 placed.width(),
+///                 section.height() - placed.height(),
+///                 section.depth(),
+///             ),
+///         );
+///
+///         vec![right, above]
+///     }
+/// }
+///
+/// let options = PackOptions::new().with_split_strategy(Arc::new(TwoWaySplit));
+/// ```
+///
+/// [`ComparePotentialContainersFn`]: crate::ComparePotentialContainersFn
+/// [`PackOptions.with_split_strategy`]: crate::PackOptions::with_split_strategy
+/// [`TargetBin.with_block_size`]: crate::TargetBin::with_block_size
+pub trait SplitStrategy: Debug + Send + Sync {
+    /// The free sections that replace `section` after a rectangle was placed into the space
+    /// described by `placed`, which always starts at the same corner as `section`.
+    ///
+    /// The returned sections must lie within `section` and must not overlap `placed` or each
+    /// other, otherwise the rectangle is not placed into the bin. Empty sections are ignored, and
+    /// space that isn't covered by any of them can never be placed into again.
+    fn split(&self, section: &BinSection, placed: &BinSection) -> Vec<BinSection>;
+}
+
+/// Whether the non-empty sections that a [`SplitStrategy`] returned lie within `section` without
+/// overlapping `placed` or each other.
+pub(crate) fn is_valid_split(
+    section: &BinSection,
+    placed: &BinSection,
+    split: &[BinSection],
+) -> bool {
+    split.iter().enumerate().all(|(idx, new_section)| {
+        section.contains_section(new_section)
+            && new_section.intersection(placed).is_none()
+            && split[idx + 1..]
+                .iter()
+                .all(|other| new_section.intersection(other).is_none())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        contains_smallest_box, pack_rects_with_options, volume_heuristic, GroupedRectsToPlace,
+        PackOptions, RectToInsert, TargetBin, WidthHeightDepth,
+    };
+
+    use alloc::collections::BTreeMap;
+    use alloc::sync::Arc;
+    use alloc::vec;
+
+    /// Keeps the rest of the row to the right of the rectangle and drops everything above it.
+    #[derive(Debug)]
+    struct ShelfRow;
+
+    impl SplitStrategy for ShelfRow {
+        fn split(&self, section: &BinSection, placed: &BinSection) -> Vec<BinSection> {
+            vec![BinSection::new(
+                section.x() + placed.width(),
+                section.y(),
+                section.z(),
+                WidthHeightDepth::new(
+                    section.width() - placed.width(),
+                    placed.height(),
+                    section.depth(),
+                ),
+            )]
+        }
+    }
+
+    /// Returns the free section as it was, overlapping the rectangle placed into it.
+    #[derive(Debug)]
+    struct KeepSection;
+
+    impl SplitStrategy for KeepSection {
+        fn split(&self, section: &BinSection, _placed: &BinSection) -> Vec<BinSection> {
+            vec![*section]
+        }
+    }
+
+    /// Returns a section that reaches past the free section.
+    #[derive(Debug)]
+    struct PastSection;
+
+    impl SplitStrategy for PastSection {
+        fn split(&self, section: &BinSection, placed: &BinSection) -> Vec<BinSection> {
+            vec![BinSection::new(
+                section.x() + placed.width(),
+                section.y(),
+                section.z(),
+                WidthHeightDepth::new(section.width(), section.height(), section.depth()),
+            )]
+        }
+    }
+
+    /// Verify that the free sections that the split strategy returns replace the section that a
+    /// rectangle was placed into.
+    #[test]
+    fn split_strategy_replaces_section() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect("a", None, RectToInsert::new(4, 3, 1));
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert("bin", TargetBin::new(10, 10, 1));
+
+        pack_rects_with_options(
+            &rects_to_place,
+            &mut target_bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::new().with_split_strategy(Arc::new(ShelfRow)),
+        )
+        .unwrap();
+
+        assert_eq!(
+            target_bins["bin"].available_bin_sections,
+            vec![BinSection::new(4, 0, 0, WidthHeightDepth::new(6, 3, 1))]
+        );
+    }
+
+    /// Verify that a rectangle is not placed when the split strategy returns sections that
+    /// overlap it or reach past the section that it was placed into, leaving the bin unchanged.
+    #[test]
+    fn invalid_split_is_rejected() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect("a", None, RectToInsert::new(4, 3, 1));
+
+        let split_strategies: [Arc<dyn SplitStrategy>; 2] =
+            [Arc::new(KeepSection), Arc::new(PastSection)];
+        for split_strategy in split_strategies {
+            let mut target_bins = BTreeMap::new();
+            target_bins.insert("bin", TargetBin::new(10, 10, 1));
+            let before = target_bins["bin"].available_bin_sections.clone();
+
+            let packed = pack_rects_with_options(
+                &rects_to_place,
+                &mut target_bins,
+                &volume_heuristic,
+                &contains_smallest_box,
+                &PackOptions::new().with_split_strategy(split_strategy),
+            );
+
+            assert!(packed.is_err());
+            assert_eq!(target_bins["bin"].available_bin_sections, before);
+            assert_eq!(target_bins["bin"].placed_rect_count, 0);
+        }
+    }
+}
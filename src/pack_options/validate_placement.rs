@@ -0,0 +1,88 @@
+use crate::PackedLocation;
+
+use core::fmt::Debug;
+
+/// Decides whether a rectangle may be placed at a location that the packer found for it.
+///
+/// Every location is offered before the rectangle is placed there. Rejecting it makes the packer
+/// try the rectangle's other candidate sections, and then its other bins, the same as if it
+/// hadn't fit into that section.
+///
+/// Useful for constraints that there is no built in option for, such as keeping rectangles from
+/// crossing the boundary between two tiles.
+///
+/// See [`PackOptions.with_placement_validator`].
+///
+/// ```
+/// use rectangle_pack::{PackOptions, PackedLocation, ValidatePlacement};
+/// use std::sync::Arc;
+///
+/// /// Keep every rectangle within one 64x64 tile.
+/// #[derive(Debug)]
+/// struct WithinTile;
+///
+/// impl ValidatePlacement for WithinTile {
+///     fn is_valid(&self, location: &PackedLocation) -> bool {
+///         let last_x = location.x() + location.width() - 1;
+///         let last_y = location.y() + location.height() - 1;
+///
+///         location.x() / 64 == last_x / 64 && location.y() / 64 == last_y / 64
+///     }
+/// }
+///
+/// let options = PackOptions::new().with_placement_validator(Arc::new(WithinTile));
+/// ```
+///
+/// [`PackOptions.with_placement_validator`]: crate::PackOptions::with_placement_validator
+pub trait ValidatePlacement: Debug + Send + Sync {
+    /// Whether the rectangle may be placed at the given location, which is the same location
+    /// that the packer would report for it.
+    fn is_valid(&self, location: &PackedLocation) -> bool;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        contains_smallest_box, pack_rects_with_options, volume_heuristic, GroupedRectsToPlace,
+        PackOptions, RectToInsert, TargetBin,
+    };
+
+    use alloc::collections::BTreeMap;
+    use alloc::sync::Arc;
+
+    /// Keeps every rectangle within one 4 texel wide column.
+    #[derive(Debug)]
+    struct WithinColumn;
+
+    impl ValidatePlacement for WithinColumn {
+        fn is_valid(&self, location: &PackedLocation) -> bool {
+            location.x() / 4 == (location.x() + location.width() - 1) / 4
+        }
+    }
+
+    /// Verify that a rejected location isn't used, and that the rectangle is placed elsewhere.
+    #[test]
+    fn rejected_location_not_used() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect("a", None, RectToInsert::new(3, 4, 1));
+        rects_to_place.push_rect("b", None, RectToInsert::new(3, 4, 1));
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert("first", TargetBin::new(8, 4, 1));
+        target_bins.insert("second", TargetBin::new(8, 4, 1));
+
+        let packed = pack_rects_with_options(
+            &rects_to_place,
+            &mut target_bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::new().with_placement_validator(Arc::new(WithinColumn)),
+        )
+        .unwrap();
+
+        for (_, location) in packed.packed_locations().values() {
+            assert!(WithinColumn.is_valid(location));
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! A single bin that remembers where each of its rectangles was placed.
+
+use crate::bin_section::BinSection;
+use crate::pack_options::PackContext;
+use crate::{
+    contains_smallest_box, volume_heuristic, PackOptions, PackedLocation, RectToInsert,
+    RectanglePackError, TargetBin,
+};
+
+use alloc::{collections::BTreeMap, format};
+use core::{fmt::Debug, hash::Hash};
+
+/// A [`TargetBin`] along with the location of every rectangle that was placed into it, so that
+/// rectangles can be removed by their id.
+///
+/// Removing a rectangle returns the section that it took up to the bin, the same as pushing
+/// [`TargetBin.section_occupied_by`] back using [`TargetBin.push_available_bin_section`], and
+/// gives back its [`RectToInsert.with_weight`].
+///
+/// ```
+/// use rectangle_pack::{PackedBin, RectToInsert, TargetBin};
+///
+/// let mut bin = PackedBin::new(TargetBin::new(16, 16, 1));
+///
+/// bin.insert("grass", RectToInsert::new(16, 16, 1)).unwrap();
+/// assert!(bin.insert("stone", RectToInsert::new(16, 16, 1)).is_err());
+///
+/// bin.remove(&"grass").unwrap();
+/// assert!(bin.insert("stone", RectToInsert::new(16, 16, 1)).is_ok());
+/// ```
+///
+/// [`TargetBin.section_occupied_by`]: TargetBin::section_occupied_by
+/// [`TargetBin.push_available_bin_section`]: TargetBin::push_available_bin_section
+/// [`RectToInsert.with_weight`]: RectToInsert::with_weight
+#[derive(Debug, Clone)]
+pub struct PackedBin<RectToPlaceId> {
+    bin: TargetBin,
+    options: PackOptions,
+    /// The location and weight of every rectangle in the bin.
+    placements: BTreeMap<RectToPlaceId, (PackedLocation, u64)>,
+}
+
+impl<RectToPlaceId> PackedBin<RectToPlaceId>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+{
+    /// Keep track of the rectangles placed into the given bin from now on.
+    ///
+    /// Whatever was already placed into the bin stays where it is, but can't be removed by id.
+    pub fn new(bin: TargetBin) -> Self {
+        PackedBin {
+            bin,
+            options: PackOptions::default(),
+            placements: BTreeMap::new(),
+        }
+    }
+
+    /// Place rectangles using these options instead of the defaults.
+    pub fn with_pack_options(mut self, options: PackOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Place a rectangle into the bin.
+    ///
+    /// If a rectangle with the same id is already placed its existing location is returned.
+    ///
+    /// Returns an error if the rectangle doesn't fit, in which case the bin is left unchanged.
+    pub fn insert(
+        &mut self,
+        id: RectToPlaceId,
+        rect: RectToInsert,
+    ) -> Result<PackedLocation, RectanglePackError> {
+        if let Some((location, _)) = self.placements.get(&id) {
+            return Ok(*location);
+        }
+
+        let ctx = PackContext::new(&volume_heuristic, &contains_smallest_box, &self.options);
+        let location = match self.bin.place(&rect, &ctx) {
+            Some(location) => location,
+            None => {
+                return Err(RectanglePackError::NotEnoughBinSpace {
+                    rect_to_place_id: Some(format!("{:?}", id)),
+                    group_id: None,
+                    required_volume: rect.whd.volume(),
+                    available_volume: self.bin.free_volume(),
+                });
+            }
+        };
+
+        self.placements.insert(id, (location, rect.weight));
+
+        Ok(location)
+    }
+
+    /// Remove a rectangle from the bin, returning where it was placed.
+    ///
+    /// The freed section is not merged with its neighbors, see
+    /// [`TargetBin.coalesce_all_available_sections`].
+    ///
+    /// [`TargetBin.coalesce_all_available_sections`]: TargetBin::coalesce_all_available_sections
+    pub fn remove(&mut self, id: &RectToPlaceId) -> Option<PackedLocation> {
+        let (location, weight) = self.placements.remove(id)?;
+
+        if location.effective_whd().volume() > 0 {
+            let freed = self.bin.section_occupied_by(&location);
+            self.bin.push_available_bin_section_unchecked(freed);
+        } else {
+            self.bin.placed_rect_count -= 1;
+        }
+        self.bin.placed_weight -= weight;
+
+        Some(location)
+    }
+
+    /// Where the rectangle was placed, if it is in the bin.
+    pub fn get(&self, id: &RectToPlaceId) -> Option<&PackedLocation> {
+        self.placements.get(id).map(|(location, _)| location)
+    }
+
+    /// The rectangle that covers the texel at the given position, if any.
+    ///
+    /// Padding doesn't count as part of a rectangle. See [`TargetBin.used_section_at`] for a bin
+    /// that isn't wrapped in a [`PackedBin`].
+    ///
+    /// [`TargetBin.used_section_at`]: TargetBin::used_section_at
+    pub fn rect_at(&self, x: u32, y: u32, z: u32) -> Option<(&RectToPlaceId, &PackedLocation)> {
+        self.placements().find(|(_, location)| {
+            BinSection::new(location.x, location.y, location.z, location.effective_whd())
+                .contains_point(x, y, z)
+        })
+    }
+
+    /// The ids and locations of the rectangles in the bin, in ascending order of their ids.
+    pub fn placements(&self) -> impl Iterator<Item = (&RectToPlaceId, &PackedLocation)> {
+        self.placements
+            .iter()
+            .map(|(id, (location, _))| (id, location))
+    }
+
+    /// The bin that the rectangles are placed into.
+    pub fn bin(&self) -> &TargetBin {
+        &self.bin
+    }
+
+    /// Stop keeping track of the rectangles, returning the bin.
+    pub fn into_bin(self) -> TargetBin {
+        self.bin
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that removing a rectangle gives back its space and weight, without touching the
+    /// other rectangles.
+    #[test]
+    fn remove_returns_space_and_weight() {
+        let mut bin = PackedBin::new(TargetBin::new(10, 10, 1).with_max_weight(10))
+            .with_pack_options(PackOptions::new().with_padding(1));
+
+        bin.insert("a", RectToInsert::new(3, 3, 1).with_weight(6))
+            .unwrap();
+        let b = bin
+            .insert("b", RectToInsert::new(3, 3, 1).with_weight(4))
+            .unwrap();
+
+        assert_eq!(
+            bin.remove(&"a").map(|a| (a.width(), a.height())),
+            Some((3, 3))
+        );
+        assert_eq!(bin.remove(&"a"), None);
+
+        assert_eq!(bin.bin().free_volume(), 100 - 25);
+        assert_eq!(bin.bin().placed_weight(), 4);
+        assert_eq!(bin.bin().placed_rect_count(), 1);
+        assert_eq!(bin.get(&"b"), Some(&b));
+    }
+
+    /// Verify that removing a rectangle that takes up no space gives back its weight.
+    #[test]
+    fn remove_empty_rect_returns_weight() {
+        let mut bin = PackedBin::new(TargetBin::new(10, 10, 1).with_max_weight(10));
+
+        bin.insert("a", RectToInsert::new(0, 3, 1).with_weight(6))
+            .unwrap();
+        assert_eq!(bin.bin().placed_weight(), 6);
+        assert_eq!(bin.bin().placed_rect_count(), 1);
+
+        bin.remove(&"a").unwrap();
+
+        assert_eq!(bin.bin().placed_weight(), 0);
+        assert_eq!(bin.bin().placed_rect_count(), 0);
+        assert_eq!(bin.bin().free_volume(), 100);
+    }
+
+    /// Verify that the rectangle at a position is found, while its padding and free space are
+    /// not.
+    #[test]
+    fn rect_at_position() {
+        let mut bin = PackedBin::new(TargetBin::new(10, 10, 1))
+            .with_pack_options(PackOptions::new().with_padding(1));
+
+        let a = bin.insert("a", RectToInsert::new(3, 2, 1)).unwrap();
+
+        assert_eq!(bin.rect_at(a.x() + 2, a.y() + 1, 0), Some((&"a", &a)));
+        assert_eq!(bin.rect_at(a.x() + 3, a.y(), 0), None);
+        assert_eq!(bin.rect_at(9, 9, 0), None);
+    }
+}
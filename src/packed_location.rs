@@ -7,16 +7,83 @@ pub struct PackedLocation {
     pub(crate) y: u32,
     pub(crate) z: u32,
     pub(crate) whd: WidthHeightDepth,
+    pub(crate) allocated_whd: WidthHeightDepth,
     pub(crate) x_axis_rotation: RotatedBy,
     pub(crate) y_axis_rotation: RotatedBy,
     pub(crate) z_axis_rotation: RotatedBy,
+    pub(crate) flipped_horizontally: bool,
+    pub(crate) flipped_vertically: bool,
+    pub(crate) padding: u32,
+    pub(crate) content_hash: Option<u64>,
+    /// The origin of the bin that the rectangle was placed into, see [`TargetBin.with_origin`].
+    ///
+    /// [`TargetBin.with_origin`]: crate::TargetBin::with_origin
+    pub(crate) origin: [i64; 3],
 }
 
-#[derive(Debug, PartialEq, Copy, Clone)]
-#[allow(unused)] // TODO: Implement rotations
+/// How far a placed rectangle was rotated, counterclockwise, about one of the axes.
+///
+/// The packer only ever rotates by [`RotatedBy::ZeroDegrees`] or [`RotatedBy::NinetyDegrees`].
+/// The other variants are there for renderers that combine the packer's rotation with their own.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[allow(missing_docs)]
 pub enum RotatedBy {
     ZeroDegrees,
     NinetyDegrees,
+    OneHundredEightyDegrees,
+    TwoHundredSeventyDegrees,
+}
+
+impl RotatedBy {
+    /// The rotation for the given number of degrees, if it is a multiple of 90.
+    pub fn from_degrees(degrees: u32) -> Option<Self> {
+        match degrees % 360 {
+            0 => Some(RotatedBy::ZeroDegrees),
+            90 => Some(RotatedBy::NinetyDegrees),
+            180 => Some(RotatedBy::OneHundredEightyDegrees),
+            270 => Some(RotatedBy::TwoHundredSeventyDegrees),
+            _ => None,
+        }
+    }
+
+    /// The rotation in degrees.
+    pub fn degrees(&self) -> u32 {
+        match self {
+            RotatedBy::ZeroDegrees => 0,
+            RotatedBy::NinetyDegrees => 90,
+            RotatedBy::OneHundredEightyDegrees => 180,
+            RotatedBy::TwoHundredSeventyDegrees => 270,
+        }
+    }
+
+    /// The rotation in radians.
+    pub fn radians(&self) -> f32 {
+        self.degrees() as f32 * core::f32::consts::PI / 180.
+    }
+
+    /// The row major 2x2 matrix that rotates a point counterclockwise by this rotation, so
+    /// `[x, y]` becomes `[m[0][0] * x + m[0][1] * y, m[1][0] * x + m[1][1] * y]`.
+    ///
+    /// The entries are exact, without any floating point error.
+    pub fn matrix(&self) -> [[f32; 2]; 2] {
+        let (cos, sin) = match self {
+            RotatedBy::ZeroDegrees => (1., 0.),
+            RotatedBy::NinetyDegrees => (0., 1.),
+            RotatedBy::OneHundredEightyDegrees => (-1., 0.),
+            RotatedBy::TwoHundredSeventyDegrees => (0., -1.),
+        };
+
+        [[cos, -sin], [sin, cos]]
+    }
+
+    /// Whether the rotation swaps the two axes that it rotates, as a 90 or 270 degree rotation
+    /// does.
+    pub fn swaps_axes(&self) -> bool {
+        match self {
+            RotatedBy::ZeroDegrees | RotatedBy::OneHundredEightyDegrees => false,
+            RotatedBy::NinetyDegrees | RotatedBy::TwoHundredSeventyDegrees => true,
+        }
+    }
 }
 
 #[allow(missing_docs)]
@@ -44,4 +111,518 @@ impl PackedLocation {
     pub fn depth(&self) -> u32 {
         self.whd.depth
     }
+
+    /// The space that was set aside for the rectangle, before applying its rotations.
+    ///
+    /// This is larger than the [`PackedLocation.width`], [`PackedLocation.height`] and
+    /// [`PackedLocation.depth`] that were requested when [`PackOptions.with_size_rounding`] rounded
+    /// the rectangle's size up. The rectangle starts at the same corner as its allocated space.
+    ///
+    /// [`PackedLocation.width`]: PackedLocation::width
+    /// [`PackedLocation.height`]: PackedLocation::height
+    /// [`PackedLocation.depth`]: PackedLocation::depth
+    /// [`PackOptions.with_size_rounding`]: crate::PackOptions::with_size_rounding
+    pub fn allocated_whd(&self) -> WidthHeightDepth {
+        self.allocated_whd
+    }
+
+    /// How far the rectangle was rotated about the x axis.
+    ///
+    /// See [`PackedLocation.z_axis_rotation`] for the order that rotations are applied in.
+    ///
+    /// [`PackedLocation.z_axis_rotation`]: PackedLocation::z_axis_rotation
+    pub fn x_axis_rotation(&self) -> RotatedBy {
+        self.x_axis_rotation
+    }
+
+    /// How far the rectangle was rotated about the y axis.
+    ///
+    /// See [`PackedLocation.z_axis_rotation`] for the order that rotations are applied in.
+    ///
+    /// [`PackedLocation.z_axis_rotation`]: PackedLocation::z_axis_rotation
+    pub fn y_axis_rotation(&self) -> RotatedBy {
+        self.y_axis_rotation
+    }
+
+    /// How far the rectangle was rotated about the z axis.
+    ///
+    /// The [`PackedLocation.width`], [`PackedLocation.height`] and [`PackedLocation.depth`] are
+    /// always those of the rectangle before it was rotated. For example, when only rotated about
+    /// the z axis by [`RotatedBy::NinetyDegrees`] the rectangle takes up `height` columns and
+    /// `width` rows of the bin.
+    ///
+    /// When the rectangle was rotated about more than one axis, the rotation about the x axis is
+    /// applied first, then the y axis, then the z axis.
+    ///
+    /// [`PackedLocation.width`]: PackedLocation::width
+    /// [`PackedLocation.height`]: PackedLocation::height
+    /// [`PackedLocation.depth`]: PackedLocation::depth
+    pub fn z_axis_rotation(&self) -> RotatedBy {
+        self.z_axis_rotation
+    }
+
+    /// Whether the rectangle's contents should be mirrored from left to right, after applying its
+    /// rotations.
+    ///
+    /// This is always `false` unless a [`CompareOrientations`] chose to flip the rectangle.
+    ///
+    /// See [`RectToInsert.with_horizontal_flip`].
+    ///
+    /// [`CompareOrientations`]: crate::CompareOrientations
+    /// [`RectToInsert.with_horizontal_flip`]: crate::RectToInsert::with_horizontal_flip
+    pub fn flipped_horizontally(&self) -> bool {
+        self.flipped_horizontally
+    }
+
+    /// Whether the rectangle's contents should be mirrored from top to bottom, after applying its
+    /// rotations.
+    ///
+    /// This is always `false` unless a [`CompareOrientations`] chose to flip the rectangle.
+    ///
+    /// See [`RectToInsert.with_vertical_flip`].
+    ///
+    /// [`CompareOrientations`]: crate::CompareOrientations
+    /// [`RectToInsert.with_vertical_flip`]: crate::RectToInsert::with_vertical_flip
+    pub fn flipped_vertically(&self) -> bool {
+        self.flipped_vertically
+    }
+
+    /// The number of texels that were reserved on every side of the rectangle.
+    ///
+    /// See [`RectToInsert.with_padding`].
+    ///
+    /// [`RectToInsert.with_padding`]: crate::RectToInsert::with_padding
+    pub fn padding(&self) -> u32 {
+        self.padding
+    }
+
+    /// The hash of the rectangle's contents that it was placed with, if any.
+    ///
+    /// See [`RectToInsert.with_content_hash`].
+    ///
+    /// [`RectToInsert.with_content_hash`]: crate::RectToInsert::with_content_hash
+    pub fn content_hash(&self) -> Option<u64> {
+        self.content_hash
+    }
+
+    /// The position of the bin's `(0, 0, 0)` in the caller's coordinate space, see
+    /// [`TargetBin.with_origin`].
+    ///
+    /// [`TargetBin.with_origin`]: crate::TargetBin::with_origin
+    pub fn origin(&self) -> [i64; 3] {
+        self.origin
+    }
+
+    /// The [`PackedLocation.x`] offset by the bin's origin, or `None` if that doesn't fit into
+    /// an `i64`.
+    ///
+    /// [`PackedLocation.x`]: PackedLocation::x
+    pub fn world_x(&self) -> Option<i64> {
+        world(self.origin[0], self.x)
+    }
+
+    /// The [`PackedLocation.y`] offset by the bin's origin, or `None` if that doesn't fit into
+    /// an `i64`.
+    ///
+    /// [`PackedLocation.y`]: PackedLocation::y
+    pub fn world_y(&self) -> Option<i64> {
+        world(self.origin[1], self.y)
+    }
+
+    /// The [`PackedLocation.z`] offset by the bin's origin, or `None` if that doesn't fit into
+    /// an `i64`.
+    ///
+    /// [`PackedLocation.z`]: PackedLocation::z
+    pub fn world_z(&self) -> Option<i64> {
+        world(self.origin[2], self.z)
+    }
+
+    /// Whether the rectangle was rotated about any of the axes.
+    pub fn is_rotated(&self) -> bool {
+        [
+            self.x_axis_rotation,
+            self.y_axis_rotation,
+            self.z_axis_rotation,
+        ]
+        .iter()
+        .any(|rotation| *rotation != RotatedBy::ZeroDegrees)
+    }
+
+    /// The axis aligned space that the rectangle takes up within the bin, after applying its
+    /// rotations.
+    ///
+    /// For a rectangle that was not rotated this is the same as its width, height and depth.
+    pub fn effective_whd(&self) -> WidthHeightDepth {
+        rotate(
+            self.whd,
+            [
+                self.x_axis_rotation,
+                self.y_axis_rotation,
+                self.z_axis_rotation,
+            ],
+        )
+    }
+}
+
+impl PackedLocation {
+    /// A rectangle of the given size at the given position, without any rotations, flips or
+    /// padding, such as for testing code that consumes placements.
+    ///
+    /// ```
+    /// use rectangle_pack::{PackedLocation, RotatedBy, WidthHeightDepth};
+    ///
+    /// let location = PackedLocation::new(4, 8, 0, WidthHeightDepth::new(16, 32, 1))
+    ///     .with_z_axis_rotation(RotatedBy::NinetyDegrees);
+    ///
+    /// assert_eq!(location.effective_whd(), WidthHeightDepth::new(32, 16, 1));
+    /// ```
+    pub fn new(x: u32, y: u32, z: u32, whd: WidthHeightDepth) -> Self {
+        PackedLocation {
+            x,
+            y,
+            z,
+            whd,
+            allocated_whd: whd,
+            x_axis_rotation: RotatedBy::ZeroDegrees,
+            y_axis_rotation: RotatedBy::ZeroDegrees,
+            z_axis_rotation: RotatedBy::ZeroDegrees,
+            flipped_horizontally: false,
+            flipped_vertically: false,
+            padding: 0,
+            content_hash: None,
+            origin: [0, 0, 0],
+        }
+    }
+
+    /// Move the rectangle so that it starts at the given position, such as to offset it into a
+    /// larger texture.
+    pub fn with_position(mut self, x: u32, y: u32, z: u32) -> Self {
+        self.x = x;
+        self.y = y;
+        self.z = z;
+        self
+    }
+
+    /// See [`PackedLocation.origin`].
+    ///
+    /// [`PackedLocation.origin`]: PackedLocation::origin
+    pub fn with_origin(mut self, x: i64, y: i64, z: i64) -> Self {
+        self.origin = [x, y, z];
+        self
+    }
+
+    /// See [`PackedLocation.allocated_whd`].
+    ///
+    /// [`PackedLocation.allocated_whd`]: PackedLocation::allocated_whd
+    pub fn with_allocated_whd(mut self, allocated_whd: WidthHeightDepth) -> Self {
+        self.allocated_whd = allocated_whd;
+        self
+    }
+
+    /// See [`PackedLocation.x_axis_rotation`].
+    ///
+    /// [`PackedLocation.x_axis_rotation`]: PackedLocation::x_axis_rotation
+    pub fn with_x_axis_rotation(mut self, rotation: RotatedBy) -> Self {
+        self.x_axis_rotation = rotation;
+        self
+    }
+
+    /// See [`PackedLocation.y_axis_rotation`].
+    ///
+    /// [`PackedLocation.y_axis_rotation`]: PackedLocation::y_axis_rotation
+    pub fn with_y_axis_rotation(mut self, rotation: RotatedBy) -> Self {
+        self.y_axis_rotation = rotation;
+        self
+    }
+
+    /// See [`PackedLocation.z_axis_rotation`].
+    ///
+    /// [`PackedLocation.z_axis_rotation`]: PackedLocation::z_axis_rotation
+    pub fn with_z_axis_rotation(mut self, rotation: RotatedBy) -> Self {
+        self.z_axis_rotation = rotation;
+        self
+    }
+
+    /// See [`PackedLocation.flipped_horizontally`].
+    ///
+    /// [`PackedLocation.flipped_horizontally`]: PackedLocation::flipped_horizontally
+    pub fn with_horizontal_flip(mut self, flipped: bool) -> Self {
+        self.flipped_horizontally = flipped;
+        self
+    }
+
+    /// See [`PackedLocation.flipped_vertically`].
+    ///
+    /// [`PackedLocation.flipped_vertically`]: PackedLocation::flipped_vertically
+    pub fn with_vertical_flip(mut self, flipped: bool) -> Self {
+        self.flipped_vertically = flipped;
+        self
+    }
+
+    /// See [`PackedLocation.padding`].
+    ///
+    /// The padding lies outside of the rectangle, so the rectangle must start at least `padding`
+    /// texels away from the bin's edges.
+    ///
+    /// [`PackedLocation.padding`]: PackedLocation::padding
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// See [`PackedLocation.content_hash`].
+    ///
+    /// [`PackedLocation.content_hash`]: PackedLocation::content_hash
+    pub fn with_content_hash(mut self, content_hash: u64) -> Self {
+        self.content_hash = Some(content_hash);
+        self
+    }
+}
+
+impl PackedLocation {
+    /// The `[u_min, v_min, u_max, v_max]` texture coordinates of the space that the rectangle
+    /// takes up within a bin of the given size, after applying its rotations.
+    ///
+    /// To sample a rotated or flipped rectangle the right way up use
+    /// [`PackedLocation.uv_corners`] instead.
+    ///
+    /// [`PackedLocation.uv_corners`]: PackedLocation::uv_corners
+    pub fn uv_rect(&self, bin_width: u32, bin_height: u32) -> [f32; 4] {
+        let effective_whd = self.effective_whd();
+
+        [
+            self.x as f32 / bin_width as f32,
+            self.y as f32 / bin_height as f32,
+            (self.x + effective_whd.width) as f32 / bin_width as f32,
+            (self.y + effective_whd.height) as f32 / bin_height as f32,
+        ]
+    }
+
+    /// The `[u, v]` texture coordinates, within a bin of the given size, of the corners `(0, 0)`,
+    /// `(width, 0)`, `(width, height)` and `(0, height)` of the rectangle's contents, in that
+    /// order.
+    ///
+    /// These undo the rectangle's rotation about the z axis and its flips, so a quad drawn with
+    /// them shows the contents the same way up as before they were packed. Rotations about the x
+    /// and y axes only apply to 3d boxes and are ignored.
+    pub fn uv_corners(&self, bin_width: u32, bin_height: u32) -> [[f32; 2]; 4] {
+        let width = self.whd.width as f32;
+        let height = self.whd.height as f32;
+        let effective_whd = self.effective_whd();
+        let matrix = self.z_axis_rotation.matrix();
+
+        let mut corners = [[0., 0.], [width, 0.], [width, height], [0., height]];
+        for corner in corners.iter_mut() {
+            let [x, y] = *corner;
+            *corner = [
+                matrix[0][0] * x + matrix[0][1] * y,
+                matrix[1][0] * x + matrix[1][1] * y,
+            ];
+        }
+
+        // Move the rotated corners back to start at the rectangle's location.
+        let min_x = corners.iter().map(|[x, _]| *x).fold(f32::MAX, f32::min);
+        let min_y = corners.iter().map(|[_, y]| *y).fold(f32::MAX, f32::min);
+        for [x, y] in corners.iter_mut() {
+            *x -= min_x;
+            *y -= min_y;
+
+            if self.flipped_horizontally {
+                *x = effective_whd.width as f32 - *x;
+            }
+            if self.flipped_vertically {
+                *y = effective_whd.height as f32 - *y;
+            }
+
+            *x = (self.x as f32 + *x) / bin_width as f32;
+            *y = (self.y as f32 + *y) / bin_height as f32;
+        }
+
+        corners
+    }
+
+    /// The location of the rectangle of the `requested` size within a padded location that was
+    /// placed into a bin.
+    pub(crate) fn unpadded(mut self, padding: u32, requested: WidthHeightDepth) -> PackedLocation {
+        let offset = self.padding_offset(padding);
+
+        self.x += offset.width;
+        self.y += offset.height;
+        self.z += offset.depth;
+        self.allocated_whd.width -= 2 * padding;
+        self.allocated_whd.height -= 2 * padding;
+        self.whd = requested;
+        self.padding = padding;
+
+        self
+    }
+
+    /// The location, including the padding and any rounding up, that was placed into the bin.
+    pub(crate) fn padded(mut self) -> PackedLocation {
+        let offset = self.padding_offset(self.padding);
+
+        self.x -= offset.width;
+        self.y -= offset.height;
+        self.z -= offset.depth;
+        self.allocated_whd.width += 2 * self.padding;
+        self.allocated_whd.height += 2 * self.padding;
+        self.whd = self.allocated_whd;
+        self.padding = 0;
+
+        self
+    }
+
+    /// How far the padding moves the rectangle along each of the bin's axes.
+    fn padding_offset(&self, padding: u32) -> WidthHeightDepth {
+        rotate(
+            WidthHeightDepth {
+                width: padding,
+                height: padding,
+                depth: 0,
+            },
+            [
+                self.x_axis_rotation,
+                self.y_axis_rotation,
+                self.z_axis_rotation,
+            ],
+        )
+    }
+}
+
+/// The coordinate offset by the origin, or `None` if that doesn't fit into an `i64`.
+fn world(origin: i64, coordinate: u32) -> Option<i64> {
+    origin.checked_add(coordinate as i64)
+}
+
+/// The space that a box takes up after rotating it about the x, then y, then z axis by the given
+/// amounts.
+///
+/// Rotating by 90 (or 270) degrees about the x axis swaps the height and depth, about the y axis swaps the
+/// width and depth and about the z axis swaps the width and height.
+pub(crate) fn rotate(whd: WidthHeightDepth, rotations: [RotatedBy; 3]) -> WidthHeightDepth {
+    let [x_axis_rotation, y_axis_rotation, z_axis_rotation] = rotations;
+    let mut rotated = whd;
+
+    if x_axis_rotation.swaps_axes() {
+        core::mem::swap(&mut rotated.height, &mut rotated.depth);
+    }
+    if y_axis_rotation.swaps_axes() {
+        core::mem::swap(&mut rotated.width, &mut rotated.depth);
+    }
+    if z_axis_rotation.swaps_axes() {
+        core::mem::swap(&mut rotated.width, &mut rotated.height);
+    }
+
+    rotated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use RotatedBy::*;
+
+    /// Verify that world coordinates that don't fit into an `i64` are `None` instead of
+    /// overflowing.
+    #[test]
+    fn world_coordinates_out_of_range() {
+        let location = PackedLocation::new(10, 0, 0, WidthHeightDepth::new(1, 1, 1)).with_origin(
+            i64::MAX - 10,
+            i64::MIN,
+            0,
+        );
+        assert_eq!(location.world_x(), Some(i64::MAX));
+        assert_eq!(location.world_y(), Some(i64::MIN));
+
+        let location = location.with_position(11, 0, 0);
+        assert_eq!(location.world_x(), None);
+
+        let far = location.with_position(u32::MAX, 0, 0);
+        assert_eq!(far.world_x(), None);
+        assert_eq!(
+            far.with_origin(i64::MIN, 0, 0).world_x(),
+            Some(i64::MIN + u32::MAX as i64)
+        );
+    }
+
+    /// Verify that the rotations about the three axes reach all six orientations of a box.
+    #[test]
+    fn rotations_reach_every_orientation() {
+        let whd = WidthHeightDepth::new(1, 2, 3);
+
+        let mut orientations: Vec<(u32, u32, u32)> = [
+            [ZeroDegrees, ZeroDegrees, ZeroDegrees],
+            [ZeroDegrees, ZeroDegrees, NinetyDegrees],
+            [NinetyDegrees, ZeroDegrees, ZeroDegrees],
+            [ZeroDegrees, NinetyDegrees, ZeroDegrees],
+            [NinetyDegrees, NinetyDegrees, ZeroDegrees],
+            [NinetyDegrees, ZeroDegrees, NinetyDegrees],
+        ]
+        .iter()
+        .map(|rotations| {
+            let rotated = rotate(whd, *rotations);
+            (rotated.width, rotated.height, rotated.depth)
+        })
+        .collect();
+        orientations.sort();
+        orientations.dedup();
+
+        assert_eq!(orientations.len(), 6);
+    }
+
+    /// Verify that converting to and from degrees round trips and that the matrices rotate
+    /// counterclockwise.
+    #[test]
+    fn rotation_conversions() {
+        for rotation in [
+            ZeroDegrees,
+            NinetyDegrees,
+            OneHundredEightyDegrees,
+            TwoHundredSeventyDegrees,
+        ]
+        .iter()
+        {
+            assert_eq!(RotatedBy::from_degrees(rotation.degrees()), Some(*rotation));
+        }
+        assert_eq!(RotatedBy::from_degrees(45), None);
+
+        assert_eq!(NinetyDegrees.matrix(), [[0., -1.], [1., 0.]]);
+        assert_eq!(OneHundredEightyDegrees.radians(), core::f32::consts::PI);
+    }
+
+    /// Verify that the texture coordinates of a rotated rectangle cover the space that it takes
+    /// up, with its corners rotated along with it.
+    #[test]
+    fn uv_of_rotated_rect() {
+        let location = PackedLocation {
+            x: 2,
+            y: 0,
+            z: 0,
+            whd: WidthHeightDepth::new(2, 1, 1),
+            allocated_whd: WidthHeightDepth::new(2, 1, 1),
+            x_axis_rotation: ZeroDegrees,
+            y_axis_rotation: ZeroDegrees,
+            z_axis_rotation: NinetyDegrees,
+            flipped_horizontally: false,
+            flipped_vertically: false,
+            padding: 0,
+            content_hash: None,
+            origin: [0, 0, 0],
+        };
+
+        assert_eq!(location.uv_rect(4, 4), [0.5, 0., 0.75, 0.5]);
+        assert_eq!(
+            location.uv_corners(4, 4),
+            [[0.75, 0.], [0.75, 0.5], [0.5, 0.5], [0.5, 0.]]
+        );
+
+        let flipped = PackedLocation {
+            flipped_horizontally: true,
+            ..location
+        };
+        assert_eq!(
+            flipped.uv_corners(4, 4),
+            [[0.5, 0.], [0.5, 0.5], [0.75, 0.5], [0.75, 0.]]
+        );
+    }
 }
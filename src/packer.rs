@@ -0,0 +1,261 @@
+//! A reusable configuration for packing rectangles.
+
+use crate::pack_options::PackContext;
+use crate::{
+    contains_smallest_box, pack_rects_keyed_by, volume_heuristic, BoxSizeHeuristic,
+    BoxSizeHeuristicFn, ComparePotentialContainers, ComparePotentialContainersFn,
+    GroupedRectsToPlace, PackOptions, RectanglePackError, RectanglePackOk, TargetBin,
+};
+
+use alloc::collections::BTreeMap;
+use core::{cell::RefCell, fmt::Debug, hash::Hash};
+
+pub(crate) use self::scratch::{recycle, PackScratch};
+
+mod scratch;
+
+/// Everything that controls how rectangles get packed, gathered in one place so that it can be
+/// set up once and then used for every call to [`Packer.pack`].
+///
+/// The packer also holds on to the temporary buffers that packing needs, such as the copies of
+/// the bins that groups are test placed into, so that packing every frame doesn't allocate them
+/// over and over again.
+///
+/// Defaults to [`volume_heuristic`], [`contains_smallest_box`] and the default [`PackOptions`],
+/// which packs exactly the same way as [`pack_rects`].
+///
+/// ```
+/// use rectangle_pack::{GroupedRectsToPlace, PackOptions, Packer, RectToInsert, TargetBin};
+/// use std::collections::BTreeMap;
+///
+/// let mut packer = Packer::new().with_pack_options(PackOptions::new().with_padding(1));
+///
+/// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+/// rects_to_place.push_rect("grass", None, RectToInsert::new(8, 8, 1));
+///
+/// let mut target_bins = BTreeMap::new();
+/// target_bins.insert("atlas", TargetBin::new(10, 10, 1));
+///
+/// let packed = packer.pack(&rects_to_place, &mut target_bins).unwrap();
+/// assert_eq!(packed.packed_locations()["grass"].1.x(), 1);
+/// ```
+///
+/// [`Packer.pack`]: Packer::pack
+/// [`volume_heuristic`]: crate::volume_heuristic
+/// [`contains_smallest_box`]: crate::contains_smallest_box
+/// [`pack_rects`]: crate::pack_rects
+pub struct Packer<
+    'a,
+    Heuristic: ?Sized = BoxSizeHeuristicFn<'a>,
+    Compare: ?Sized = ComparePotentialContainersFn<'a>,
+> {
+    box_size_heuristic: &'a Heuristic,
+    more_suitable_containers_fn: &'a Compare,
+    options: PackOptions,
+    scratch: RefCell<PackScratch>,
+}
+
+impl<'a> Packer<'a> {
+    /// Create a packer that uses the defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<'a, Heuristic, Compare> Packer<'a, Heuristic, Compare>
+where
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+{
+    /// Decide which rectangles and free sections are larger using this heuristic instead of
+    /// [`volume_heuristic`].
+    ///
+    /// [`volume_heuristic`]: crate::volume_heuristic
+    pub fn with_box_size_heuristic<NewHeuristic: BoxSizeHeuristic + ?Sized>(
+        self,
+        box_size_heuristic: &'a NewHeuristic,
+    ) -> Packer<'a, NewHeuristic, Compare> {
+        Packer {
+            box_size_heuristic,
+            more_suitable_containers_fn: self.more_suitable_containers_fn,
+            options: self.options,
+            scratch: self.scratch,
+        }
+    }
+
+    /// Decide how to split free sections using this comparison instead of
+    /// [`contains_smallest_box`].
+    ///
+    /// [`contains_smallest_box`]: crate::contains_smallest_box
+    pub fn with_more_suitable_containers_fn<NewCompare: ComparePotentialContainers + ?Sized>(
+        self,
+        more_suitable_containers_fn: &'a NewCompare,
+    ) -> Packer<'a, Heuristic, NewCompare> {
+        Packer {
+            box_size_heuristic: self.box_size_heuristic,
+            more_suitable_containers_fn,
+            options: self.options,
+            scratch: self.scratch,
+        }
+    }
+
+    /// Pack using these options instead of the defaults.
+    pub fn with_pack_options(mut self, options: PackOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// The options that rectangles are packed with.
+    pub fn pack_options(&self) -> &PackOptions {
+        &self.options
+    }
+
+    /// Pack the rectangles into the bins, the same way as [`pack_rects_with_options`].
+    ///
+    /// [`pack_rects_with_options`]: crate::pack_rects_with_options
+    pub fn pack<RectToPlaceId, BinId, GroupId>(
+        &mut self,
+        rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+        target_bins: &mut BTreeMap<BinId, TargetBin>,
+    ) -> Result<RectanglePackOk<RectToPlaceId, BinId>, RectanglePackError>
+    where
+        RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+        BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+        GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    {
+        let mut packed = RectanglePackOk::default();
+        self.pack_reusing(rects_to_place, target_bins, &mut packed)?;
+
+        Ok(packed)
+    }
+
+    /// Pack the rectangles into the bins, writing the placements into a [`RectanglePackOk`] from
+    /// an earlier call, the same way as [`pack_rects_reusing`].
+    ///
+    /// Every buffer that packing needs is kept in the packer, the bins or `packed`. So once the
+    /// same rectangles have been packed before, packing them again into bins that were
+    /// [`TargetBin.clear`]ed with the default options only allocates the nodes of the `BTreeMap`
+    /// that the placements are written into.
+    ///
+    /// [`pack_rects_reusing`]: crate::pack_rects_reusing
+    /// [`TargetBin.clear`]: crate::TargetBin::clear
+    pub fn pack_reusing<RectToPlaceId, BinId, GroupId>(
+        &mut self,
+        rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+        target_bins: &mut BTreeMap<BinId, TargetBin>,
+        packed: &mut RectanglePackOk<RectToPlaceId, BinId>,
+    ) -> Result<(), RectanglePackError>
+    where
+        RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+        BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+        GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    {
+        let ctx = PackContext {
+            scratch: Some(&self.scratch),
+            ..PackContext::new(
+                self.box_size_heuristic,
+                self.more_suitable_containers_fn,
+                &self.options,
+            )
+        };
+
+        pack_rects_keyed_by(
+            rects_to_place,
+            target_bins,
+            None,
+            ctx,
+            RectToPlaceId::clone,
+            packed,
+        )
+    }
+}
+
+impl Default for Packer<'_> {
+    fn default() -> Self {
+        Packer {
+            box_size_heuristic: &volume_heuristic,
+            more_suitable_containers_fn: &contains_smallest_box,
+            options: PackOptions::default(),
+            scratch: RefCell::default(),
+        }
+    }
+}
+
+impl<Heuristic: ?Sized, Compare: ?Sized> Clone for Packer<'_, Heuristic, Compare> {
+    fn clone(&self) -> Self {
+        Packer {
+            box_size_heuristic: self.box_size_heuristic,
+            more_suitable_containers_fn: self.more_suitable_containers_fn,
+            options: self.options.clone(),
+            scratch: self.scratch.clone(),
+        }
+    }
+}
+
+impl<Heuristic: ?Sized, Compare: ?Sized> Debug for Packer<'_, Heuristic, Compare> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Packer")
+            .field("options", &self.options)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pack_rects, RectToInsert, WidthHeightDepth};
+
+    use alloc::vec;
+
+    /// Verify that a custom heuristic is used, by ranking the bins so that the larger one is
+    /// tried first.
+    #[test]
+    fn uses_custom_box_size_heuristic() {
+        let inverse_volume = |whd: WidthHeightDepth| u128::MAX - volume_heuristic(whd);
+        let mut packer = Packer::new().with_box_size_heuristic(&inverse_volume);
+
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect("a", None, RectToInsert::new(2, 2, 1));
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert("small", TargetBin::new(5, 5, 1));
+        target_bins.insert("large", TargetBin::new(50, 50, 1));
+
+        let packed = packer.pack(&rects_to_place, &mut target_bins).unwrap();
+        assert_eq!(packed.packed_locations()["a"].0, "large");
+    }
+
+    /// Verify that packing again with the same packer, reusing its buffers, places the same way
+    /// as a fresh pack.
+    #[test]
+    fn repeated_packs_match_fresh_pack() {
+        let mut packer = Packer::new();
+
+        let mut rects_to_place: GroupedRectsToPlace<_, u8> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect("a", Some(vec![0]), RectToInsert::new(6, 4, 1));
+        rects_to_place.push_rect("b", Some(vec![0]), RectToInsert::new(4, 4, 1));
+        rects_to_place.push_rect("c", None, RectToInsert::new(9, 9, 1));
+        let copies = vec!["a", "c"];
+        rects_to_place.push_distinct_bin_set(copies);
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert(0, TargetBin::new(10, 10, 1));
+        target_bins.insert(1, TargetBin::new(10, 10, 1));
+
+        let expected = pack_rects(
+            &rects_to_place,
+            &mut target_bins.clone(),
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+
+        let mut packed = RectanglePackOk::default();
+        for _ in 0..3 {
+            packer
+                .pack_reusing(&rects_to_place, &mut target_bins.clone(), &mut packed)
+                .unwrap();
+            assert_eq!(packed, expected);
+        }
+    }
+}
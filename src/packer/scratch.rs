@@ -0,0 +1,148 @@
+//! Buffers that are reused from one pack to the next, see [`Packer`].
+//!
+//! [`Packer`]: crate::Packer
+
+use crate::grouped_rects_to_place::MergedGroup;
+use crate::{PackedLocation, PlacedGroup, TargetBin};
+
+use alloc::vec::Vec;
+use core::mem::MaybeUninit;
+
+/// The temporary buffers of [`place_groups`], so that they can outlive a single pack.
+///
+/// Buffers that borrow from the rectangles or bins of a pack can't be kept as they are, so they
+/// are kept empty in between packs as buffers of [`StandIn`] items and converted using
+/// [`recycle`]. Keeping their memory this way is best-effort, a pack that can't reuse it only
+/// allocates new buffers.
+///
+/// [`place_groups`]: crate::place_groups
+#[derive(Debug, Clone)]
+pub(crate) struct PackScratch {
+    /// The copy of a bin that groups are placed into while checking whether they fit, without
+    /// changing the real bin.
+    pub(crate) trial_bin: TargetBin,
+    /// The `(bin index, split rank)` pairs to try placing the current group with.
+    pub(crate) alternatives: Vec<(usize, usize)>,
+    /// The number of placed groups in each bin.
+    pub(crate) groups_per_bin: Vec<usize>,
+    /// The indices of the bins that each distinct bin set's placed rectangles are in.
+    pub(crate) bins_per_distinct_bin_set: Vec<Vec<usize>>,
+    /// The groups that have been placed or left out so far, in the order that they were tried.
+    pub(crate) placed_groups: Vec<PlacedGroup>,
+    /// The locations of the placed rectangles, in the same order as their groups and the
+    /// rectangles within them.
+    pub(crate) placements: Vec<PackedLocation>,
+    /// Stands in for the `(&BinId, &mut TargetBin)` pairs of the bins being packed into.
+    pub(crate) bins: Vec<StandIn<(&'static (), &'static TargetBin)>>,
+    /// Stands in for the merged groups of the rectangles being packed.
+    pub(crate) merged_groups: Vec<StandIn<MergedGroup<'static, (), ()>>>,
+}
+
+impl PackScratch {
+    /// Empty the buffers for a pack into `bin_count` bins with `distinct_bin_set_count` distinct
+    /// bin sets, keeping their allocations.
+    pub(crate) fn reset(&mut self, bin_count: usize, distinct_bin_set_count: usize) {
+        self.alternatives.clear();
+        self.placed_groups.clear();
+        self.placements.clear();
+
+        self.groups_per_bin.clear();
+        self.groups_per_bin.resize(bin_count, 0);
+
+        self.bins_per_distinct_bin_set
+            .truncate(distinct_bin_set_count);
+        for bins in self.bins_per_distinct_bin_set.iter_mut() {
+            bins.clear();
+        }
+        self.bins_per_distinct_bin_set
+            .resize_with(distinct_bin_set_count, Vec::new);
+    }
+}
+
+impl Default for PackScratch {
+    fn default() -> Self {
+        PackScratch {
+            trial_bin: TargetBin::new(0, 0, 0),
+            alternatives: Vec::new(),
+            groups_per_bin: Vec::new(),
+            bins_per_distinct_bin_set: Vec::new(),
+            placed_groups: Vec::new(),
+            placements: Vec::new(),
+            bins: Vec::new(),
+            merged_groups: Vec::new(),
+        }
+    }
+}
+
+/// An item of an empty buffer that is only kept for its memory, with the same size and alignment
+/// as the `T` items that the buffer gets converted to with [`recycle`].
+///
+/// Stand-in items can't be created, so a buffer of them is always empty.
+pub(crate) struct StandIn<T>(MaybeUninit<T>, Never);
+
+/// A type without any values.
+enum Never {}
+
+impl<T> core::fmt::Debug for StandIn<T> {
+    fn fmt(&self, _: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self.1 {}
+    }
+}
+
+impl<T> Clone for StandIn<T> {
+    fn clone(&self) -> Self {
+        match self.1 {}
+    }
+}
+
+/// Empty the buffer and convert it into a buffer of another type, such as from [`StandIn`] items
+/// to items that borrow from the rectangles of the current pack, or back.
+///
+/// This relies on `std` collecting a mapped `vec::IntoIter` into the memory of the original
+/// `Vec` when both item types have the same size and alignment. That is an optimization rather
+/// than a documented guarantee, so the memory is only reused on a best-effort basis. If it isn't,
+/// the new buffer starts out without any memory and nothing else changes.
+pub(crate) fn recycle<T, U>(mut buffer: Vec<T>) -> Vec<U> {
+    buffer.clear();
+    // The buffer is empty, so the closure never runs.
+    buffer.into_iter().map(|_| unreachable!()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that recycling a buffer to and from its stand-ins keeps its memory, which is what
+    /// lets a warmed up [`Packer`] pack without allocating its temporary buffers.
+    ///
+    /// If this starts failing `std` no longer reuses the memory, which makes packing allocate more
+    /// but is otherwise harmless.
+    ///
+    /// [`Packer`]: crate::Packer
+    #[test]
+    fn recycle_keeps_memory() {
+        let bin = TargetBin::new(1, 1, 1);
+        let mut bins: Vec<(&u8, &TargetBin)> = Vec::with_capacity(8);
+        bins.push((&0, &bin));
+        let ptr = bins.as_ptr() as usize;
+
+        let stand_ins: Vec<StandIn<(&'static (), &'static TargetBin)>> = recycle(bins);
+        assert!(stand_ins.is_empty());
+        assert_eq!(stand_ins.capacity(), 8);
+        assert_eq!(stand_ins.as_ptr() as usize, ptr);
+
+        let bins: Vec<(&u8, &TargetBin)> = recycle(stand_ins);
+        assert_eq!(bins.capacity(), 8);
+        assert_eq!(bins.as_ptr() as usize, ptr);
+    }
+
+    /// Verify that recycling into items of a different size starts out with an empty buffer.
+    #[test]
+    fn recycle_into_different_size() {
+        let buffer: Vec<u64> = vec![1, 2, 3];
+
+        let recycled: Vec<u8> = recycle(buffer);
+
+        assert!(recycled.is_empty());
+    }
+}
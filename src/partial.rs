@@ -0,0 +1,123 @@
+//! Packing as many rectangles as fit, instead of failing when some of them don't.
+
+use crate::pack_options::PackContext;
+use crate::{
+    pack_rects_keyed_by, BoxSizeHeuristic, ComparePotentialContainers, GroupedRectsToPlace,
+    PackOptions, RectanglePackError, RectanglePackOk, TargetBin,
+};
+
+use alloc::collections::BTreeMap;
+use core::{fmt::Debug, hash::Hash};
+
+/// Pack rectangles the same way as [`pack_rects_with_options`], but instead of failing with
+/// [`RectanglePackError::NotEnoughBinSpace`] leave out every group that doesn't fit.
+///
+/// Every group is treated as optional (see [`RectToInsert.with_optional`]), so the rectangles
+/// that could not be placed are listed in [`RectanglePackOk.omitted_optional_rects`], and their
+/// groups can be found using [`GroupedRectsToPlace.group_ids_of`], such as for spilling them
+/// onto a new atlas page.
+///
+/// Other errors, such as [`RectanglePackError::SearchLimitExceeded`], are still returned.
+///
+/// ```
+/// use rectangle_pack::{
+///     contains_smallest_box, pack_rects_partial, volume_heuristic, GroupedRectsToPlace,
+///     PackOptions, RectToInsert, TargetBin,
+/// };
+/// use std::collections::BTreeMap;
+///
+/// let mut rects_to_place = GroupedRectsToPlace::new();
+/// rects_to_place.push_rect("grass", Some(vec!["terrain"]), RectToInsert::new(16, 16, 1));
+/// rects_to_place.push_rect("player", Some(vec!["characters"]), RectToInsert::new(8, 8, 1));
+///
+/// let mut target_bins = BTreeMap::new();
+/// target_bins.insert("page-0", TargetBin::new(16, 16, 1));
+///
+/// let packed = pack_rects_partial(
+///     &rects_to_place,
+///     &mut target_bins,
+///     &volume_heuristic,
+///     &contains_smallest_box,
+///     &PackOptions::default(),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(packed.omitted_optional_rects(), &["player"]);
+/// assert_eq!(
+///     rects_to_place.group_ids_of(packed.omitted_optional_rects()),
+///     vec![&"characters"]
+/// );
+/// ```
+///
+/// [`pack_rects_with_options`]: crate::pack_rects_with_options
+/// [`RectanglePackError::NotEnoughBinSpace`]: crate::RectanglePackError::NotEnoughBinSpace
+/// [`RectanglePackError::SearchLimitExceeded`]: crate::RectanglePackError::SearchLimitExceeded
+/// [`RectToInsert.with_optional`]: crate::RectToInsert::with_optional
+/// [`RectanglePackOk.omitted_optional_rects`]: crate::RectanglePackOk::omitted_optional_rects
+/// [`GroupedRectsToPlace.group_ids_of`]: crate::GroupedRectsToPlace::group_ids_of
+pub fn pack_rects_partial<RectToPlaceId, BinId, GroupId, Heuristic, Compare>(
+    rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    target_bins: &mut BTreeMap<BinId, TargetBin>,
+    box_size_heuristic: &Heuristic,
+    more_suitable_containers_fn: &Compare,
+    options: &PackOptions,
+) -> Result<RectanglePackOk<RectToPlaceId, BinId>, RectanglePackError>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+{
+    let options = PackOptions {
+        best_effort: true,
+        ..options.clone()
+    };
+
+    let mut packed = RectanglePackOk::default();
+    pack_rects_keyed_by(
+        rects_to_place,
+        target_bins,
+        None,
+        PackContext::new(box_size_heuristic, more_suitable_containers_fn, &options),
+        RectToPlaceId::clone,
+        &mut packed,
+    )?;
+
+    Ok(packed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{contains_smallest_box, volume_heuristic, RectToInsert};
+
+    /// Verify that a group is left out as a whole when it doesn't fit, while the groups after it
+    /// that do fit are still placed.
+    #[test]
+    fn leaves_out_whole_groups_that_do_not_fit() {
+        let mut rects_to_place = GroupedRectsToPlace::new();
+        rects_to_place.push_rect("tree-a", Some(vec!["trees"]), RectToInsert::new(8, 8, 1));
+        rects_to_place.push_rect("tree-b", Some(vec!["trees"]), RectToInsert::new(8, 8, 1));
+        rects_to_place.push_rect("rock", Some(vec!["rocks"]), RectToInsert::new(4, 4, 1));
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert(0, TargetBin::new(10, 10, 1));
+
+        let packed = pack_rects_partial(
+            &rects_to_place,
+            &mut target_bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+            &PackOptions::default(),
+        )
+        .unwrap();
+
+        assert!(packed.packed_locations().contains_key("rock"));
+        assert_eq!(packed.omitted_optional_rects(), &["tree-a", "tree-b"]);
+        assert_eq!(
+            rects_to_place.group_ids_of(packed.omitted_optional_rects()),
+            vec![&"trees"]
+        );
+    }
+}
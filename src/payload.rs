@@ -0,0 +1,183 @@
+//! Rectangles that carry a payload, such as the asset that each rectangle was created for.
+
+use crate::{GroupedRectsToPlace, KeyValMap, PackedLocation, RectToInsert, RectanglePackOk};
+
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+
+/// A [`GroupedRectsToPlace`] that keeps a payload alongside every rectangle, so that the payloads
+/// can be looked up next to the placements without keeping a separate map from rectangle ids
+/// back to them.
+///
+/// Pack the rectangles using any of the packing functions by passing in
+/// [`GroupedRectsWithPayloads.rects_to_place`], then pair the placements with their payloads
+/// using [`GroupedRectsWithPayloads.placed_with_payloads`].
+///
+/// ```
+/// use rectangle_pack::{
+///     contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsWithPayloads,
+///     RectToInsert, TargetBin,
+/// };
+/// use std::collections::BTreeMap;
+///
+/// let mut rects: GroupedRectsWithPayloads<_, (), _> = GroupedRectsWithPayloads::new();
+/// rects.push_rect("grass", None, RectToInsert::new(8, 8, 1), "textures/grass.png");
+/// rects.push_rect("stone", None, RectToInsert::new(8, 8, 1), "textures/stone.png");
+///
+/// let mut target_bins = BTreeMap::new();
+/// target_bins.insert("atlas", TargetBin::new(16, 16, 1));
+///
+/// let packed = pack_rects(
+///     rects.rects_to_place(),
+///     &mut target_bins,
+///     &volume_heuristic,
+///     &contains_smallest_box,
+/// )
+/// .unwrap();
+///
+/// let mut paths: Vec<_> = rects
+///     .placed_with_payloads(&packed)
+///     .map(|(_, _, _, path)| *path)
+///     .collect();
+/// paths.sort();
+///
+/// assert_eq!(paths, vec!["textures/grass.png", "textures/stone.png"]);
+/// ```
+///
+/// [`GroupedRectsWithPayloads.rects_to_place`]: GroupedRectsWithPayloads::rects_to_place
+/// [`GroupedRectsWithPayloads.placed_with_payloads`]: GroupedRectsWithPayloads::placed_with_payloads
+#[derive(Debug)]
+pub struct GroupedRectsWithPayloads<RectToPlaceId, GroupId, Payload>
+where
+    RectToPlaceId: Debug + Hash + Eq + Ord + PartialOrd,
+    GroupId: Debug + Hash + Eq + Ord + PartialOrd,
+{
+    rects_to_place: GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    payloads: KeyValMap<RectToPlaceId, Payload>,
+}
+
+impl<RectToPlaceId, GroupId, Payload> GroupedRectsWithPayloads<RectToPlaceId, GroupId, Payload>
+where
+    RectToPlaceId: Debug + Hash + Clone + Eq + Ord + PartialOrd,
+    GroupId: Debug + Hash + Clone + Eq + Ord + PartialOrd,
+{
+    /// Create a new `GroupedRectsWithPayloads`
+    pub fn new() -> Self {
+        Self {
+            rects_to_place: GroupedRectsToPlace::new(),
+            payloads: Default::default(),
+        }
+    }
+
+    /// Push a rectangle along with its payload.
+    ///
+    /// Pushing a rectangle id that was already pushed replaces the earlier rectangle and its
+    /// payload. See [`GroupedRectsToPlace.push_rect`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if a `Some(Vec<GroupId>)` passed in but the length is 0.
+    ///
+    /// [`GroupedRectsToPlace.push_rect`]: GroupedRectsToPlace::push_rect
+    pub fn push_rect(
+        &mut self,
+        inbound_id: RectToPlaceId,
+        group_ids: Option<Vec<GroupId>>,
+        inbound: RectToInsert,
+        payload: Payload,
+    ) {
+        self.rects_to_place
+            .push_rect(inbound_id.clone(), group_ids, inbound);
+        self.payloads.insert(inbound_id, payload);
+    }
+
+    /// The rectangles to pass into the packing functions.
+    pub fn rects_to_place(&self) -> &GroupedRectsToPlace<RectToPlaceId, GroupId> {
+        &self.rects_to_place
+    }
+
+    /// The payload that was pushed with the given rectangle id.
+    pub fn payload(&self, inbound_id: &RectToPlaceId) -> Option<&Payload> {
+        self.payloads.get(inbound_id)
+    }
+
+    /// Every placed rectangle along with its bin, location and payload, in an unspecified order.
+    ///
+    /// Placements of rectangles that weren't pushed into these rectangles are skipped.
+    pub fn placed_with_payloads<'a, BinId>(
+        &'a self,
+        packed: &'a RectanglePackOk<RectToPlaceId, BinId>,
+    ) -> impl Iterator<
+        Item = (
+            &'a RectToPlaceId,
+            &'a BinId,
+            &'a PackedLocation,
+            &'a Payload,
+        ),
+    > + 'a
+    where
+        BinId: PartialEq + Eq + Hash,
+    {
+        packed
+            .packed_locations()
+            .iter()
+            .filter_map(move |(inbound_id, (bin_id, location))| {
+                let payload = self.payloads.get(inbound_id)?;
+                Some((inbound_id, bin_id, location, payload))
+            })
+    }
+}
+
+impl<RectToPlaceId, GroupId, Payload> Default
+    for GroupedRectsWithPayloads<RectToPlaceId, GroupId, Payload>
+where
+    RectToPlaceId: Debug + Hash + Clone + Eq + Ord + PartialOrd,
+    GroupId: Debug + Hash + Clone + Eq + Ord + PartialOrd,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{contains_smallest_box, pack_rects, volume_heuristic, TargetBin};
+
+    use alloc::collections::BTreeMap;
+    use alloc::vec;
+
+    /// Verify that pushing a rectangle again replaces its payload, and that rectangles that
+    /// weren't placed have no entry in the placed payloads.
+    #[test]
+    fn payloads_follow_their_rects() {
+        let mut rects: GroupedRectsWithPayloads<_, (), _> = GroupedRectsWithPayloads::new();
+        rects.push_rect("a", None, RectToInsert::new(4, 4, 1), 1);
+        rects.push_rect("a", None, RectToInsert::new(4, 4, 1), 2);
+        rects.push_rect(
+            "optional",
+            None,
+            RectToInsert::new(8, 8, 1).with_optional(true),
+            3,
+        );
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert("bin", TargetBin::new(4, 4, 1));
+
+        let packed = pack_rects(
+            rects.rects_to_place(),
+            &mut target_bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+
+        let placed: Vec<_> = rects.placed_with_payloads(&packed).collect();
+
+        assert_eq!(
+            placed,
+            vec![(&"a", &"bin", &packed.packed_locations()["a"].1, &2)]
+        );
+        assert_eq!(rects.payload(&"optional"), Some(&3));
+    }
+}
@@ -0,0 +1,211 @@
+//! Heuristics that carry state between calls.
+
+use crate::{
+    pack_rects_with_options, BoxSizeHeuristicFn, GroupedRectsToPlace, PackOptions,
+    RectanglePackError, RectanglePackOk, TargetBin, WidthHeightDepth,
+};
+
+use alloc::collections::BTreeMap;
+use core::{cell::RefCell, cmp::Ordering, fmt::Debug, hash::Hash};
+
+/// The box size heuristic and the comparison of potential containers, as a single value that can
+/// change while rectangles are being packed, such as to cache sizes, adjust learned weights or
+/// gather statistics about the pack.
+///
+/// Used by [`pack_rects_with_heuristic`]. The plain [`BoxSizeHeuristicFn`] and
+/// [`ComparePotentialContainersFn`] can only be `Fn`s, so they can't hold any state of their
+/// own.
+///
+/// [`ComparePotentialContainersFn`]: crate::ComparePotentialContainersFn
+pub trait PlacementHeuristic {
+    /// How large the box is. A larger heuristic means that the box is larger.
+    ///
+    /// See [`BoxSizeHeuristicFn`].
+    fn box_size(&mut self, whd: WidthHeightDepth) -> u128;
+
+    /// Which of two ways of splitting up the space left over after placing a box is better,
+    /// with [`Ordering::Greater`] meaning that `a` is better.
+    ///
+    /// See [`ComparePotentialContainersFn`]. Defaults to the same comparison as
+    /// [`contains_smallest_box`], using [`PlacementHeuristic.box_size`].
+    ///
+    /// [`ComparePotentialContainersFn`]: crate::ComparePotentialContainersFn
+    /// [`contains_smallest_box`]: crate::contains_smallest_box
+    /// [`PlacementHeuristic.box_size`]: PlacementHeuristic::box_size
+    fn compare_containers(
+        &mut self,
+        a: [WidthHeightDepth; 3],
+        b: [WidthHeightDepth; 3],
+    ) -> Ordering {
+        let mut a = [
+            self.box_size(a[0]),
+            self.box_size(a[1]),
+            self.box_size(a[2]),
+        ];
+        let mut b = [
+            self.box_size(b[0]),
+            self.box_size(b[1]),
+            self.box_size(b[2]),
+        ];
+        a.sort_unstable();
+        b.sort_unstable();
+
+        match b[0].cmp(&a[0]) {
+            Ordering::Equal => b[1].cmp(&a[1]),
+            o => o,
+        }
+    }
+}
+
+/// Pack rectangles the same way as [`pack_rects_with_options`], using a [`PlacementHeuristic`]
+/// instead of a separate box size heuristic and comparison.
+///
+/// The heuristic is borrowed mutably, so that its state can be read back after packing.
+///
+/// ```
+/// use rectangle_pack::{
+///     pack_rects_with_heuristic, volume_heuristic, GroupedRectsToPlace, PackOptions,
+///     PlacementHeuristic, RectToInsert, TargetBin, WidthHeightDepth,
+/// };
+/// use std::collections::BTreeMap;
+///
+/// /// The volume of the box, counting how often it was needed.
+/// #[derive(Default)]
+/// struct CountingVolume {
+///     calls: usize,
+/// }
+///
+/// impl PlacementHeuristic for CountingVolume {
+///     fn box_size(&mut self, whd: WidthHeightDepth) -> u128 {
+///         self.calls += 1;
+///         volume_heuristic(whd)
+///     }
+/// }
+///
+/// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+/// rects_to_place.push_rect("grass", None, RectToInsert::new(8, 8, 1));
+///
+/// let mut target_bins = BTreeMap::new();
+/// target_bins.insert("atlas", TargetBin::new(16, 16, 1));
+///
+/// let mut heuristic = CountingVolume::default();
+/// pack_rects_with_heuristic(
+///     &rects_to_place,
+///     &mut target_bins,
+///     &mut heuristic,
+///     &PackOptions::default(),
+/// )
+/// .unwrap();
+///
+/// assert!(heuristic.calls > 0);
+/// ```
+///
+/// [`pack_rects_with_options`]: crate::pack_rects_with_options
+pub fn pack_rects_with_heuristic<RectToPlaceId, BinId, GroupId, Heuristic>(
+    rects_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    target_bins: &mut BTreeMap<BinId, TargetBin>,
+    heuristic: &mut Heuristic,
+    options: &PackOptions,
+) -> Result<RectanglePackOk<RectToPlaceId, BinId>, RectanglePackError>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Heuristic: PlacementHeuristic + ?Sized,
+{
+    // Both closures need to call the heuristic, but are never called while the other one is
+    // running.
+    let heuristic = RefCell::new(heuristic);
+    let box_size = |whd| heuristic.borrow_mut().box_size(whd);
+    let compare_containers =
+        |a, b, _: &BoxSizeHeuristicFn| heuristic.borrow_mut().compare_containers(a, b);
+
+    pack_rects_with_options(
+        rects_to_place,
+        target_bins,
+        &box_size,
+        &compare_containers,
+        options,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pack_rects, volume_heuristic, RectToInsert};
+
+    use alloc::vec::Vec;
+
+    struct Volume;
+
+    impl PlacementHeuristic for Volume {
+        fn box_size(&mut self, whd: WidthHeightDepth) -> u128 {
+            volume_heuristic(whd)
+        }
+    }
+
+    /// Verify that the default comparison packs the same way as `contains_smallest_box`.
+    #[test]
+    fn default_comparison_matches_contains_smallest_box() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        for (id, (width, height)) in [(3, 7), (5, 2), (4, 4), (1, 6)].iter().enumerate() {
+            rects_to_place.push_rect(id, None, RectToInsert::new(*width, *height, 1));
+        }
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert(0, TargetBin::new(12, 12, 1));
+        let mut expected_bins = target_bins.clone();
+
+        let packed = pack_rects_with_heuristic(
+            &rects_to_place,
+            &mut target_bins,
+            &mut Volume,
+            &PackOptions::default(),
+        )
+        .unwrap();
+        let expected = pack_rects(
+            &rects_to_place,
+            &mut expected_bins,
+            &volume_heuristic,
+            &crate::contains_smallest_box,
+        )
+        .unwrap();
+
+        assert_eq!(packed, expected);
+    }
+
+    /// Records the sizes that it was asked about into a buffer that it borrows.
+    struct Recording<'a> {
+        sizes: &'a mut Vec<WidthHeightDepth>,
+    }
+
+    impl PlacementHeuristic for Recording<'_> {
+        fn box_size(&mut self, whd: WidthHeightDepth) -> u128 {
+            self.sizes.push(whd);
+            volume_heuristic(whd)
+        }
+    }
+
+    /// Verify that a heuristic that borrows from its surroundings can be used, including as a
+    /// trait object.
+    #[test]
+    fn heuristic_may_borrow() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect("a", None, RectToInsert::new(3, 7, 1));
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert(0, TargetBin::new(12, 12, 1));
+
+        let mut sizes = Vec::new();
+        let heuristic: &mut dyn PlacementHeuristic = &mut Recording { sizes: &mut sizes };
+        pack_rects_with_heuristic(
+            &rects_to_place,
+            &mut target_bins,
+            heuristic,
+            &PackOptions::default(),
+        )
+        .unwrap();
+
+        assert!(sizes.contains(&WidthHeightDepth::new(3, 7, 1)));
+    }
+}
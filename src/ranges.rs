@@ -0,0 +1,137 @@
+//! Packing one dimensional ranges, such as audio clips into buffers.
+
+use crate::pack_options::PackContext;
+use crate::{
+    contains_smallest_box, pack_rects_keyed_by, volume_heuristic, GroupedRectsToPlace, PackOptions,
+    RectanglePackError, RectanglePackOk, TargetBin,
+};
+
+use alloc::collections::BTreeMap;
+use core::{fmt::Debug, hash::Hash};
+
+/// Pack ranges into one dimensional bins, such as audio clips into sound buffers or vertex
+/// ranges into a shared vertex buffer.
+///
+/// Every [`RectToInsert`] is a range whose length is its width, and every [`TargetBin`] is a
+/// buffer whose length is its width. Their heights and depths must all be `1`.
+///
+/// Groups, optional rectangles, priorities, distinct bin sets, frozen bins, exclusion zones and
+/// the results all work the same way as they do for [`pack_rects_with_options`]. Since there is
+/// only one way to place a range into a free section, ranges skip the splitting heuristics and
+/// are placed at the start of the first free section that fits them, making each bin a simple
+/// free-list allocator. Ranges that are removed are returned using
+/// [`TargetBin.push_available_bin_section`], the same as for rectangles.
+///
+/// [`RectToInsert.with_max_x`] limits where a range may end. Padding, size rounding, rotations,
+/// flips and [`TargetBin.with_block_size`] are ignored, along with the placement policy and
+/// orientation comparator of the `options`.
+///
+/// # Panics
+///
+/// Panics if any of the ranges or bins has a height or depth other than `1`.
+///
+/// ```
+/// use rectangle_pack::{pack_ranges, GroupedRectsToPlace, PackOptions, RectToInsert, TargetBin};
+/// use std::collections::BTreeMap;
+///
+/// let mut clips: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+/// clips.push_rect("footstep", None, RectToInsert::new(4_800, 1, 1));
+/// clips.push_rect("explosion", None, RectToInsert::new(96_000, 1, 1));
+///
+/// let mut buffers = BTreeMap::new();
+/// buffers.insert("sfx", TargetBin::new(192_000, 1, 1));
+///
+/// let packed = pack_ranges(&clips, &mut buffers, &PackOptions::new()).unwrap();
+///
+/// let (_, explosion) = packed.packed_locations()["explosion"];
+/// let (_, footstep) = packed.packed_locations()["footstep"];
+/// assert_eq!((explosion.x(), footstep.x()), (0, 96_000));
+/// ```
+///
+/// [`RectToInsert`]: crate::RectToInsert
+/// [`RectToInsert.with_max_x`]: crate::RectToInsert::with_max_x
+/// [`pack_rects_with_options`]: crate::pack_rects_with_options
+/// [`TargetBin.push_available_bin_section`]: TargetBin::push_available_bin_section
+/// [`TargetBin.with_block_size`]: TargetBin::with_block_size
+pub fn pack_ranges<RectToPlaceId, BinId, GroupId>(
+    ranges_to_place: &GroupedRectsToPlace<RectToPlaceId, GroupId>,
+    target_bins: &mut BTreeMap<BinId, TargetBin>,
+    options: &PackOptions,
+) -> Result<RectanglePackOk<RectToPlaceId, BinId>, RectanglePackError>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+{
+    for (range_id, range) in ranges_to_place.rects.iter() {
+        assert!(
+            range.whd.height == 1 && range.whd.depth == 1,
+            "Range {:?} must have a height and depth of 1",
+            range_id
+        );
+    }
+    for (bin_id, bin) in target_bins.iter() {
+        let dimensions = bin.dimensions();
+        assert!(
+            dimensions.height == 1 && dimensions.depth == 1,
+            "Bin {:?} must have a height and depth of 1",
+            bin_id
+        );
+    }
+
+    let options = PackOptions {
+        one_dimensional: true,
+        ..options.clone()
+    };
+
+    let mut packed = RectanglePackOk::default();
+    pack_rects_keyed_by(
+        ranges_to_place,
+        target_bins,
+        None,
+        PackContext::new(&volume_heuristic, &contains_smallest_box, &options),
+        RectToPlaceId::clone,
+        &mut packed,
+    )?;
+
+    Ok(packed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bin_section::BinSection;
+    use crate::width_height_depth::WidthHeightDepth;
+    use crate::RectToInsert;
+
+    /// Verify that freed space is reused by the next range that fits into it, and that ranges
+    /// of the same group share a bin.
+    #[test]
+    fn freed_space_is_reused() {
+        let mut ranges = GroupedRectsToPlace::new();
+        ranges.push_rect("a", Some(vec![0]), RectToInsert::new(5, 1, 1));
+        ranges.push_rect("b", Some(vec![0]), RectToInsert::new(3, 1, 1));
+
+        let mut buffers = BTreeMap::new();
+        buffers.insert(0, TargetBin::new(10, 1, 1));
+        buffers.insert(1, TargetBin::new(10, 1, 1));
+
+        let packed = pack_ranges(&ranges, &mut buffers, &PackOptions::new()).unwrap();
+        let (a_bin, a) = packed.packed_locations()["a"];
+        let (b_bin, b) = packed.packed_locations()["b"];
+        assert_eq!(a_bin, b_bin);
+        assert_eq!((a.x(), b.x()), (0, 5));
+
+        let buffer = buffers.get_mut(&a_bin).unwrap();
+        buffer
+            .push_available_bin_section(BinSection::new(0, 0, 0, WidthHeightDepth::new(5, 1, 1)))
+            .unwrap();
+
+        let mut more: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        more.push_rect("c", None, RectToInsert::new(4, 1, 1));
+
+        let packed = pack_ranges(&more, &mut buffers, &PackOptions::new()).unwrap();
+        let (c_bin, c) = packed.packed_locations()["c"];
+        assert_eq!((c_bin, c.x()), (a_bin, 0));
+    }
+}
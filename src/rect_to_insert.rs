@@ -1,3 +1,4 @@
+use crate::packed_location::{rotate, RotatedBy};
 use crate::width_height_depth::WidthHeightDepth;
 
 /// A rectangle that we want to insert into a target bin
@@ -7,20 +8,107 @@ pub struct RectToInsert {
     allow_global_x_axis_rotation: bool,
     allow_global_y_axis_rotation: bool,
     allow_global_z_axis_rotation: bool,
+    pub(crate) allow_horizontal_flip: bool,
+    pub(crate) allow_vertical_flip: bool,
+    pub(crate) max_x: Option<u32>,
+    pub(crate) max_y: Option<u32>,
+    pub(crate) layer: Option<u32>,
+    pub(crate) edge: Option<BinEdge>,
+    pub(crate) padding: Option<u32>,
+    pub(crate) optional: bool,
+    pub(crate) priority: u32,
+    pub(crate) content_hash: Option<u64>,
+    pub(crate) weight: u64,
+    pub(crate) value: u64,
+    /// The size of the blocks of the bin that the rectangle is being placed into.
+    ///
+    /// See [`TargetBin.with_block_size`].
+    ///
+    /// [`TargetBin.with_block_size`]: crate::TargetBin::with_block_size
+    pub(crate) block_size: WidthHeightDepth,
+    /// Whether the depth is a number of layers, see [`TargetBin.with_layered_depth`].
+    ///
+    /// [`TargetBin.with_layered_depth`]: crate::TargetBin::with_layered_depth
+    pub(crate) layered_depth: bool,
+    /// The size, in blocks, of the bin that the rectangle is being placed into, if known.
+    pub(crate) bin_whd: Option<WidthHeightDepth>,
 }
 
-impl Into<WidthHeightDepth> for RectToInsert {
-    fn into(self) -> WidthHeightDepth {
+/// An edge of a bin that a [`RectToInsert`] can be anchored to.
+///
+/// See [`RectToInsert.with_edge`].
+///
+/// [`RectToInsert.with_edge`]: RectToInsert::with_edge
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BinEdge {
+    /// The edge at `x = 0`.
+    Left,
+    /// The edge at the bin's width.
+    Right,
+    /// The edge at `y = 0`.
+    Bottom,
+    /// The edge at the bin's height.
+    Top,
+}
+
+/// The axes that a [`RectToInsert`] may be rotated about by 90 degrees when it gets placed.
+///
+/// See [`RectToInsert::new_with_rotation`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct RotationAllowances {
+    /// See [`RectToInsert.with_global_x_axis_rotation`].
+    ///
+    /// [`RectToInsert.with_global_x_axis_rotation`]: RectToInsert::with_global_x_axis_rotation
+    pub x_axis: bool,
+    /// See [`RectToInsert.with_global_y_axis_rotation`].
+    ///
+    /// [`RectToInsert.with_global_y_axis_rotation`]: RectToInsert::with_global_y_axis_rotation
+    pub y_axis: bool,
+    /// See [`RectToInsert.with_global_z_axis_rotation`].
+    ///
+    /// [`RectToInsert.with_global_z_axis_rotation`]: RectToInsert::with_global_z_axis_rotation
+    pub z_axis: bool,
+}
+
+impl RotationAllowances {
+    /// Allow rotating about every axis.
+    pub fn all() -> Self {
+        RotationAllowances {
+            x_axis: true,
+            y_axis: true,
+            z_axis: true,
+        }
+    }
+}
+
+impl From<RectToInsert> for WidthHeightDepth {
+    fn from(rect: RectToInsert) -> Self {
         WidthHeightDepth {
-            width: self.width(),
-            height: self.height(),
-            depth: self.depth(),
+            width: rect.width(),
+            height: rect.height(),
+            depth: rect.depth(),
         }
     }
 }
 
 #[allow(missing_docs)]
 impl RectToInsert {
+    /// Create a rectangle of the given size.
+    ///
+    /// A rectangle with a width, height or depth of `0`, such as the glyph of a space, is empty.
+    /// Empty rectangles take up no space, so they are placed without touching the bin's free
+    /// sections, even into a full bin. They go at `(0, 0, 0)`, or onto their
+    /// [`RectToInsert.with_layer`] and against their [`RectToInsert.with_edge`], ignoring any
+    /// padding. They are still only placed onto a layer within the bin, within their
+    /// [`RectToInsert.with_max_x`] and [`RectToInsert.with_max_y`] and where the
+    /// [`PackOptions.with_placement_validator`] accepts them. See [`RectToInsert.is_empty`].
+    ///
+    /// [`RectToInsert.with_layer`]: RectToInsert::with_layer
+    /// [`RectToInsert.with_edge`]: RectToInsert::with_edge
+    /// [`RectToInsert.with_max_x`]: RectToInsert::with_max_x
+    /// [`RectToInsert.with_max_y`]: RectToInsert::with_max_y
+    /// [`PackOptions.with_placement_validator`]: crate::PackOptions::with_placement_validator
+    /// [`RectToInsert.is_empty`]: RectToInsert::is_empty
     pub fn new(width: u32, height: u32, depth: u32) -> Self {
         RectToInsert {
             whd: WidthHeightDepth {
@@ -28,12 +116,474 @@ impl RectToInsert {
                 height,
                 depth,
             },
-            // Rotation is not yet supported
             allow_global_x_axis_rotation: false,
             allow_global_y_axis_rotation: false,
             allow_global_z_axis_rotation: false,
+            allow_horizontal_flip: false,
+            allow_vertical_flip: false,
+            max_x: None,
+            max_y: None,
+            layer: None,
+            edge: None,
+            padding: None,
+            optional: false,
+            priority: 0,
+            content_hash: None,
+            weight: 0,
+            value: 0,
+            block_size: WidthHeightDepth::new(1, 1, 1),
+            layered_depth: false,
+            bin_whd: None,
         }
     }
+
+    /// Create a flat rectangle with a depth of `1`, such as a sprite or a glyph.
+    ///
+    /// See [`pack_rects_2d`].
+    ///
+    /// [`pack_rects_2d`]: crate::pack_rects_2d
+    pub fn new_2d(width: u32, height: u32) -> Self {
+        RectToInsert::new(width, height, 1)
+    }
+
+    /// Create a rectangle that may be rotated about the given axes.
+    ///
+    /// ```
+    /// use rectangle_pack::{RectToInsert, RotationAllowances};
+    ///
+    /// let rect = RectToInsert::new_with_rotation(
+    ///     10,
+    ///     20,
+    ///     1,
+    ///     RotationAllowances {
+    ///         z_axis: true,
+    ///         ..Default::default()
+    ///     },
+    /// );
+    ///
+    /// assert_eq!(rect, RectToInsert::new(10, 20, 1).with_global_z_axis_rotation(true));
+    /// ```
+    pub fn new_with_rotation(
+        width: u32,
+        height: u32,
+        depth: u32,
+        rotation: RotationAllowances,
+    ) -> Self {
+        RectToInsert::new(width, height, depth)
+            .with_global_x_axis_rotation(rotation.x_axis)
+            .with_global_y_axis_rotation(rotation.y_axis)
+            .with_global_z_axis_rotation(rotation.z_axis)
+    }
+}
+
+impl RectToInsert {
+    /// Allow the rectangle to be rotated by 90 degrees about the x axis, swapping its height and
+    /// depth.
+    ///
+    /// See [`PackedLocation.x_axis_rotation`].
+    ///
+    /// [`PackedLocation.x_axis_rotation`]: crate::PackedLocation::x_axis_rotation
+    pub fn with_global_x_axis_rotation(mut self, allow: bool) -> Self {
+        self.allow_global_x_axis_rotation = allow;
+        self
+    }
+
+    /// Allow the rectangle to be rotated by 90 degrees about the y axis, swapping its width and
+    /// depth.
+    ///
+    /// See [`PackedLocation.y_axis_rotation`].
+    ///
+    /// [`PackedLocation.y_axis_rotation`]: crate::PackedLocation::y_axis_rotation
+    pub fn with_global_y_axis_rotation(mut self, allow: bool) -> Self {
+        self.allow_global_y_axis_rotation = allow;
+        self
+    }
+
+    /// Allow the rectangle to be rotated by 90 degrees about the z axis, so that a `WxH`
+    /// rectangle can be placed as `HxW` when it wouldn't fit otherwise.
+    ///
+    /// See [`PackedLocation.z_axis_rotation`].
+    ///
+    /// [`PackedLocation.z_axis_rotation`]: crate::PackedLocation::z_axis_rotation
+    pub fn with_global_z_axis_rotation(mut self, allow: bool) -> Self {
+        self.allow_global_z_axis_rotation = allow;
+        self
+    }
+
+    /// Allow the rectangle's contents to be mirrored from left to right, for renderers that can
+    /// flip it for free.
+    ///
+    /// Flips are only a transform that is applied after placing the rectangle. Flipping never
+    /// changes the space that a rectangle takes up, so it never changes where rectangles fit or
+    /// how densely a bin gets packed. The packer only flips a rectangle when a
+    /// [`CompareOrientations`] prefers it, such as to undo a rotation's effect on how the contents
+    /// are read. Without one, allowing flips has no effect and nothing is ever flipped.
+    ///
+    /// See [`PackedLocation.flipped_horizontally`].
+    ///
+    /// [`CompareOrientations`]: crate::CompareOrientations
+    /// [`PackedLocation.flipped_horizontally`]: crate::PackedLocation::flipped_horizontally
+    pub fn with_horizontal_flip(mut self, allow: bool) -> Self {
+        self.allow_horizontal_flip = allow;
+        self
+    }
+
+    /// Allow the rectangle's contents to be mirrored from top to bottom.
+    ///
+    /// Like horizontal flips, this only has an effect when a [`CompareOrientations`] prefers the
+    /// flipped orientation.
+    ///
+    /// See [`RectToInsert.with_horizontal_flip`] and [`PackedLocation.flipped_vertically`].
+    ///
+    /// [`CompareOrientations`]: crate::CompareOrientations
+    /// [`RectToInsert.with_horizontal_flip`]: RectToInsert::with_horizontal_flip
+    /// [`PackedLocation.flipped_vertically`]: crate::PackedLocation::flipped_vertically
+    pub fn with_vertical_flip(mut self, allow: bool) -> Self {
+        self.allow_vertical_flip = allow;
+        self
+    }
+
+    /// The ways that the rectangle may be flipped, as `(horizontally, vertically)`. Not flipping
+    /// always comes first.
+    pub(crate) fn allowed_flips(&self) -> impl Iterator<Item = (bool, bool)> + '_ {
+        [(false, false), (true, false), (false, true), (true, true)]
+            .iter()
+            .copied()
+            .filter(move |(horizontally, vertically)| {
+                (!horizontally || self.allow_horizontal_flip)
+                    && (!vertically || self.allow_vertical_flip)
+            })
+    }
+
+    /// Only place the rectangle where its right edge is at or before the column `max_x`, meaning
+    /// that `x + width <= max_x`.
+    ///
+    /// Useful when the consumer of a bin can only read part of it.
+    pub fn with_max_x(mut self, max_x: u32) -> Self {
+        self.max_x = Some(max_x);
+        self
+    }
+
+    /// Only place the rectangle where its far edge is at or before the row `max_y`, meaning that
+    /// `y + height <= max_y`.
+    ///
+    /// For example, `with_max_y(1024)` keeps the rectangle within the first 1024 rows of the bin
+    /// for a loader that only reads those rows.
+    pub fn with_max_y(mut self, max_y: u32) -> Self {
+        self.max_y = Some(max_y);
+        self
+    }
+
+    /// Only place the rectangle where its front face is at the given `z`, such as a decal that
+    /// has to go onto layer 3 of a texture array.
+    ///
+    /// Only the free sections that cover that `z` are considered. The rectangle is never rotated
+    /// about the x or y axis, since that would move its front face. A `z` that isn't on a block
+    /// boundary of the bin (see [`TargetBin.with_block_size`]) can't be placed onto.
+    ///
+    /// [`TargetBin.with_block_size`]: crate::TargetBin::with_block_size
+    pub fn with_layer(mut self, z: u32) -> Self {
+        self.layer = Some(z);
+        self
+    }
+
+    /// Only place the rectangle where it sits flush against the given edge of the bin, such as a
+    /// tileable texture that needs to wrap around the border of an atlas.
+    ///
+    /// The rectangle is never rotated, so that the side that touches the edge is known. Any
+    /// padding that the rectangle has is kept between it and the edge. Within a contiguous group
+    /// (see [`PackOptions.with_contiguous_groups`]) the rectangle is only anchored to the edge of
+    /// the group's region.
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects, volume_heuristic, BinEdge, GroupedRectsToPlace,
+    ///     RectToInsert, TargetBin,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    /// rects_to_place.push_rect("tile", None, RectToInsert::new(8, 8, 1).with_edge(BinEdge::Right));
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert(0, TargetBin::new(32, 32, 1));
+    ///
+    /// let packed = pack_rects(
+    ///     &rects_to_place,
+    ///     &mut target_bins,
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    /// )
+    /// .unwrap();
+    ///
+    /// let (_, location) = packed.packed_locations()["tile"];
+    /// assert_eq!(location.x() + location.width(), 32);
+    /// ```
+    ///
+    /// [`PackOptions.with_contiguous_groups`]: crate::PackOptions::with_contiguous_groups
+    pub fn with_edge(mut self, edge: BinEdge) -> Self {
+        self.edge = Some(edge);
+        self
+    }
+
+    /// Reserve this many extra texels on every side of the rectangle, so that it doesn't bleed
+    /// into its neighbors when sampled with bilinear filtering.
+    ///
+    /// The [`PackedLocation`] still describes the rectangle without its padding. The padding is
+    /// added to the width and height of the unrotated rectangle, so a rectangle that gets rotated
+    /// about the x or y axis takes its padding along into the depth.
+    ///
+    /// Overrides [`PackOptions.with_padding`].
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace, RectToInsert,
+    ///     TargetBin,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    /// rects_to_place.push_rect("glyph", None, RectToInsert::new(8, 8, 1).with_padding(1));
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert(0, TargetBin::new(10, 10, 1));
+    ///
+    /// let packed = pack_rects(
+    ///     &rects_to_place,
+    ///     &mut target_bins,
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    /// )
+    /// .unwrap();
+    ///
+    /// let (_, location) = packed.packed_locations()["glyph"];
+    /// assert_eq!((location.x(), location.y()), (1, 1));
+    /// assert_eq!((location.width(), location.height()), (8, 8));
+    /// ```
+    ///
+    /// [`PackedLocation`]: crate::PackedLocation
+    /// [`PackOptions.with_padding`]: crate::PackOptions::with_padding
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = Some(padding);
+        self
+    }
+
+    /// Whether the longer of the rectangle's width and height is at least `min_aspect_ratio`
+    /// times the shorter one.
+    pub(crate) fn is_elongated(&self, min_aspect_ratio: u32) -> bool {
+        let longer = self.whd.width.max(self.whd.height) as u64;
+        let shorter = self.whd.width.min(self.whd.height) as u64;
+
+        longer >= shorter * min_aspect_ratio as u64
+    }
+
+    /// Only place the rectangle if there is space left after every rectangle that isn't optional
+    /// has been placed, such as for a nice to have set of debug glyphs.
+    ///
+    /// An optional rectangle that doesn't fit is reported in
+    /// [`RectanglePackOk.omitted_optional_rects`] instead of failing the pack.
+    ///
+    /// A group is only optional when every one of its rectangles is. Optional groups are placed
+    /// after all of the other groups, largest first, and are never undone by
+    /// [`PackOptions.with_backtracking`].
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace, RectToInsert,
+    ///     TargetBin,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    /// rects_to_place.push_rect("glyph", None, RectToInsert::new(8, 8, 1));
+    /// rects_to_place.push_rect(
+    ///     "debug-glyph",
+    ///     None,
+    ///     RectToInsert::new(16, 16, 1).with_optional(true),
+    /// );
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert(0, TargetBin::new(16, 16, 1));
+    ///
+    /// let packed = pack_rects(
+    ///     &rects_to_place,
+    ///     &mut target_bins,
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert!(packed.packed_locations().contains_key("glyph"));
+    /// assert_eq!(packed.omitted_optional_rects(), &["debug-glyph"]);
+    /// ```
+    ///
+    /// [`RectanglePackOk.omitted_optional_rects`]: crate::RectanglePackOk::omitted_optional_rects
+    /// [`PackOptions.with_backtracking`]: crate::PackOptions::with_backtracking
+    pub fn with_optional(mut self, optional: bool) -> Self {
+        self.optional = optional;
+        self
+    }
+
+    /// Place the rectangle before every rectangle with a lower priority, no matter how large
+    /// either of them are, such as for font fallback glyphs or sprites that a UI can't do
+    /// without.
+    ///
+    /// A group's priority is the highest priority of its rectangles. Groups with the same
+    /// priority are placed largest first. Optional groups (see [`RectToInsert.with_optional`])
+    /// are still placed after every other group.
+    ///
+    /// Defaults to `0`.
+    ///
+    /// [`RectToInsert.with_optional`]: RectToInsert::with_optional
+    pub fn with_priority(mut self, priority: u32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Attach a hash of the rectangle's contents, such as of the texels of an image, that is
+    /// handed back unchanged in the rectangle's [`PackedLocation.content_hash`].
+    ///
+    /// The packer never looks at the hash. It lets the caller of a persistent atlas check
+    /// whether the contents that were uploaded for a cached placement still match before
+    /// reusing it. The hash is kept when a rectangle is moved, such as by [`repack`].
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace, RectToInsert,
+    ///     TargetBin,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    /// rects_to_place.push_rect(
+    ///     "grass",
+    ///     None,
+    ///     RectToInsert::new(8, 8, 1).with_content_hash(0x5eed),
+    /// );
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert(0, TargetBin::new(16, 16, 1));
+    ///
+    /// let packed = pack_rects(
+    ///     &rects_to_place,
+    ///     &mut target_bins,
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    /// )
+    /// .unwrap();
+    ///
+    /// let (_, location) = packed.packed_locations()["grass"];
+    /// assert_eq!(location.content_hash(), Some(0x5eed));
+    /// ```
+    ///
+    /// [`PackedLocation.content_hash`]: crate::PackedLocation::content_hash
+    /// [`repack`]: crate::repack
+    pub fn with_content_hash(mut self, content_hash: u64) -> Self {
+        self.content_hash = Some(content_hash);
+        self
+    }
+
+    /// How heavy the box is, in whatever unit the bins' [`TargetBin.with_max_weight`] uses.
+    ///
+    /// Defaults to `0`.
+    ///
+    /// [`TargetBin.with_max_weight`]: crate::TargetBin::with_max_weight
+    pub fn with_weight(mut self, weight: u64) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// How much it is worth to have the rectangle placed, used by
+    /// [`PackOptions.with_value_maximization`] to decide which rectangles to leave out when not
+    /// all of them fit.
+    ///
+    /// A group's value is the sum of its rectangles' values.
+    ///
+    /// Defaults to `0`.
+    ///
+    /// [`PackOptions.with_value_maximization`]: crate::PackOptions::with_value_maximization
+    pub fn with_value(mut self, value: u64) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// The rectangle grown by the padding on every side of its width and height, with its
+    /// `max_x` and `max_y` moved out to match.
+    pub(crate) fn padded(&self, padding: u32) -> RectToInsert {
+        let mut padded = *self;
+
+        padded.whd.width += 2 * padding;
+        padded.whd.height += 2 * padding;
+        padded.max_x = self.max_x.map(|max_x| max_x + padding);
+        padded.max_y = self.max_y.map(|max_y| max_y + padding);
+
+        padded
+    }
+
+    /// The orientations that the rectangle may be placed in, along with how far it needs to be
+    /// rotated about the x, y and z axes to get there. The unrotated orientation always comes
+    /// first, followed by the orientations that need the fewest rotations.
+    ///
+    /// Each allowed axis is rotated about at most once, in the order x, y, then z, so with only
+    /// two axes allowed four of the six orientations of a box can be reached. Every orientation is
+    /// only listed once, even if more than one combination of rotations reaches it.
+    ///
+    /// The sizes are measured in blocks of the rectangle's `block_size`. When the depth is a number
+    /// of layers, or the rectangle has to be placed onto a layer, the rectangle is only ever
+    /// rotated about the z axis. A rectangle that is anchored to an edge is never rotated.
+    pub(crate) fn allowed_orientations(
+        &self,
+    ) -> impl Iterator<Item = (WidthHeightDepth, [RotatedBy; 3])> + '_ {
+        let anchored = self.edge.is_some();
+        let allowed = [
+            self.allow_global_x_axis_rotation
+                && !self.layered_depth
+                && self.layer.is_none()
+                && !anchored,
+            self.allow_global_y_axis_rotation
+                && !self.layered_depth
+                && self.layer.is_none()
+                && !anchored,
+            self.allow_global_z_axis_rotation && !anchored,
+        ];
+        let oriented = move |rotations: &[RotatedBy; 3]| {
+            let is_allowed = rotations
+                .iter()
+                .zip(allowed.iter())
+                .all(|(rotation, allowed)| *rotation == RotatedBy::ZeroDegrees || *allowed);
+
+            match is_allowed {
+                true => Some(rotate(self.whd, *rotations).blocks_covering(self.block_size)),
+                false => None,
+            }
+        };
+
+        // Only the first combination leaves the rectangle unrotated.
+        let candidates = match allowed.iter().any(|allowed| *allowed) {
+            true => &ROTATIONS[..],
+            false => &ROTATIONS[..1],
+        };
+
+        candidates
+            .iter()
+            .enumerate()
+            .filter_map(move |(idx, rotations)| {
+                let whd = oriented(rotations)?;
+                let reached_earlier = ROTATIONS[..idx]
+                    .iter()
+                    .any(|earlier| oriented(earlier) == Some(whd));
+
+                match reached_earlier {
+                    true => None,
+                    false => Some((whd, *rotations)),
+                }
+            })
+    }
+
+    /// The size of the rectangle, in blocks of its `block_size`, when it isn't rotated.
+    pub(crate) fn unrotated_whd_in_blocks(&self) -> WidthHeightDepth {
+        self.whd.blocks_covering(self.block_size)
+    }
 }
 
 #[allow(missing_docs)]
@@ -49,4 +599,27 @@ impl RectToInsert {
     pub fn depth(&self) -> u32 {
         self.whd.depth
     }
+
+    /// Whether the rectangle has a width, height or depth of `0`, so that it takes up no space.
+    ///
+    /// See [`RectToInsert::new`].
+    pub fn is_empty(&self) -> bool {
+        self.whd.volume() == 0
+    }
 }
+
+/// Every combination of rotating about the x, y and z axes at most once each, with the fewest
+/// rotations first.
+const ROTATIONS: [[RotatedBy; 3]; 7] = {
+    use RotatedBy::*;
+
+    [
+        [ZeroDegrees, ZeroDegrees, ZeroDegrees],
+        [ZeroDegrees, ZeroDegrees, NinetyDegrees],
+        [NinetyDegrees, ZeroDegrees, ZeroDegrees],
+        [ZeroDegrees, NinetyDegrees, ZeroDegrees],
+        [NinetyDegrees, NinetyDegrees, ZeroDegrees],
+        [NinetyDegrees, ZeroDegrees, NinetyDegrees],
+        [ZeroDegrees, NinetyDegrees, NinetyDegrees],
+    ]
+};
@@ -0,0 +1,178 @@
+//! Re-packing the rectangles within bins in order to undo fragmentation.
+
+use crate::pack_options::PackContext;
+use crate::{
+    BoxSizeHeuristic, ComparePotentialContainers, PackOptions, PackedLocation, RectToInsert,
+    RectanglePackOk, TargetBin,
+};
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+/// Compute a denser layout for every bin that holds at least one of the `packed` rectangles.
+///
+/// Over time, an application that frees rectangles (see [`TargetBin.push_available_bin_section`])
+/// and places new ones will end up with a fragmented bin whose free space is split into many
+/// small sections. Re-packing every rectangle from scratch undoes that fragmentation.
+///
+/// Every rectangle stays within the bin that it was already in, so group constraints continue to
+/// hold. Rectangles are re-placed using the size that they were packed with, largest first.
+/// Only the packed locations are known here, so any [`RectToInsert.with_max_x`] or
+/// [`RectToInsert.with_max_y`] constraints that the rectangles were packed with are not honored.
+///
+/// The bins are assumed to contain only the rectangles in `packed`. Any other space that was
+/// filled without being recorded in `packed` will be treated as free space.
+///
+/// If the rectangles of a bin can't all be re-placed, that bin and its rectangles are left as
+/// they were. Frozen bins (see [`TargetBin.freeze`]) are always left as they were.
+///
+/// Returns the rectangles that moved, along with their old and new locations, so that only those
+/// rectangles need to be re-uploaded / copied.
+///
+/// [`RectToInsert.with_max_x`]: crate::RectToInsert::with_max_x
+/// [`RectToInsert.with_max_y`]: crate::RectToInsert::with_max_y
+/// [`TargetBin.freeze`]: crate::TargetBin::freeze
+/// [`TargetBin.push_available_bin_section`]: struct.TargetBin.html#method.push_available_bin_section
+pub fn repack<RectToPlaceId, BinId, Heuristic, Compare>(
+    target_bins: &mut BTreeMap<BinId, TargetBin>,
+    packed: &mut RectanglePackOk<RectToPlaceId, BinId>,
+    box_size_heuristic: &Heuristic,
+    more_suitable_containers_fn: &Compare,
+) -> Vec<(RectToPlaceId, PackedLocation, PackedLocation)>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    Heuristic: BoxSizeHeuristic + ?Sized,
+    Compare: ComparePotentialContainers + ?Sized,
+{
+    let mut rects_per_bin: BTreeMap<BinId, Vec<(RectToPlaceId, PackedLocation)>> = BTreeMap::new();
+    for (rect_id, (bin_id, location)) in packed.packed_locations.iter() {
+        rects_per_bin
+            .entry(bin_id.clone())
+            .or_default()
+            .push((rect_id.clone(), *location));
+    }
+
+    let options = PackOptions::default();
+    let ctx = PackContext::new(box_size_heuristic, more_suitable_containers_fn, &options);
+
+    let mut moves = vec![];
+
+    for (bin_id, mut rects) in rects_per_bin {
+        let bin = match target_bins.get_mut(&bin_id) {
+            Some(bin) if !bin.frozen => bin,
+            _ => continue,
+        };
+
+        rects.sort_by(|a, b| {
+            box_size_heuristic
+                .box_size(b.1.whd)
+                .cmp(&box_size_heuristic.box_size(a.1.whd))
+                .then_with(|| a.0.cmp(&b.0))
+        });
+
+        let mut repacked_bin = bin.emptied();
+        let mut new_locations = Vec::with_capacity(rects.len());
+
+        for (rect_id, old_location) in rects.iter() {
+            let whd = old_location.whd;
+            let rect = RectToInsert::new(whd.width, whd.height, whd.depth)
+                .with_global_x_axis_rotation(old_location.x_axis_rotation.swaps_axes())
+                .with_global_y_axis_rotation(old_location.y_axis_rotation.swaps_axes())
+                .with_global_z_axis_rotation(old_location.z_axis_rotation.swaps_axes());
+
+            match repacked_bin.place_again(&rect, old_location, &ctx) {
+                Some(new_location) => new_locations.push((rect_id, old_location, new_location)),
+                None => break,
+            }
+        }
+
+        if new_locations.len() != rects.len() {
+            continue;
+        }
+
+        // Only the locations are known here, so the weight moves over as a whole.
+        repacked_bin.placed_weight = bin.placed_weight;
+        repacked_bin.metrics = bin.metrics.take();
+        *bin = repacked_bin;
+
+        for (rect_id, old_location, new_location) in new_locations {
+            packed
+                .packed_locations
+                .insert(rect_id.clone(), (bin_id.clone(), new_location));
+
+            if *old_location != new_location {
+                moves.push((rect_id.clone(), *old_location, new_location));
+            }
+        }
+    }
+
+    moves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bin_section::BinSection;
+    use crate::width_height_depth::WidthHeightDepth;
+    use crate::{contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace};
+
+    /// Free the middle of three rectangles and verify that re-packing moves the last rectangle
+    /// into the freed space, leaving one large free section behind.
+    #[test]
+    fn repack_closes_gaps() {
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect(RectToPlaceId::One, None, RectToInsert::new(3, 1, 1));
+        rects_to_place.push_rect(RectToPlaceId::Two, None, RectToInsert::new(3, 1, 1));
+        rects_to_place.push_rect(RectToPlaceId::Three, None, RectToInsert::new(3, 1, 1));
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert(BinId::Four, TargetBin::new(10, 1, 1));
+
+        let mut packed = pack_rects(
+            &rects_to_place,
+            &mut target_bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+
+        let (_, freed) = packed.packed_locations.remove(&RectToPlaceId::Two).unwrap();
+        target_bins
+            .get_mut(&BinId::Four)
+            .unwrap()
+            .push_available_bin_section(BinSection::new(freed.x, freed.y, freed.z, freed.whd))
+            .unwrap();
+
+        let old_three = packed.packed_locations[&RectToPlaceId::Three].1;
+        assert_eq!(old_three.x, 6);
+
+        let moves = repack(
+            &mut target_bins,
+            &mut packed,
+            &volume_heuristic,
+            &contains_smallest_box,
+        );
+
+        let new_three = packed.packed_locations[&RectToPlaceId::Three].1;
+        assert_eq!(new_three.x, 3);
+        assert_eq!(moves, vec![(RectToPlaceId::Three, old_three, new_three)]);
+
+        assert_eq!(
+            target_bins[&BinId::Four].available_bin_sections,
+            vec![BinSection::new(6, 0, 0, WidthHeightDepth::new(4, 1, 1))]
+        );
+    }
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+    enum RectToPlaceId {
+        One,
+        Two,
+        Three,
+    }
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+    enum BinId {
+        Four,
+    }
+}
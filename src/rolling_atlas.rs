@@ -0,0 +1,277 @@
+//! A ring of bins that is filled one generation at a time, for atlases of short lived
+//! rectangles.
+//!
+//! Enabled using the `rolling_atlas` feature.
+
+use crate::pack_options::PackContext;
+use crate::{
+    contains_smallest_box, volume_heuristic, PackOptions, PackedLocation, RectToInsert,
+    RectanglePackError, TargetBin,
+};
+
+use alloc::{collections::BTreeMap, format, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+/// Packs short lived rectangles, such as the text and UI elements drawn over the last few
+/// frames, into a fixed ring of bins.
+///
+/// - Rectangles are placed into the bin of the current generation.
+/// - When a rectangle doesn't fit, or when [`RollingAtlas.advance`] is called, the atlas moves on
+///   to the next bin in the ring and starts a new generation. That bin is cleared first, which
+///   invalidates the oldest generation, along with every placement in it, all at once.
+/// - Every placement carries the generation that it belongs to, so callers can tell whether
+///   what they cached is still in the atlas using [`RollingAtlas.is_live`].
+///
+/// ```
+/// use rectangle_pack::{RectToInsert, RollingAtlas, TargetBin};
+///
+/// let mut atlas = RollingAtlas::new(2, TargetBin::new(16, 16, 1));
+///
+/// let hello = atlas.insert("hello", RectToInsert::new(16, 10, 1)).unwrap();
+/// let world = atlas.insert("world", RectToInsert::new(16, 10, 1)).unwrap();
+/// assert_eq!((hello.generation(), world.generation()), (0, 1));
+///
+/// // Rolling over to the first bin again invalidates "hello".
+/// atlas.insert("again", RectToInsert::new(16, 10, 1)).unwrap();
+/// assert!(!atlas.is_live(hello.generation()));
+/// assert!(atlas.get(&"hello").is_none());
+/// assert!(atlas.get(&"world").is_some());
+/// ```
+///
+/// [`RollingAtlas.advance`]: RollingAtlas::advance
+/// [`RollingAtlas.is_live`]: RollingAtlas::is_live
+#[derive(Debug, Clone)]
+pub struct RollingAtlas<RectToPlaceId> {
+    /// The bins of the ring, along with the generation that each one holds, if any.
+    slots: Vec<(Option<u64>, TargetBin)>,
+    current_slot: usize,
+    generation: u64,
+    options: PackOptions,
+    placements: BTreeMap<RectToPlaceId, RollingPlacement>,
+}
+
+/// Where a rectangle was placed within a [`RollingAtlas`], and which generation it belongs to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RollingPlacement {
+    generation: u64,
+    slot: usize,
+    location: PackedLocation,
+}
+
+impl<RectToPlaceId> RollingAtlas<RectToPlaceId>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+{
+    /// Create a ring of `slot_count` empty copies of the given bin, with the first one holding
+    /// generation `0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slot_count` is `0`.
+    pub fn new(slot_count: usize, bin: TargetBin) -> Self {
+        assert!(slot_count > 0, "a rolling atlas needs at least one bin");
+
+        let mut slots: Vec<(Option<u64>, TargetBin)> =
+            (0..slot_count).map(|_| (None, bin.emptied())).collect();
+        slots[0].0 = Some(0);
+
+        RollingAtlas {
+            slots,
+            current_slot: 0,
+            generation: 0,
+            options: PackOptions::default(),
+            placements: BTreeMap::new(),
+        }
+    }
+
+    /// Place rectangles using these options instead of the defaults.
+    pub fn with_pack_options(mut self, options: PackOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Place a rectangle into the current generation, advancing to the next generation if it
+    /// doesn't fit.
+    ///
+    /// If the rectangle is already placed in a live generation its existing placement is
+    /// returned.
+    ///
+    /// Returns an error if the rectangle wouldn't fit even into an empty bin, in which case the
+    /// atlas is left unchanged.
+    pub fn insert(
+        &mut self,
+        id: RectToPlaceId,
+        rect: RectToInsert,
+    ) -> Result<RollingPlacement, RectanglePackError> {
+        if let Some(placement) = self.placements.get(&id) {
+            return Ok(*placement);
+        }
+
+        // Cloned so that the atlas can advance while the context is in use.
+        let options = self.options.clone();
+        let ctx = PackContext::new(&volume_heuristic, &contains_smallest_box, &options);
+
+        let location = match self.slots[self.current_slot].1.place(&rect, &ctx) {
+            Some(location) => location,
+            None => {
+                let mut empty = self.slots[self.current_slot].1.emptied();
+                if empty.place(&rect, &ctx).is_none() {
+                    return Err(RectanglePackError::NotEnoughBinSpace {
+                        rect_to_place_id: Some(format!("{:?}", id)),
+                        group_id: None,
+                        required_volume: rect.whd.volume(),
+                        available_volume: empty.free_volume(),
+                    });
+                }
+
+                self.advance();
+                self.slots[self.current_slot]
+                    .1
+                    .place(&rect, &ctx)
+                    .expect("fits into an empty bin")
+            }
+        };
+
+        let placement = RollingPlacement {
+            generation: self.generation,
+            slot: self.current_slot,
+            location,
+        };
+        self.placements.insert(id, placement);
+
+        Ok(placement)
+    }
+
+    /// Start a new generation in the next bin of the ring, clearing the bin and invalidating the
+    /// generation that it held.
+    ///
+    /// Returns the new generation.
+    pub fn advance(&mut self) -> u64 {
+        self.generation += 1;
+        self.current_slot = (self.current_slot + 1) % self.slots.len();
+
+        let slot = &mut self.slots[self.current_slot];
+        slot.0 = Some(self.generation);
+        slot.1.clear();
+
+        self.retain_live_placements();
+        self.generation
+    }
+
+    /// Invalidate every generation older than the given one, clearing their bins.
+    ///
+    /// The current generation is never invalidated.
+    pub fn invalidate_before(&mut self, generation: u64) {
+        let generation = generation.min(self.generation);
+
+        for slot in self.slots.iter_mut() {
+            let is_older = match slot.0 {
+                Some(slot_generation) => slot_generation < generation,
+                None => false,
+            };
+            if is_older {
+                slot.0 = None;
+                slot.1.clear();
+            }
+        }
+
+        self.retain_live_placements();
+    }
+
+    /// Where the rectangle was placed, if it is in a live generation.
+    pub fn get(&self, id: &RectToPlaceId) -> Option<&RollingPlacement> {
+        self.placements.get(id)
+    }
+
+    /// Whether the placements of the given generation are still in the atlas.
+    pub fn is_live(&self, generation: u64) -> bool {
+        self.slots
+            .iter()
+            .any(|(slot_generation, _)| *slot_generation == Some(generation))
+    }
+
+    /// The generation that rectangles are currently being placed into.
+    pub fn current_generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The bin at the given position in the ring.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slot` is not less than the number of bins in the ring.
+    pub fn bin(&self, slot: usize) -> &TargetBin {
+        &self.slots[slot].1
+    }
+
+    /// The number of bins in the ring.
+    pub fn slot_count(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn retain_live_placements(&mut self) {
+        let slots = &self.slots;
+        self.placements
+            .retain(|_, placement| slots[placement.slot].0 == Some(placement.generation));
+    }
+}
+
+#[allow(missing_docs)]
+impl RollingPlacement {
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The position of the placement's bin in the ring. See [`RollingAtlas.bin`].
+    ///
+    /// [`RollingAtlas.bin`]: RollingAtlas::bin
+    pub fn slot(&self) -> usize {
+        self.slot
+    }
+
+    pub fn location(&self) -> &PackedLocation {
+        &self.location
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that invalidating old generations clears their bins and drops their placements,
+    /// while the current generation is kept.
+    #[test]
+    fn invalidate_before_clears_old_generations() {
+        let mut atlas = RollingAtlas::new(3, TargetBin::new(10, 10, 1));
+
+        atlas.insert("a", RectToInsert::new(5, 5, 1)).unwrap();
+        assert_eq!(atlas.advance(), 1);
+        atlas.insert("b", RectToInsert::new(5, 5, 1)).unwrap();
+        assert_eq!(atlas.advance(), 2);
+        atlas.insert("c", RectToInsert::new(5, 5, 1)).unwrap();
+
+        atlas.invalidate_before(5);
+
+        assert!(atlas.get(&"a").is_none());
+        assert!(atlas.get(&"b").is_none());
+        assert_eq!(atlas.get(&"c").unwrap().generation(), 2);
+        assert!(atlas.is_live(2));
+        assert_eq!(atlas.bin(0).placed_rect_count(), 0);
+        assert_eq!(atlas.bin(1).placed_rect_count(), 0);
+        assert_eq!(atlas.bin(2).placed_rect_count(), 1);
+    }
+
+    /// Verify that a rectangle too large for an empty bin doesn't advance the generation.
+    #[test]
+    fn error_if_rect_never_fits() {
+        let mut atlas = RollingAtlas::new(2, TargetBin::new(10, 10, 1));
+        atlas.insert("a", RectToInsert::new(5, 5, 1)).unwrap();
+
+        assert!(matches!(
+            atlas.insert("huge", RectToInsert::new(20, 20, 1)),
+            Err(RectanglePackError::NotEnoughBinSpace { .. })
+        ));
+        assert_eq!(atlas.current_generation(), 0);
+        assert!(atlas.get(&"a").is_some());
+    }
+}
@@ -1,9 +1,38 @@
 use crate::bin_section::BinSection;
 use crate::width_height_depth::WidthHeightDepth;
-use alloc::vec::Vec;
+use crate::RectToInsert;
+use alloc::{sync::Arc, vec::Vec};
 
+pub use self::grow::GrowBinError;
+pub use self::metrics::{BinMetrics, CountingBinMetrics};
+pub use self::push_available_bin_section::PushBinSectionError;
+pub use self::size_classes::SizeClasses;
+pub use self::snapshot::BinSnapshot;
+
+mod allocate;
+mod block_size;
 mod coalesce;
+mod contiguous_group;
+mod cost;
+mod edge;
+mod exclusion_zones;
+mod freeze;
+mod from_sections;
+mod grow;
+mod layer;
+mod layered_depth;
+mod metrics;
+mod origin;
+mod place;
+mod priority;
 mod push_available_bin_section;
+mod range;
+mod size_classes;
+mod snapshot;
+mod support;
+mod usage;
+mod used_sections;
+mod weight;
 
 /// A bin that we'd like to play our incoming rectangles into
 #[derive(Debug, Clone)]
@@ -12,6 +41,22 @@ pub struct TargetBin {
     pub(crate) max_height: u32,
     pub(crate) max_depth: u32,
     pub(crate) available_bin_sections: Vec<BinSection>,
+    pub(crate) size_classes: Option<SizeClasses>,
+    pub(crate) metrics: Option<Arc<dyn BinMetrics>>,
+    pub(crate) block_size: WidthHeightDepth,
+    pub(crate) layered_depth: bool,
+    pub(crate) frozen: bool,
+    pub(crate) exclusion_zones: Vec<BinSection>,
+    /// The combined volume of the available bin sections, measured in blocks.
+    pub(crate) free_section_volume: u128,
+    pub(crate) placed_rect_count: usize,
+    pub(crate) priority: u32,
+    pub(crate) cost: u64,
+    pub(crate) used_sections: Option<Vec<BinSection>>,
+    pub(crate) max_weight: Option<u64>,
+    /// The combined weight of the rectangles placed into the bin.
+    pub(crate) placed_weight: u64,
+    pub(crate) origin: [i64; 3],
 }
 
 impl TargetBin {
@@ -33,7 +78,98 @@ impl TargetBin {
             max_height,
             max_depth,
             available_bin_sections,
+            size_classes: None,
+            metrics: None,
+            block_size: WidthHeightDepth::new(1, 1, 1),
+            layered_depth: false,
+            frozen: false,
+            exclusion_zones: vec![],
+            free_section_volume: max_width as u128 * max_height as u128 * max_depth as u128,
+            placed_rect_count: 0,
+            priority: 0,
+            cost: 0,
+            used_sections: None,
+            max_weight: None,
+            placed_weight: 0,
+            origin: [0, 0, 0],
+        }
+    }
+
+    /// Create a flat bin with a depth of `1`, such as a texture atlas.
+    ///
+    /// See [`pack_rects_2d`].
+    ///
+    /// [`pack_rects_2d`]: crate::pack_rects_2d
+    pub fn new_2d(max_width: u32, max_height: u32) -> Self {
+        TargetBin::new(max_width, max_height, 1)
+    }
+
+    /// A copy of this bin, with the same configuration, that has nothing placed in it.
+    ///
+    /// The copy does not report to this bin's [`BinMetrics`].
+    pub(crate) fn emptied(&self) -> TargetBin {
+        let mut emptied = TargetBin::new(self.max_width, self.max_height, self.max_depth);
+        emptied.block_size = self.block_size;
+        emptied.layered_depth = self.layered_depth;
+        emptied.frozen = self.frozen;
+        emptied.priority = self.priority;
+        emptied.cost = self.cost;
+        emptied.max_weight = self.max_weight;
+        emptied.origin = self.origin;
+        emptied.used_sections = self.used_sections.as_ref().map(|_| Vec::new());
+        emptied.exclusion_zones = self.exclusion_zones.clone();
+
+        if let Some(size_classes) = self.size_classes.as_ref() {
+            emptied =
+                emptied.with_size_classes(SizeClasses::new(size_classes.upper_bounds().to_vec()));
+        }
+        emptied.carve_out_exclusion_zones();
+
+        emptied
+    }
+
+    /// Remove everything that was placed into this bin, keeping its configuration and the memory
+    /// that was allocated for its free sections.
+    ///
+    /// Useful for re-packing the same bins every frame without allocating new ones. See
+    /// [`pack_rects_reusing`].
+    ///
+    /// [`pack_rects_reusing`]: crate::pack_rects_reusing
+    pub fn clear(&mut self) {
+        self.clear_available_sections();
+        self.placed_rect_count = 0;
+        self.placed_weight = 0;
+        if let Some(used_sections) = self.used_sections.as_mut() {
+            used_sections.clear();
         }
+        self.insert_available_section(BinSection::new(
+            0,
+            0,
+            0,
+            WidthHeightDepth {
+                width: self.max_width,
+                height: self.max_height,
+                depth: self.max_depth,
+            },
+        ));
+        self.carve_out_exclusion_zones();
+    }
+
+    /// The incoming rectangle, set up to be placed into this bin's blocks and layers.
+    pub(crate) fn rect_for_bin(&self, incoming: &RectToInsert) -> RectToInsert {
+        let mut rect = *incoming;
+
+        rect.block_size = self.block_size;
+        rect.max_x = incoming.max_x.map(|max_x| max_x / self.block_size.width);
+        rect.max_y = incoming.max_y.map(|max_y| max_y / self.block_size.height);
+        rect.layered_depth = self.layered_depth;
+        rect.bin_whd = Some(WidthHeightDepth::new(
+            self.max_width,
+            self.max_height,
+            self.max_depth,
+        ));
+
+        rect
     }
 
     /// The free [`BinSection`]s within the [`TargetBin`] that rectangles can still be placed into.
@@ -43,17 +179,20 @@ impl TargetBin {
 
     /// Remove the section that was just split by a placed rectangle.
     pub fn remove_filled_section(&mut self, idx: usize) {
-        self.available_bin_sections.remove(idx);
+        self.remove_available_section(idx);
     }
 
-    /// When a section is filled it gets split into three new sections.
+    /// When a section is filled it gets split into up to three new sections.
     /// Here we add those.
     ///
-    /// TODO: Ignore sections with a volume of 0
-    pub fn add_new_sections(&mut self, new_sections: [BinSection; 3]) {
-        for new_section in new_sections.iter() {
+    /// Accepts the [`RemainingSections`] from a placement, as well as a plain array of sections.
+    /// Sections with a volume of 0 are ignored.
+    ///
+    /// [`RemainingSections`]: crate::RemainingSections
+    pub fn add_new_sections(&mut self, new_sections: impl AsRef<[BinSection]>) {
+        for new_section in new_sections.as_ref().iter() {
             if new_section.whd.volume() > 0 {
-                self.available_bin_sections.push(*new_section);
+                self.insert_available_section(*new_section);
             }
         }
     }
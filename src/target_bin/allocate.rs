@@ -0,0 +1,150 @@
+//! Placing rectangles into a [`TargetBin`] one at a time, as they arrive.
+
+use crate::pack_options::PackContext;
+use crate::{
+    BoxSizeHeuristic, ComparePotentialContainers, PackOptions, PackedLocation, RectToInsert,
+    RectanglePackError, TargetBin,
+};
+
+impl TargetBin {
+    /// Place a single rectangle into this bin right away, such as when using the bin as a
+    /// runtime atlas allocator, without building a [`GroupedRectsToPlace`].
+    ///
+    /// Returns where the rectangle was placed, which can later be freed by pushing
+    /// [`TargetBin.section_occupied_by`] back using [`TargetBin.push_available_bin_section`].
+    ///
+    /// Returns [`RectanglePackError::NotEnoughBinSpace`] if the rectangle doesn't fit, in which
+    /// case the bin is left unchanged.
+    ///
+    /// ```
+    /// use rectangle_pack::{contains_smallest_box, volume_heuristic, RectToInsert, TargetBin};
+    ///
+    /// let mut atlas = TargetBin::new(32, 32, 1);
+    ///
+    /// let glyph = atlas
+    ///     .allocate(&RectToInsert::new(8, 12, 1), &volume_heuristic, &contains_smallest_box)
+    ///     .unwrap();
+    /// assert_eq!((glyph.width(), glyph.height()), (8, 12));
+    ///
+    /// assert!(atlas
+    ///     .allocate(&RectToInsert::new(64, 64, 1), &volume_heuristic, &contains_smallest_box)
+    ///     .is_err());
+    /// ```
+    ///
+    /// [`GroupedRectsToPlace`]: crate::GroupedRectsToPlace
+    /// [`TargetBin.section_occupied_by`]: TargetBin::section_occupied_by
+    /// [`TargetBin.push_available_bin_section`]: TargetBin::push_available_bin_section
+    pub fn allocate<Heuristic, Compare>(
+        &mut self,
+        incoming: &RectToInsert,
+        box_size_heuristic: &Heuristic,
+        more_suitable_containers_fn: &Compare,
+    ) -> Result<PackedLocation, RectanglePackError>
+    where
+        Heuristic: BoxSizeHeuristic + ?Sized,
+        Compare: ComparePotentialContainers + ?Sized,
+    {
+        self.allocate_with_options(
+            incoming,
+            box_size_heuristic,
+            more_suitable_containers_fn,
+            &PackOptions::default(),
+        )
+    }
+
+    /// The same as [`TargetBin.allocate`], using [`PackOptions`] to control how the rectangle
+    /// gets placed.
+    ///
+    /// [`TargetBin.allocate`]: TargetBin::allocate
+    pub fn allocate_with_options<Heuristic, Compare>(
+        &mut self,
+        incoming: &RectToInsert,
+        box_size_heuristic: &Heuristic,
+        more_suitable_containers_fn: &Compare,
+        options: &PackOptions,
+    ) -> Result<PackedLocation, RectanglePackError>
+    where
+        Heuristic: BoxSizeHeuristic + ?Sized,
+        Compare: ComparePotentialContainers + ?Sized,
+    {
+        let ctx = PackContext::new(box_size_heuristic, more_suitable_containers_fn, options);
+
+        self.place(incoming, &ctx)
+            .ok_or_else(|| RectanglePackError::NotEnoughBinSpace {
+                rect_to_place_id: None,
+                group_id: None,
+                required_volume: incoming.whd.volume(),
+                available_volume: self.free_volume(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{contains_smallest_box, volume_heuristic, BinEdge};
+
+    /// Verify that rectangles are allocated until the bin is full, and that the options are
+    /// used.
+    #[test]
+    fn allocate_until_full() {
+        let mut bin = TargetBin::new(10, 10, 1);
+        let options = PackOptions::new().with_padding(1);
+
+        for _ in 0..4 {
+            bin.allocate_with_options(
+                &RectToInsert::new(3, 3, 1),
+                &volume_heuristic,
+                &contains_smallest_box,
+                &options,
+            )
+            .unwrap();
+        }
+
+        assert_eq!(bin.free_volume(), 0);
+        assert!(matches!(
+            bin.allocate(
+                &RectToInsert::new(1, 1, 1),
+                &volume_heuristic,
+                &contains_smallest_box
+            ),
+            Err(RectanglePackError::NotEnoughBinSpace {
+                required_volume: 1,
+                available_volume: 0,
+                ..
+            })
+        ));
+    }
+
+    /// Verify that a rectangle that can't be allocated leaves the bin's free sections the way
+    /// they were, even when they would have been split or merged to make room for it.
+    #[test]
+    fn failed_allocate_leaves_bin_unchanged() {
+        let mut bin = TargetBin::new(10, 10, 1);
+        bin.allocate(
+            &RectToInsert::new(10, 2, 1),
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+        let before = bin.available_bin_sections().clone();
+
+        let anchored = RectToInsert::new(4, 9, 1).with_edge(BinEdge::Right);
+        assert!(bin
+            .allocate(&anchored, &volume_heuristic, &contains_smallest_box)
+            .is_err());
+        assert_eq!(bin.available_bin_sections(), &before);
+
+        let merging = PackOptions::new().with_free_section_merging(true);
+        assert!(bin
+            .allocate_with_options(
+                &RectToInsert::new(11, 1, 1),
+                &volume_heuristic,
+                &contains_smallest_box,
+                &merging
+            )
+            .is_err());
+        assert_eq!(bin.available_bin_sections(), &before);
+        assert_eq!(bin.placed_rect_count(), 1);
+    }
+}
@@ -0,0 +1,147 @@
+//! Bins whose coordinates are measured in blocks of texels instead of single texels.
+
+use crate::bin_section::BinSection;
+use crate::width_height_depth::WidthHeightDepth;
+use crate::{PackedLocation, TargetBin};
+
+impl TargetBin {
+    /// Treat this bin as a grid of `width x height x depth` blocks, such as the 6x6 texel blocks
+    /// of an ASTC compressed texture.
+    ///
+    /// The bin's size, the sizes of incoming rectangles and the returned [`PackedLocation`]s stay
+    /// in texels. Rectangles are rounded up to a whole number of blocks and are only ever placed
+    /// at the start of a block, so a placed rectangle never shares a block with another one.
+    /// Texels left over at the end of the bin that don't make up a whole block are not used.
+    ///
+    /// The bin's free sections, including the ones pushed back using
+    /// [`TargetBin.push_available_bin_section`] and the volumes of its [`SizeClasses`], are
+    /// measured in blocks. Use [`TargetBin.section_occupied_by`] to get the section to push
+    /// back when removing a rectangle.
+    ///
+    /// This resets the bin's free sections, so it should be called before placing anything into
+    /// it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if width, height or depth is 0.
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace, RectToInsert,
+    ///     TargetBin,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    /// rects_to_place.push_rect("first", None, RectToInsert::new(7, 5, 1));
+    /// rects_to_place.push_rect("second", None, RectToInsert::new(7, 5, 1));
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert(0, TargetBin::new(24, 6, 1).with_block_size(6, 6, 1));
+    ///
+    /// let packed = pack_rects(
+    ///     &rects_to_place,
+    ///     &mut target_bins,
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    /// )
+    /// .unwrap();
+    ///
+    /// // Each 7x5 rectangle takes up two blocks.
+    /// let mut xs: Vec<u32> = packed.packed_locations().values().map(|(_, l)| l.x()).collect();
+    /// xs.sort();
+    /// assert_eq!(xs, vec![0, 12]);
+    /// ```
+    ///
+    /// [`TargetBin.push_available_bin_section`]: TargetBin::push_available_bin_section
+    /// [`TargetBin.section_occupied_by`]: TargetBin::section_occupied_by
+    /// [`SizeClasses`]: crate::SizeClasses
+    pub fn with_block_size(mut self, width: u32, height: u32, depth: u32) -> Self {
+        let block_size = WidthHeightDepth::new(width, height, depth);
+        let dimensions = self.dimensions();
+
+        self.max_width = dimensions.width / block_size.width;
+        self.max_height = dimensions.height / block_size.height;
+        self.max_depth = dimensions.depth / block_size.depth;
+        self.block_size = block_size;
+
+        let mut bin = self.emptied();
+        bin.metrics = self.metrics.take();
+        bin
+    }
+
+    /// The free section that a rectangle that was placed into this bin at the given location
+    /// takes up, for returning it using [`TargetBin.push_available_bin_section`] after removing
+    /// the rectangle.
+    ///
+    /// This takes into account how the rectangle was rotated, its padding and the bin's
+    /// [`TargetBin.with_block_size`].
+    ///
+    /// [`TargetBin.push_available_bin_section`]: TargetBin::push_available_bin_section
+    /// [`TargetBin.with_block_size`]: TargetBin::with_block_size
+    pub fn section_occupied_by(&self, location: &PackedLocation) -> BinSection {
+        let location = location.padded();
+
+        BinSection::new(
+            location.x / self.block_size.width,
+            location.y / self.block_size.height,
+            location.z / self.block_size.depth,
+            location.effective_whd().blocks_covering(self.block_size),
+        )
+    }
+
+    /// The size of the bin in texels, leaving out any texels that don't make up a whole block.
+    pub(crate) fn dimensions(&self) -> WidthHeightDepth {
+        WidthHeightDepth {
+            width: self.max_width * self.block_size.width,
+            height: self.max_height * self.block_size.height,
+            depth: self.max_depth * self.block_size.depth,
+        }
+    }
+
+    /// Convert a location within this bin's blocks into texels.
+    pub(crate) fn location_in_texels(&self, mut location: PackedLocation) -> PackedLocation {
+        location.x *= self.block_size.width;
+        location.y *= self.block_size.height;
+        location.z *= self.block_size.depth;
+        location.origin = self.origin;
+
+        location
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pack_options::PackContext;
+    use crate::packed_location::RotatedBy;
+    use crate::{contains_smallest_box, volume_heuristic, PackOptions, RectToInsert};
+
+    /// Verify that rectangles are rounded up to whole blocks and that freeing a rotated
+    /// rectangle returns the blocks that it took up.
+    #[test]
+    fn places_rotated_rect_on_block_boundaries() {
+        let options = PackOptions::default();
+        let ctx = PackContext::new(&volume_heuristic, &contains_smallest_box, &options);
+
+        let mut bin = TargetBin::new(10, 25, 1).with_block_size(2, 4, 1);
+        assert_eq!(
+            bin.available_bin_sections[0].whd,
+            WidthHeightDepth::new(5, 6, 1)
+        );
+
+        let location = bin
+            .place(
+                &RectToInsert::new(13, 5, 1).with_global_z_axis_rotation(true),
+                &ctx,
+            )
+            .unwrap();
+
+        assert_eq!(location.z_axis_rotation(), RotatedBy::NinetyDegrees);
+        assert_eq!((location.width(), location.height()), (13, 5));
+        assert_eq!(
+            bin.section_occupied_by(&location),
+            BinSection::new(0, 0, 0, WidthHeightDepth::new(3, 4, 1))
+        );
+    }
+}
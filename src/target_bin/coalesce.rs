@@ -1,3 +1,5 @@
+use crate::bin_section::BinSection;
+use crate::width_height_depth::WidthHeightDepth;
 use crate::TargetBin;
 
 use core::ops::Range;
@@ -16,15 +18,21 @@ impl TargetBin {
     /// This means that fully coalescing the entire list of available bin sections is O(n^2) time
     /// complexity, where n is the number of available empty sections.
     ///
+    /// Two sections are neighbors if they share an entire face. At most one neighbor gets combined
+    /// per call. The two sections are removed and the combined section is added back as a new
+    /// section, so the indices of the other available sections can shift.
+    ///
     /// # Basic Usage
     ///
-    /// ```ignore
+    /// ```
     /// # use rectangle_pack::TargetBin;
-    /// let target_bin = my_target_bin();
+    /// let mut target_bin = my_target_bin();
     ///
     /// for idx in 0..target_bin.available_bin_sections().len() {
     ///     let len = target_bin.available_bin_sections().len();
-    ///     target_bin.coalesce_available_sections(idx, 0..len);
+    ///     if idx < len {
+    ///         target_bin.coalesce_available_sections(idx, 0..len);
+    ///     }
     /// }
     ///
     /// # fn my_target_bin () -> TargetBin {
@@ -50,14 +58,17 @@ impl TargetBin {
     ///
     /// Here's a basic eample of splitting the work.
     ///
-    /// ```ignore
+    /// ```
     /// # use rectangle_pack::TargetBin;
-    /// let target_bin = my_target_bin();
+    /// let mut target_bin = my_target_bin();
     ///
     /// let current_frame: usize = get_current_frame() % 600;
     ///
     /// for idx in 0..target_bin.available_bin_sections().len() {
     ///     let len = target_bin.available_bin_sections().len();
+    ///     if idx >= len {
+    ///         break;
+    ///     }
     ///
     ///     let start = len / 600 * current_frame;
     ///     let end = start + len / 600;
@@ -75,14 +86,199 @@ impl TargetBin {
     /// ```
     ///
     /// [`TargetBin.push_available_bin_section`]: #method.push_available_bin_section
-    // TODO: Write tests, implement then remove the "ignore" from the examples above.
-    //  Tests cases should have a rectangle and then a neighbor (above, below, left, right) and
-    //  verify that they get combined, but only if the comparison indices are correct and only if
-    //  the neighbor has the same width (uf above/below) or height (if left/right).
     pub fn coalesce_available_sections(
-        _bin_section_index: usize,
-        _compare_to_indices: Range<usize>,
+        &mut self,
+        bin_section_index: usize,
+        compare_to_indices: Range<usize>,
     ) {
-        unimplemented!()
+        let section = self.available_bin_sections[bin_section_index];
+
+        let end = compare_to_indices
+            .end
+            .min(self.available_bin_sections.len());
+        for compare_to_index in compare_to_indices.start..end {
+            if compare_to_index == bin_section_index {
+                continue;
+            }
+
+            let neighbor = self.available_bin_sections[compare_to_index];
+
+            if let Some(combined) = section.combine(&neighbor) {
+                self.remove_available_section(bin_section_index.max(compare_to_index));
+                self.remove_available_section(bin_section_index.min(compare_to_index));
+                self.insert_available_section(combined);
+
+                if let Some(metrics) = self.metrics.as_ref() {
+                    metrics.record_coalesce();
+                }
+
+                return;
+            }
+        }
+    }
+
+    /// Repeatedly combine neighboring available bin sections until no two sections can be
+    /// combined.
+    ///
+    /// See [`TargetBin.coalesce_available_sections`] for more details.
+    ///
+    /// [`TargetBin.coalesce_available_sections`]: #method.coalesce_available_sections
+    pub fn coalesce_all_available_sections(&mut self) {
+        'coalesce: loop {
+            let len = self.available_bin_sections.len();
+
+            for idx in 0..len {
+                self.coalesce_available_sections(idx, idx + 1..len);
+
+                if self.available_bin_sections.len() != len {
+                    continue 'coalesce;
+                }
+            }
+
+            return;
+        }
+    }
+}
+
+impl BinSection {
+    /// If the two sections are neighbors that share an entire face, the section that they form
+    /// when combined.
+    fn combine(&self, other: &BinSection) -> Option<BinSection> {
+        let (a, b) = (self, other);
+
+        if a.y == b.y && a.z == b.z && a.whd.height == b.whd.height && a.whd.depth == b.whd.depth {
+            if let Some(x) = touching_start(a.x, a.whd.width, b.x, b.whd.width) {
+                let whd = WidthHeightDepth {
+                    width: a.whd.width + b.whd.width,
+                    ..a.whd
+                };
+                return Some(BinSection::new(x, a.y, a.z, whd));
+            }
+        }
+
+        if a.x == b.x && a.z == b.z && a.whd.width == b.whd.width && a.whd.depth == b.whd.depth {
+            if let Some(y) = touching_start(a.y, a.whd.height, b.y, b.whd.height) {
+                let whd = WidthHeightDepth {
+                    height: a.whd.height + b.whd.height,
+                    ..a.whd
+                };
+                return Some(BinSection::new(a.x, y, a.z, whd));
+            }
+        }
+
+        if a.x == b.x && a.y == b.y && a.whd.width == b.whd.width && a.whd.height == b.whd.height {
+            if let Some(z) = touching_start(a.z, a.whd.depth, b.z, b.whd.depth) {
+                let whd = WidthHeightDepth {
+                    depth: a.whd.depth + b.whd.depth,
+                    ..a.whd
+                };
+                return Some(BinSection::new(a.x, a.y, z, whd));
+            }
+        }
+
+        None
+    }
+}
+
+/// If two ranges along an axis touch end to end, the start of their combined range.
+fn touching_start(a_start: u32, a_len: u32, b_start: u32, b_len: u32) -> Option<u32> {
+    if a_start + a_len == b_start {
+        Some(a_start)
+    } else if b_start + b_len == a_start {
+        Some(b_start)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that neighbors to the left, right, above, below, in front and behind of a section
+    /// get combined with it.
+    #[test]
+    fn combines_neighbors_that_share_a_face() {
+        let section = BinSection::new(5, 5, 5, WidthHeightDepth::new(5, 5, 5));
+
+        for (neighbor, expected) in [
+            (
+                BinSection::new(0, 5, 5, WidthHeightDepth::new(5, 5, 5)),
+                BinSection::new(0, 5, 5, WidthHeightDepth::new(10, 5, 5)),
+            ),
+            (
+                BinSection::new(10, 5, 5, WidthHeightDepth::new(1, 5, 5)),
+                BinSection::new(5, 5, 5, WidthHeightDepth::new(6, 5, 5)),
+            ),
+            (
+                BinSection::new(5, 0, 5, WidthHeightDepth::new(5, 5, 5)),
+                BinSection::new(5, 0, 5, WidthHeightDepth::new(5, 10, 5)),
+            ),
+            (
+                BinSection::new(5, 10, 5, WidthHeightDepth::new(5, 2, 5)),
+                BinSection::new(5, 5, 5, WidthHeightDepth::new(5, 7, 5)),
+            ),
+            (
+                BinSection::new(5, 5, 0, WidthHeightDepth::new(5, 5, 5)),
+                BinSection::new(5, 5, 0, WidthHeightDepth::new(5, 5, 10)),
+            ),
+            (
+                BinSection::new(5, 5, 10, WidthHeightDepth::new(5, 5, 3)),
+                BinSection::new(5, 5, 5, WidthHeightDepth::new(5, 5, 8)),
+            ),
+        ]
+        .iter()
+        {
+            let mut bin = bin_with_sections(&[section, *neighbor]);
+            bin.coalesce_available_sections(0, 0..2);
+
+            assert_eq!(bin.available_bin_sections, vec![*expected]);
+        }
+    }
+
+    /// Verify that neighbors that only share part of a face are not combined.
+    #[test]
+    fn does_not_combine_partial_neighbors() {
+        let section = BinSection::new(0, 0, 0, WidthHeightDepth::new(5, 5, 1));
+        let taller_neighbor = BinSection::new(5, 0, 0, WidthHeightDepth::new(5, 6, 1));
+
+        let mut bin = bin_with_sections(&[section, taller_neighbor]);
+        bin.coalesce_available_sections(0, 0..2);
+
+        assert_eq!(bin.available_bin_sections, vec![section, taller_neighbor]);
+    }
+
+    /// Verify that a section is only compared with the sections in the provided range.
+    #[test]
+    fn only_compares_to_provided_indices() {
+        let section = BinSection::new(0, 0, 0, WidthHeightDepth::new(5, 5, 1));
+        let neighbor = BinSection::new(5, 0, 0, WidthHeightDepth::new(5, 5, 1));
+
+        let mut bin = bin_with_sections(&[section, neighbor]);
+        bin.coalesce_available_sections(0, 0..1);
+
+        assert_eq!(bin.available_bin_sections, vec![section, neighbor]);
+    }
+
+    /// Verify that coalescing everything combines chains of neighbors into a single section.
+    #[test]
+    fn coalesce_all_combines_chains() {
+        let mut bin = bin_with_sections(&[
+            BinSection::new(0, 0, 0, WidthHeightDepth::new(2, 5, 1)),
+            BinSection::new(4, 0, 0, WidthHeightDepth::new(2, 5, 1)),
+            BinSection::new(2, 0, 0, WidthHeightDepth::new(2, 5, 1)),
+        ]);
+        bin.coalesce_all_available_sections();
+
+        assert_eq!(
+            bin.available_bin_sections,
+            vec![BinSection::new(0, 0, 0, WidthHeightDepth::new(6, 5, 1))]
+        );
+    }
+
+    fn bin_with_sections(sections: &[BinSection]) -> TargetBin {
+        let mut bin = TargetBin::new(100, 100, 100);
+        bin.available_bin_sections = sections.to_vec();
+        bin
     }
 }
@@ -0,0 +1,158 @@
+//! Placing the rectangles of a group within a single region of a [`TargetBin`].
+//!
+//! See [`PackOptions.with_contiguous_groups`].
+//!
+//! [`PackOptions.with_contiguous_groups`]: crate::PackOptions::with_contiguous_groups
+
+use crate::pack_options::PackContext;
+use crate::{BoxSizeHeuristic, ComparePotentialContainers};
+use crate::{PackOptions, PackedLocation, RectToInsert, TargetBin};
+
+use alloc::vec::Vec;
+
+impl TargetBin {
+    /// Lay the group's rectangles out on their own and then place the bounding box of that
+    /// layout into this bin, so that nothing else can ever be placed between them.
+    ///
+    /// Returns the location of every rectangle, in the same order as the group, or `None` if the
+    /// group does not fit.
+    pub(crate) fn place_contiguous_group<Heuristic, Compare>(
+        &mut self,
+        group: &[&RectToInsert],
+        ctx: &PackContext<Heuristic, Compare>,
+        split_rank: usize,
+    ) -> Option<Vec<PackedLocation>>
+    where
+        Heuristic: BoxSizeHeuristic + ?Sized,
+        Compare: ComparePotentialContainers + ?Sized,
+    {
+        if self.frozen {
+            return None;
+        }
+
+        let mut layout_bin = TargetBin::new(self.max_width, self.max_height, self.max_depth);
+        layout_bin.block_size = self.block_size;
+        layout_bin.layered_depth = self.layered_depth;
+
+        let mut locations = Vec::with_capacity(group.len());
+        for rect in group.iter() {
+            locations.push(layout_bin.place_with_split_rank(rect, ctx, split_rank)?);
+        }
+
+        let (mut width, mut height, mut depth) = (0, 0, 0);
+        for location in locations.iter() {
+            let occupied = layout_bin.section_occupied_by(location);
+            width = width.max(occupied.x + occupied.whd.width);
+            height = height.max(occupied.y + occupied.whd.height);
+            depth = depth.max(occupied.z + occupied.whd.depth);
+        }
+
+        let mut region = RectToInsert::new(
+            width * self.block_size.width,
+            height * self.block_size.height,
+            depth * self.block_size.depth,
+        )
+        .with_padding(0)
+        .with_weight(group.iter().map(|rect| rect.weight).sum());
+        // Keep the layout where it is along the z axis, so that rectangles stay on their layers.
+        if group.iter().any(|rect| rect.layer.is_some()) {
+            region.layer = Some(0);
+        }
+        for (rect, location) in group.iter().zip(locations.iter()) {
+            let whd = location.effective_whd();
+
+            if let Some(max_x) = rect.max_x {
+                let max_x = (max_x + region.whd.width).saturating_sub(location.x + whd.width);
+                region.max_x = Some(region.max_x.map_or(max_x, |m| m.min(max_x)));
+            }
+            if let Some(max_y) = rect.max_y {
+                let max_y = (max_y + region.whd.height).saturating_sub(location.y + whd.height);
+                region.max_y = Some(region.max_y.map_or(max_y, |m| m.min(max_y)));
+            }
+        }
+
+        let region_options = PackOptions {
+            padding: 0,
+            size_rounding: None,
+            ..ctx.options.clone()
+        };
+        let region_ctx = PackContext {
+            options: &region_options,
+            ..*ctx
+        };
+        let region = self.place_with_split_rank(&region, &region_ctx, split_rank)?;
+        self.placed_rect_count += group.len().saturating_sub(1);
+
+        if let Some(used_sections) = self.used_sections.as_mut() {
+            used_sections.pop();
+        }
+        for location in locations.iter_mut() {
+            location.x += region.x;
+            location.y += region.y;
+            location.z += region.z;
+            location.origin = self.origin;
+
+            self.record_used_section(location);
+        }
+
+        Some(locations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bin_section::BinSection;
+    use crate::pack_options::PackContext;
+    use crate::width_height_depth::WidthHeightDepth;
+    use crate::{contains_smallest_box, volume_heuristic, PackOptions, RectToInsert, TargetBin};
+
+    /// Verify that a group is placed within a region that doesn't overlap anything else, and that
+    /// rectangles placed afterwards stay out of that region.
+    #[test]
+    fn group_is_placed_within_its_own_region() {
+        let options = PackOptions::default();
+        let ctx = PackContext::new(&volume_heuristic, &contains_smallest_box, &options);
+
+        let mut bin = TargetBin::new(20, 20, 1);
+        bin.place(&RectToInsert::new(5, 5, 1), &ctx).unwrap();
+
+        let frame = RectToInsert::new(4, 4, 1);
+        let locations = bin
+            .place_contiguous_group(&[&frame, &frame, &frame], &ctx, 0)
+            .unwrap();
+        assert_eq!(bin.placed_rect_count(), 4);
+
+        let x = locations.iter().map(|l| l.x).min().unwrap();
+        let y = locations.iter().map(|l| l.y).min().unwrap();
+        let right = locations.iter().map(|l| l.x + l.width()).max().unwrap();
+        let top = locations.iter().map(|l| l.y + l.height()).max().unwrap();
+        let region = BinSection::new(x, y, 0, WidthHeightDepth::new(right - x, top - y, 1));
+
+        for section in bin.available_bin_sections() {
+            assert!(!section.overlaps(&region));
+        }
+    }
+
+    /// Verify that the rectangles of a group carry the origin of the bin that they were placed
+    /// into.
+    #[test]
+    fn group_carries_bin_origin() {
+        let options = PackOptions::default();
+        let ctx = PackContext::new(&volume_heuristic, &contains_smallest_box, &options);
+
+        let mut bin = TargetBin::new(20, 20, 1).with_origin(-100, 50, 0);
+        bin.place(&RectToInsert::new(5, 20, 1), &ctx).unwrap();
+
+        let frame = RectToInsert::new(4, 4, 1);
+        let locations = bin
+            .place_contiguous_group(&[&frame, &frame], &ctx, 0)
+            .unwrap();
+
+        for location in locations.iter() {
+            assert_eq!(location.origin(), [-100, 50, 0]);
+            assert_eq!(location.world_x(), Some(-100 + location.x() as i64));
+            assert_eq!(location.world_y(), Some(50 + location.y() as i64));
+        }
+        assert!(locations.iter().all(|location| location.x() >= 5));
+    }
+}
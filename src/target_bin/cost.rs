@@ -0,0 +1,35 @@
+//! What it costs to start using a bin.
+
+use crate::TargetBin;
+
+impl TargetBin {
+    /// What it costs to start placing rectangles into this bin while it is empty, such as the
+    /// memory of allocating the texture that backs it.
+    ///
+    /// Only used by [`PackOptions.with_bin_cost_minimization`]. A bin that already holds
+    /// rectangles costs nothing to keep using.
+    ///
+    /// Defaults to `0`.
+    ///
+    /// [`PackOptions.with_bin_cost_minimization`]: crate::PackOptions::with_bin_cost_minimization
+    pub fn with_cost(mut self, cost: u64) -> Self {
+        self.cost = cost;
+        self
+    }
+
+    /// The bin's cost, see [`TargetBin.with_cost`].
+    ///
+    /// [`TargetBin.with_cost`]: TargetBin::with_cost
+    pub fn cost(&self) -> u64 {
+        self.cost
+    }
+
+    /// What placing another rectangle into this bin adds to the total cost of the used bins.
+    pub(crate) fn marginal_cost(&self) -> u64 {
+        if self.placed_rect_count > 0 {
+            0
+        } else {
+            self.cost
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! Placing rectangles flush against an edge of a [`TargetBin`].
+//!
+//! See [`RectToInsert.with_edge`].
+//!
+//! [`RectToInsert.with_edge`]: crate::RectToInsert::with_edge
+
+use crate::bin_section::BinSection;
+use crate::width_height_depth::WidthHeightDepth;
+use crate::{BinEdge, TargetBin};
+
+impl TargetBin {
+    /// Split every free section that runs along the given right or top edge, and is wider or
+    /// taller than the incoming rectangle, so that its last `incoming` blocks become a section of
+    /// their own that a rectangle placed at the start of a section fills up to the edge.
+    ///
+    /// Sections along the left and bottom edges already start at the edge, so they are left as
+    /// they are.
+    pub(crate) fn split_available_sections_at_edge(
+        &mut self,
+        edge: BinEdge,
+        incoming: WidthHeightDepth,
+    ) {
+        let mut idx = 0;
+        while idx < self.available_bin_sections.len() {
+            let section = self.available_bin_sections[idx];
+            let split = match edge {
+                BinEdge::Right
+                    if section.x + section.whd.width == self.max_width
+                        && section.whd.width > incoming.width =>
+                {
+                    let near_width = section.whd.width - incoming.width;
+                    [
+                        BinSection::new(
+                            section.x,
+                            section.y,
+                            section.z,
+                            WidthHeightDepth::new(
+                                near_width,
+                                section.whd.height,
+                                section.whd.depth,
+                            ),
+                        ),
+                        BinSection::new(
+                            section.x + near_width,
+                            section.y,
+                            section.z,
+                            WidthHeightDepth::new(
+                                incoming.width,
+                                section.whd.height,
+                                section.whd.depth,
+                            ),
+                        ),
+                    ]
+                }
+                BinEdge::Top
+                    if section.y + section.whd.height == self.max_height
+                        && section.whd.height > incoming.height =>
+                {
+                    let near_height = section.whd.height - incoming.height;
+                    [
+                        BinSection::new(
+                            section.x,
+                            section.y,
+                            section.z,
+                            WidthHeightDepth::new(
+                                section.whd.width,
+                                near_height,
+                                section.whd.depth,
+                            ),
+                        ),
+                        BinSection::new(
+                            section.x,
+                            section.y + near_height,
+                            section.z,
+                            WidthHeightDepth::new(
+                                section.whd.width,
+                                incoming.height,
+                                section.whd.depth,
+                            ),
+                        ),
+                    ]
+                }
+                _ => {
+                    idx += 1;
+                    continue;
+                }
+            };
+
+            self.remove_available_section(idx);
+            for section in split.iter() {
+                self.insert_available_section(*section);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pack_options::PackContext;
+    use crate::{
+        contains_smallest_box, volume_heuristic, BinEdge, PackOptions, RectToInsert, TargetBin,
+    };
+
+    /// Verify that anchored rectangles are placed against their edges, and aren't placed at all
+    /// once nothing is left along their edge.
+    #[test]
+    fn places_against_edges() {
+        let options = PackOptions::default();
+        let ctx = PackContext::new(&volume_heuristic, &contains_smallest_box, &options);
+
+        let mut bin = TargetBin::new(10, 10, 1);
+
+        let top = RectToInsert::new(4, 4, 1).with_edge(BinEdge::Top);
+        let location = bin.place(&top, &ctx).unwrap();
+        assert_eq!(location.y() + location.height(), 10);
+
+        let right = RectToInsert::new(4, 6, 1).with_edge(BinEdge::Right);
+        let location = bin.place(&right, &ctx).unwrap();
+        assert_eq!(location.x() + location.width(), 10);
+
+        assert!(bin.place(&right, &ctx).is_none());
+        assert_eq!(bin.free_volume(), 100 - 16 - 24);
+    }
+
+    /// Verify that the free sections are only split along the edge when the rectangle fits.
+    #[test]
+    fn failed_placement_leaves_sections_unchanged() {
+        let options = PackOptions::default();
+        let ctx = PackContext::new(&volume_heuristic, &contains_smallest_box, &options);
+
+        let mut bin = TargetBin::new(10, 10, 1);
+        bin.place(&RectToInsert::new(10, 2, 1), &ctx).unwrap();
+        let before = bin.available_bin_sections().clone();
+
+        let too_tall = RectToInsert::new(4, 9, 1).with_edge(BinEdge::Right);
+        assert!(bin.place(&too_tall, &ctx).is_none());
+        assert_eq!(bin.available_bin_sections(), &before);
+    }
+}
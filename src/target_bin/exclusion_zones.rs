@@ -0,0 +1,254 @@
+//! Regions of a bin that rectangles must never be placed into.
+
+use crate::bin_section::BinSection;
+use crate::width_height_depth::WidthHeightDepth;
+use crate::TargetBin;
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+impl TargetBin {
+    /// Never place rectangles anywhere within the given region of the bin, such as a corner of an
+    /// atlas that is reserved for UI elements.
+    ///
+    /// The free sections that overlap the region are split into the smaller sections that
+    /// surround it. Zones can overlap each other and extend past the edge of the bin.
+    ///
+    /// The zone is remembered, so it stays reserved after [`TargetBin.clear`] and can't be
+    /// handed back using [`TargetBin.push_available_bin_section`].
+    ///
+    /// Like the bin's free sections, the zone is measured in blocks for a bin with a
+    /// [`TargetBin.with_block_size`], which should be set before adding any zones.
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects, volume_heuristic, BinSection, GroupedRectsToPlace,
+    ///     RectToInsert, TargetBin, WidthHeightDepth,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut atlas = TargetBin::new(64, 64, 1);
+    /// atlas.add_exclusion_zone(BinSection::new(0, 0, 0, WidthHeightDepth::new(32, 64, 1)));
+    ///
+    /// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    /// rects_to_place.push_rect("sprite", None, RectToInsert::new(16, 16, 1));
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert("atlas", atlas);
+    ///
+    /// let packed = pack_rects(
+    ///     &rects_to_place,
+    ///     &mut target_bins,
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(packed.packed_locations()["sprite"].1.x(), 32);
+    /// ```
+    ///
+    /// [`TargetBin.clear`]: TargetBin::clear
+    /// [`TargetBin.push_available_bin_section`]: TargetBin::push_available_bin_section
+    /// [`TargetBin.with_block_size`]: TargetBin::with_block_size
+    pub fn add_exclusion_zone(&mut self, zone: BinSection) {
+        self.exclusion_zones.push(zone);
+        self.carve_out(&zone);
+    }
+
+    /// Never place rectangles into the given columns, along the full height and depth of the
+    /// bin, such as columns of a surface that another system owns.
+    ///
+    /// This adds an exclusion zone, see [`TargetBin.add_exclusion_zone`]. Like the zones, the
+    /// columns are measured in blocks for a bin with a [`TargetBin.with_block_size`]. An empty
+    /// range reserves nothing.
+    ///
+    /// [`TargetBin.add_exclusion_zone`]: TargetBin::add_exclusion_zone
+    /// [`TargetBin.with_block_size`]: TargetBin::with_block_size
+    pub fn reserve_full_column(&mut self, x: Range<u32>) {
+        if x.is_empty() {
+            return;
+        }
+
+        self.add_exclusion_zone(BinSection::new(
+            x.start,
+            0,
+            0,
+            WidthHeightDepth::new(x.end - x.start, self.max_height, self.max_depth),
+        ));
+    }
+
+    /// Never place rectangles into the given rows, along the full width and depth of the bin.
+    ///
+    /// See [`TargetBin.reserve_full_column`].
+    ///
+    /// [`TargetBin.reserve_full_column`]: TargetBin::reserve_full_column
+    pub fn reserve_full_row(&mut self, y: Range<u32>) {
+        if y.is_empty() {
+            return;
+        }
+
+        self.add_exclusion_zone(BinSection::new(
+            0,
+            y.start,
+            0,
+            WidthHeightDepth::new(self.max_width, y.end - y.start, self.max_depth),
+        ));
+    }
+
+    /// The regions that rectangles must never be placed into, see
+    /// [`TargetBin.add_exclusion_zone`].
+    ///
+    /// [`TargetBin.add_exclusion_zone`]: TargetBin::add_exclusion_zone
+    pub fn exclusion_zones(&self) -> &[BinSection] {
+        &self.exclusion_zones
+    }
+
+    /// Remove every exclusion zone from the free sections.
+    pub(crate) fn carve_out_exclusion_zones(&mut self) {
+        for zone in self.exclusion_zones.clone().iter() {
+            self.carve_out(zone);
+        }
+    }
+
+    /// Replace every free section that overlaps the zone with the parts of it that don't.
+    fn carve_out(&mut self, zone: &BinSection) {
+        let mut idx = self.available_bin_sections.len();
+        let mut remaining = vec![];
+
+        while idx > 0 {
+            idx -= 1;
+
+            let section = self.available_bin_sections[idx];
+            if let Some(overlap) = section.intersection(zone) {
+                self.remove_available_section(idx);
+                remaining.extend(sections_around(&section, &overlap));
+            }
+        }
+
+        for section in remaining {
+            self.insert_available_section(section);
+        }
+    }
+}
+
+/// Split the section into the up to six sections that surround the hole within it: one to the
+/// left and right of it, then one below and above it, then one in front of and behind it.
+fn sections_around(section: &BinSection, hole: &BinSection) -> Vec<BinSection> {
+    let right = hole.x + hole.whd.width;
+    let top = hole.y + hole.whd.height;
+    let back = hole.z + hole.whd.depth;
+
+    let section_right = section.x + section.whd.width;
+    let section_top = section.y + section.whd.height;
+    let section_back = section.z + section.whd.depth;
+
+    let candidates = [
+        (
+            section.x,
+            section.y,
+            section.z,
+            hole.x - section.x,
+            section.whd.height,
+            section.whd.depth,
+        ),
+        (
+            right,
+            section.y,
+            section.z,
+            section_right - right,
+            section.whd.height,
+            section.whd.depth,
+        ),
+        (
+            hole.x,
+            section.y,
+            section.z,
+            hole.whd.width,
+            hole.y - section.y,
+            section.whd.depth,
+        ),
+        (
+            hole.x,
+            top,
+            section.z,
+            hole.whd.width,
+            section_top - top,
+            section.whd.depth,
+        ),
+        (
+            hole.x,
+            hole.y,
+            section.z,
+            hole.whd.width,
+            hole.whd.height,
+            hole.z - section.z,
+        ),
+        (
+            hole.x,
+            hole.y,
+            back,
+            hole.whd.width,
+            hole.whd.height,
+            section_back - back,
+        ),
+    ];
+
+    candidates
+        .iter()
+        .filter(|(_, _, _, width, height, depth)| *width > 0 && *height > 0 && *depth > 0)
+        .map(|(x, y, z, width, height, depth)| {
+            BinSection::new(
+                *x,
+                *y,
+                *z,
+                WidthHeightDepth {
+                    width: *width,
+                    height: *height,
+                    depth: *depth,
+                },
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that a zone in the middle of a section leaves the sections around it, and that the
+    /// zone is reserved again after clearing the bin.
+    #[test]
+    fn carves_zone_out_of_free_sections() {
+        let mut bin = TargetBin::new(10, 10, 1);
+        bin.add_exclusion_zone(BinSection::new(2, 3, 0, WidthHeightDepth::new(4, 5, 1)));
+
+        let expected = vec![
+            BinSection::new(0, 0, 0, WidthHeightDepth::new(2, 10, 1)),
+            BinSection::new(6, 0, 0, WidthHeightDepth::new(4, 10, 1)),
+            BinSection::new(2, 0, 0, WidthHeightDepth::new(4, 3, 1)),
+            BinSection::new(2, 8, 0, WidthHeightDepth::new(4, 2, 1)),
+        ];
+        assert_eq!(bin.available_bin_sections, expected);
+
+        bin.clear();
+        assert_eq!(bin.available_bin_sections, expected);
+    }
+
+    /// Verify that reserving a column and a row leaves only the free space outside of both.
+    #[test]
+    fn reserves_full_columns_and_rows() {
+        let mut bin = TargetBin::new(10, 10, 2);
+        bin.reserve_full_column(2..4);
+        bin.reserve_full_row(5..10);
+        bin.reserve_full_row(0..0);
+
+        assert_eq!(bin.free_volume(), (10 - 2) * 5 * 2);
+        assert_eq!(
+            bin.exclusion_zones(),
+            &[
+                BinSection::new(2, 0, 0, WidthHeightDepth::new(2, 10, 2)),
+                BinSection::new(0, 5, 0, WidthHeightDepth::new(10, 5, 2)),
+            ]
+        );
+    }
+}
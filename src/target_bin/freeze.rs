@@ -0,0 +1,92 @@
+//! Bins that the packer may no longer place rectangles into.
+
+use crate::TargetBin;
+
+impl TargetBin {
+    /// Stop the packer from placing anything new into this bin, such as once an atlas page has
+    /// been uploaded and must not change for the rest of the session.
+    ///
+    /// The rectangles that were already placed stay where they are. [`repack`] and
+    /// [`compact_lightest_bin`] leave a frozen bin's rectangles alone as well.
+    ///
+    /// The bin's free sections and [`BinMetrics`] can still be queried, and free sections can
+    /// still be pushed back using [`TargetBin.push_available_bin_section`] to be used after
+    /// calling [`TargetBin.unfreeze`].
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace, RectToInsert,
+    ///     TargetBin,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    /// rects_to_place.push_rect("new-glyph", None, RectToInsert::new(4, 4, 1));
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert("uploaded-page", TargetBin::new(64, 64, 1));
+    /// target_bins.insert("next-page", TargetBin::new(64, 64, 1));
+    /// target_bins.get_mut("uploaded-page").unwrap().freeze();
+    ///
+    /// let packed = pack_rects(
+    ///     &rects_to_place,
+    ///     &mut target_bins,
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(packed.packed_locations()["new-glyph"].0, "next-page");
+    /// ```
+    ///
+    /// [`repack`]: crate::repack
+    /// [`compact_lightest_bin`]: crate::compact_lightest_bin
+    /// [`BinMetrics`]: crate::BinMetrics
+    /// [`TargetBin.push_available_bin_section`]: TargetBin::push_available_bin_section
+    /// [`TargetBin.unfreeze`]: TargetBin::unfreeze
+    pub fn freeze(&mut self) {
+        self.frozen = true;
+    }
+
+    /// Allow the packer to place rectangles into this bin again after [`TargetBin.freeze`].
+    ///
+    /// [`TargetBin.freeze`]: TargetBin::freeze
+    pub fn unfreeze(&mut self) {
+        self.frozen = false;
+    }
+
+    /// Whether the bin is frozen, see [`TargetBin.freeze`].
+    ///
+    /// [`TargetBin.freeze`]: TargetBin::freeze
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pack_options::PackContext;
+    use crate::{contains_smallest_box, volume_heuristic, PackOptions, RectToInsert};
+
+    /// Verify that nothing is placed into a frozen bin, without touching its free sections, until
+    /// it is unfrozen.
+    #[test]
+    fn does_not_place_into_frozen_bin() {
+        let options = PackOptions::new().with_free_section_merging(true);
+        let ctx = PackContext::new(&volume_heuristic, &contains_smallest_box, &options);
+        let rect = RectToInsert::new(2, 2, 1);
+
+        let mut bin = TargetBin::new(4, 4, 1);
+        bin.place(&rect, &ctx).unwrap();
+        let sections_before = bin.available_bin_sections.clone();
+
+        bin.freeze();
+        assert!(bin.is_frozen());
+        assert_eq!(bin.place(&rect, &ctx), None);
+        assert_eq!(bin.available_bin_sections, sections_before);
+
+        bin.unfreeze();
+        assert!(bin.place(&rect, &ctx).is_some());
+    }
+}
@@ -0,0 +1,113 @@
+//! Bins whose free space isn't a single box.
+
+use crate::bin_section::BinSection;
+use crate::target_bin::PushBinSectionError;
+use crate::TargetBin;
+
+impl TargetBin {
+    /// Create a bin that rectangles can only be placed into the given free sections of, such as
+    /// an L-shaped region or an atlas that already contains fixed content.
+    ///
+    /// Everything outside of the sections counts as used space. Sections with a volume of 0 are
+    /// ignored.
+    ///
+    /// Returns an error if a section sticks out of the bin or overlaps an earlier section.
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects, volume_heuristic, BinSection, GroupedRectsToPlace,
+    ///     RectToInsert, TargetBin, WidthHeightDepth,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// // The bottom left 32x32 of the atlas already holds a baked in logo.
+    /// let free = vec![
+    ///     BinSection::new(32, 0, 0, WidthHeightDepth::new(32, 32, 1)),
+    ///     BinSection::new(0, 32, 0, WidthHeightDepth::new(64, 32, 1)),
+    /// ];
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert("atlas", TargetBin::from_sections(64, 64, 1, free).unwrap());
+    ///
+    /// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    /// rects_to_place.push_rect("banner", None, RectToInsert::new(64, 16, 1));
+    ///
+    /// let packed = pack_rects(
+    ///     &rects_to_place,
+    ///     &mut target_bins,
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(packed.packed_locations()["banner"].1.y(), 32);
+    /// assert_eq!(target_bins["atlas"].used_volume(), 32 * 32 + 64 * 16);
+    /// ```
+    pub fn from_sections(
+        max_width: u32,
+        max_height: u32,
+        max_depth: u32,
+        sections: impl IntoIterator<Item = BinSection>,
+    ) -> Result<Self, PushBinSectionError> {
+        let mut bin = TargetBin::new(max_width, max_height, max_depth);
+        bin.clear_available_sections();
+
+        for section in sections {
+            if section.whd.volume() == 0 {
+                continue;
+            }
+
+            if section.x as u64 + section.whd.width as u64 > max_width as u64
+                || section.y as u64 + section.whd.height as u64 > max_height as u64
+                || section.z as u64 + section.whd.depth as u64 > max_depth as u64
+            {
+                return Err(PushBinSectionError::OutOfBounds(section));
+            }
+
+            if let Some(existing) = bin
+                .available_bin_sections
+                .iter()
+                .find(|existing| existing.intersection(&section).is_some())
+            {
+                return Err(PushBinSectionError::Overlaps {
+                    remaining_section: *existing,
+                    new_section: section,
+                });
+            }
+
+            bin.insert_available_section(section);
+        }
+
+        Ok(bin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::width_height_depth::WidthHeightDepth;
+
+    /// Verify that sections that overlap each other or stick out of the bin are rejected.
+    #[test]
+    fn error_if_sections_invalid() {
+        let left = BinSection::new(0, 0, 0, WidthHeightDepth::new(6, 10, 1));
+        let right = BinSection::new(5, 0, 0, WidthHeightDepth::new(5, 10, 1));
+
+        match TargetBin::from_sections(10, 10, 1, vec![left, right]) {
+            Err(PushBinSectionError::Overlaps {
+                remaining_section,
+                new_section,
+            }) => {
+                assert_eq!(remaining_section, left);
+                assert_eq!(new_section, right);
+            }
+            other => panic!("{:?}", other),
+        }
+
+        let too_wide = BinSection::new(5, 0, 0, WidthHeightDepth::new(6, 10, 1));
+        assert!(matches!(
+            TargetBin::from_sections(10, 10, 1, vec![too_wide]),
+            Err(PushBinSectionError::OutOfBounds(_))
+        ));
+    }
+}
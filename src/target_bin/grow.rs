@@ -0,0 +1,158 @@
+//! Growing a [`TargetBin`] without moving what was already placed into it.
+
+use crate::bin_section::BinSection;
+use crate::width_height_depth::WidthHeightDepth;
+use crate::TargetBin;
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+impl TargetBin {
+    /// Grow the bin to the given size, in texels, adding the new space along its right, top and
+    /// back sides as free sections.
+    ///
+    /// Everything that was already placed into the bin stays where it is, so a dynamic atlas can
+    /// be resized without re-packing it. Only the new texels need to be cleared.
+    ///
+    /// With a [`TargetBin.with_block_size`] any texels that don't make up a whole block are not
+    /// used, the same as when the bin was created.
+    ///
+    /// Returns an error, leaving the bin unchanged, if the new size is smaller than the current
+    /// size along any axis.
+    ///
+    /// ```
+    /// use rectangle_pack::TargetBin;
+    ///
+    /// let mut bin = TargetBin::new(16, 16, 1);
+    /// bin.grow(32, 16, 1).unwrap();
+    ///
+    /// assert_eq!(bin.free_volume(), 32 * 16);
+    /// assert!(bin.grow(8, 16, 1).is_err());
+    /// ```
+    ///
+    /// [`TargetBin.with_block_size`]: TargetBin::with_block_size
+    pub fn grow(
+        &mut self,
+        new_width: u32,
+        new_height: u32,
+        new_depth: u32,
+    ) -> Result<(), GrowBinError> {
+        let current = self.dimensions();
+        let requested = WidthHeightDepth::new(new_width, new_height, new_depth);
+
+        if requested.width < current.width
+            || requested.height < current.height
+            || requested.depth < current.depth
+        {
+            return Err(GrowBinError::WouldShrink { current, requested });
+        }
+
+        self.grow_to_blocks(WidthHeightDepth::new(
+            new_width / self.block_size.width,
+            new_height / self.block_size.height,
+            new_depth / self.block_size.depth,
+        ));
+
+        Ok(())
+    }
+
+    /// Grow the bin to the given number of blocks along each axis, which must not be smaller than
+    /// it is now, adding the new space as free sections.
+    pub(crate) fn grow_to_blocks(&mut self, blocks: WidthHeightDepth) {
+        let (width, height, depth) = (self.max_width, self.max_height, self.max_depth);
+
+        let new_sections = [
+            ([width, 0, 0], [blocks.width - width, height, depth]),
+            (
+                [0, height, 0],
+                [blocks.width, blocks.height - height, depth],
+            ),
+            (
+                [0, 0, depth],
+                [blocks.width, blocks.height, blocks.depth - depth],
+            ),
+        ];
+
+        self.max_width = blocks.width;
+        self.max_height = blocks.height;
+        self.max_depth = blocks.depth;
+
+        for ([x, y, z], [width, height, depth]) in new_sections.iter() {
+            if *width > 0 && *height > 0 && *depth > 0 {
+                let whd = WidthHeightDepth::new(*width, *height, *depth);
+                self.insert_available_section(BinSection::new(*x, *y, *z, whd));
+            }
+        }
+    }
+}
+
+/// An error while attempting to grow a [`TargetBin`].
+///
+/// See [`TargetBin.grow`].
+///
+/// [`TargetBin.grow`]: TargetBin::grow
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrowBinError {
+    /// The requested size is smaller than the bin along at least one axis.
+    WouldShrink {
+        /// The size of the bin, in texels.
+        current: WidthHeightDepth,
+        /// The size that the bin was asked to grow to, in texels.
+        requested: WidthHeightDepth,
+    },
+}
+
+impl Display for GrowBinError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            GrowBinError::WouldShrink { current, requested } => write!(
+                f,
+                "Can not grow a {}x{}x{} bin to {}x{}x{}, since that would shrink it.",
+                current.width,
+                current.height,
+                current.depth,
+                requested.width,
+                requested.height,
+                requested.depth
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pack_options::PackContext;
+    use crate::{contains_smallest_box, volume_heuristic, PackOptions, RectToInsert};
+
+    /// Verify that growing keeps what was placed, and that the new space along every axis can be
+    /// placed into.
+    #[test]
+    fn grown_space_can_be_placed_into() {
+        let options = PackOptions::default();
+        let ctx = PackContext::new(&volume_heuristic, &contains_smallest_box, &options);
+
+        let mut bin = TargetBin::new(4, 4, 1);
+        bin.place(&RectToInsert::new(4, 4, 1), &ctx).unwrap();
+
+        bin.grow(8, 6, 2).unwrap();
+        assert_eq!(bin.placed_rect_count(), 1);
+        assert_eq!(bin.free_volume(), 8 * 6 * 2 - 16);
+
+        let location = bin.place(&RectToInsert::new(4, 4, 1), &ctx).unwrap();
+        assert!(location.x() >= 4 || location.y() >= 4 || location.z() >= 1);
+    }
+
+    /// Verify that we return an error, without changing the bin, if it would shrink.
+    #[test]
+    fn error_if_shrinking() {
+        let mut bin = TargetBin::new(4, 4, 1);
+
+        assert_eq!(
+            bin.grow(8, 3, 1),
+            Err(GrowBinError::WouldShrink {
+                current: WidthHeightDepth::new(4, 4, 1),
+                requested: WidthHeightDepth::new(8, 3, 1),
+            })
+        );
+        assert_eq!(bin.free_volume(), 16);
+    }
+}
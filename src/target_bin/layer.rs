@@ -0,0 +1,158 @@
+//! Placing rectangles onto a given layer of a [`TargetBin`].
+//!
+//! See [`RectToInsert.with_layer`].
+//!
+//! [`RectToInsert.with_layer`]: crate::RectToInsert::with_layer
+
+use crate::bin_section::BinSection;
+use crate::width_height_depth::WidthHeightDepth;
+use crate::TargetBin;
+
+impl TargetBin {
+    /// Split every free section that covers the given `z`, in texels, without starting at it
+    /// into the part in front of it and the part from it onwards, so that a rectangle placed at
+    /// the start of a section ends up on that layer.
+    // `u32::is_multiple_of` needs a newer Rust than the rest of the crate.
+    #[allow(clippy::manual_is_multiple_of)]
+    pub(crate) fn split_available_sections_at_layer(&mut self, layer: u32) {
+        if layer % self.block_size.depth != 0 {
+            return;
+        }
+        let layer = layer / self.block_size.depth;
+
+        let mut idx = 0;
+        while idx < self.available_bin_sections.len() {
+            let section = self.available_bin_sections[idx];
+            if section.z >= layer || section.z + section.whd.depth <= layer {
+                idx += 1;
+                continue;
+            }
+
+            self.remove_available_section(idx);
+
+            let front_depth = layer - section.z;
+            let front = WidthHeightDepth::new(section.whd.width, section.whd.height, front_depth);
+            let back = WidthHeightDepth::new(
+                section.whd.width,
+                section.whd.height,
+                section.whd.depth - front_depth,
+            );
+            self.insert_available_section(BinSection::new(section.x, section.y, section.z, front));
+            self.insert_available_section(BinSection::new(section.x, section.y, layer, back));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pack_options::PackContext;
+    use crate::{
+        contains_smallest_box, volume_heuristic, BinSection, PackOptions, RectToInsert, TargetBin,
+        WidthHeightDepth,
+    };
+
+    /// Verify that a rectangle is placed onto its layer, and that a layer that is already full
+    /// can't be placed onto even though other layers are free.
+    #[test]
+    fn places_onto_layer() {
+        let options = PackOptions::default();
+        let ctx = PackContext::new(&volume_heuristic, &contains_smallest_box, &options);
+
+        let mut bin = TargetBin::new(4, 4, 8).with_layered_depth(true);
+
+        let decal = RectToInsert::new(4, 4, 1).with_layer(3);
+        assert_eq!(bin.place(&decal, &ctx).unwrap().z(), 3);
+        assert_eq!(bin.free_volume(), 4 * 4 * 7);
+
+        assert!(bin.place(&decal, &ctx).is_none());
+        assert_eq!(
+            bin.place(&RectToInsert::new(4, 4, 1).with_layer(0), &ctx)
+                .unwrap()
+                .z(),
+            0
+        );
+    }
+
+    /// Verify that a free section that covers the layer is split into the part in front of the
+    /// layer and the part from the layer onwards, and that sections already starting at or ending
+    /// before the layer are left alone.
+    #[test]
+    fn splits_sections_at_layer() {
+        let mut bin = TargetBin::new(4, 4, 8);
+
+        bin.split_available_sections_at_layer(3);
+        let mut sections = bin.available_bin_sections().clone();
+        sections.sort();
+        assert_eq!(
+            sections,
+            [
+                BinSection::new(0, 0, 0, WidthHeightDepth::new(4, 4, 3)),
+                BinSection::new(0, 0, 3, WidthHeightDepth::new(4, 4, 5)),
+            ]
+        );
+
+        bin.split_available_sections_at_layer(3);
+        bin.split_available_sections_at_layer(0);
+        assert_eq!(bin.available_bin_sections().len(), 2);
+    }
+
+    /// Verify that a rectangle on a layer is never rotated about the x or y axis, which would
+    /// move its front face off of the layer.
+    #[test]
+    fn not_rotated_off_of_layer() {
+        let options = PackOptions::default();
+        let ctx = PackContext::new(&volume_heuristic, &contains_smallest_box, &options);
+
+        let rotatable = RectToInsert::new(1, 1, 2)
+            .with_global_x_axis_rotation(true)
+            .with_global_y_axis_rotation(true);
+
+        let mut bin = TargetBin::new(4, 4, 4);
+        assert!(bin.place(&rotatable.with_layer(3), &ctx).is_none());
+
+        let mut bin = TargetBin::new(4, 4, 4);
+        let placed = bin.place(&rotatable.with_layer(2), &ctx).unwrap();
+        assert_eq!((placed.z(), placed.depth()), (2, 2));
+        assert!(!placed.is_rotated());
+    }
+
+    /// Verify that a layer that isn't on a block boundary of the bin can't be placed onto.
+    #[test]
+    fn layer_off_block_boundary_not_placed() {
+        let options = PackOptions::default();
+        let ctx = PackContext::new(&volume_heuristic, &contains_smallest_box, &options);
+
+        let mut bin = TargetBin::new(4, 4, 8).with_block_size(1, 1, 2);
+
+        assert!(bin
+            .place(&RectToInsert::new(4, 4, 2).with_layer(3), &ctx)
+            .is_none());
+        assert_eq!(
+            bin.place(&RectToInsert::new(4, 4, 2).with_layer(4), &ctx)
+                .unwrap()
+                .z(),
+            4
+        );
+    }
+
+    /// Verify that the free sections are only split at the layer when the rectangle fits, so
+    /// that a failed placement doesn't leave them cut up for the rectangles that come later.
+    #[test]
+    fn failed_placement_leaves_sections_unchanged() {
+        let options = PackOptions::default();
+        let ctx = PackContext::new(&volume_heuristic, &contains_smallest_box, &options);
+
+        let mut bin = TargetBin::new(4, 4, 4);
+        let before = bin.available_bin_sections().clone();
+
+        assert!(bin
+            .place(&RectToInsert::new(1, 1, 2).with_layer(3), &ctx)
+            .is_none());
+        assert_eq!(bin.available_bin_sections(), &before);
+
+        let placed = bin
+            .place(&RectToInsert::new(4, 4, 2).with_layer(2), &ctx)
+            .unwrap();
+        assert_eq!((placed.z(), placed.depth()), (2, 2));
+    }
+}
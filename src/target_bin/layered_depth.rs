@@ -0,0 +1,45 @@
+//! Bins whose depth is a number of texture array layers.
+
+use crate::TargetBin;
+
+impl TargetBin {
+    /// Treat the depth of this bin, and of the rectangles placed into it, as a number of layers,
+    /// such as the layers of a texture array, instead of as a third dimension.
+    ///
+    /// A rectangle with a depth of `N` is given `N` contiguous layers, starting at its
+    /// [`PackedLocation.z`], that all have the same `x`, `y`, width and height. The rectangle is
+    /// never rotated about the x or y axis, since that would spread its texels across layers, and
+    /// not having enough layers left is reported as
+    /// [`BinSectionError::PlacementHasMoreLayersThanBinSection`].
+    ///
+    /// [`PackedLocation.z`]: crate::PackedLocation::z
+    /// [`BinSectionError::PlacementHasMoreLayersThanBinSection`]: crate::BinSectionError::PlacementHasMoreLayersThanBinSection
+    pub fn with_layered_depth(mut self, layered_depth: bool) -> Self {
+        self.layered_depth = layered_depth;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pack_options::PackContext;
+    use crate::packed_location::RotatedBy;
+    use crate::{contains_smallest_box, volume_heuristic, PackOptions, RectToInsert};
+
+    /// Verify that a rectangle is not rotated into the layers of a layered bin, even when it would
+    /// only fit that way.
+    #[test]
+    fn does_not_rotate_into_layers() {
+        let options = PackOptions::default();
+        let ctx = PackContext::new(&volume_heuristic, &contains_smallest_box, &options);
+        let rect = RectToInsert::new(4, 4, 1).with_global_x_axis_rotation(true);
+
+        let mut bin = TargetBin::new(4, 1, 4);
+        let location = bin.place(&rect, &ctx).unwrap();
+        assert_eq!(location.x_axis_rotation(), RotatedBy::NinetyDegrees);
+
+        let mut layered = TargetBin::new(4, 1, 4).with_layered_depth(true);
+        assert!(layered.place(&rect, &ctx).is_none());
+    }
+}
@@ -0,0 +1,253 @@
+//! Reporting what happens to a [`TargetBin`] over its lifetime.
+
+use crate::TargetBin;
+
+use alloc::sync::Arc;
+use core::fmt::Debug;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Receives a call every time that something happens to a [`TargetBin`] that it was attached to
+/// using [`TargetBin.with_metrics`].
+///
+/// Useful for long running services that keep placing rectangles into and freeing rectangles
+/// from the same bins and want to export metrics about them, without having to wrap every call
+/// site.
+///
+/// Every method does nothing by default, so only the events that you care about need to be
+/// implemented. See [`CountingBinMetrics`] for an implementation that counts every event.
+///
+/// Only changes to the bin itself are reported. The copies of a bin that are used to check
+/// whether or not a group of rectangles fits (or to look ahead) don't report anything, but
+/// placements that are later undone by backtracking are still reported. The rectangles moved by
+/// [`compact_lightest_bin`] and [`repack`] are not reported as allocations.
+///
+/// [`TargetBin.with_metrics`]: crate::TargetBin::with_metrics
+/// [`compact_lightest_bin`]: crate::compact_lightest_bin
+/// [`repack`]: crate::repack
+pub trait BinMetrics: Debug + Send + Sync {
+    /// A rectangle was placed into the bin after trying `sections_searched` free sections.
+    fn record_allocation(&self, sections_searched: usize) {
+        let _ = sections_searched;
+    }
+
+    /// A rectangle could not be placed into the bin after trying `sections_searched` free
+    /// sections.
+    fn record_failure(&self, sections_searched: usize) {
+        let _ = sections_searched;
+    }
+
+    /// Space was returned to the bin using [`TargetBin.push_available_bin_section_unchecked`] (or
+    /// one of the methods that call it), usually because a rectangle was evicted.
+    ///
+    /// [`TargetBin.push_available_bin_section_unchecked`]: crate::TargetBin::push_available_bin_section_unchecked
+    fn record_eviction(&self) {}
+
+    /// Two of the bin's free sections were combined into one.
+    fn record_coalesce(&self) {}
+}
+
+/// [`BinMetrics`] that count every event.
+///
+/// The same counters can be shared by many bins by attaching the same `Arc` to each of them.
+///
+/// ```
+/// use rectangle_pack::{CountingBinMetrics, TargetBin};
+/// use std::sync::Arc;
+///
+/// let metrics = Arc::new(CountingBinMetrics::default());
+/// let bin = TargetBin::new(2048, 2048, 1).with_metrics(metrics.clone());
+///
+/// assert_eq!(metrics.allocations(), 0);
+/// ```
+#[derive(Debug, Default)]
+pub struct CountingBinMetrics {
+    allocations: AtomicUsize,
+    failures: AtomicUsize,
+    evictions: AtomicUsize,
+    coalesces: AtomicUsize,
+    sections_searched: AtomicUsize,
+}
+
+impl CountingBinMetrics {
+    /// The number of rectangles that were placed.
+    pub fn allocations(&self) -> usize {
+        self.allocations.load(Ordering::Relaxed)
+    }
+
+    /// The number of times that a rectangle could not be placed.
+    pub fn failures(&self) -> usize {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    /// The number of times that space was returned to a bin.
+    pub fn evictions(&self) -> usize {
+        self.evictions.load(Ordering::Relaxed)
+    }
+
+    /// The number of times that two free sections were combined.
+    pub fn coalesces(&self) -> usize {
+        self.coalesces.load(Ordering::Relaxed)
+    }
+
+    /// The average number of free sections that were tried per placement attempt, including the
+    /// attempts that failed.
+    pub fn average_search_length(&self) -> f64 {
+        let attempts = self.allocations() + self.failures();
+        if attempts == 0 {
+            return 0.;
+        }
+
+        self.sections_searched.load(Ordering::Relaxed) as f64 / attempts as f64
+    }
+}
+
+impl BinMetrics for CountingBinMetrics {
+    fn record_allocation(&self, sections_searched: usize) {
+        self.allocations.fetch_add(1, Ordering::Relaxed);
+        self.sections_searched
+            .fetch_add(sections_searched, Ordering::Relaxed);
+    }
+
+    fn record_failure(&self, sections_searched: usize) {
+        self.failures.fetch_add(1, Ordering::Relaxed);
+        self.sections_searched
+            .fetch_add(sections_searched, Ordering::Relaxed);
+    }
+
+    fn record_eviction(&self) {
+        self.evictions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_coalesce(&self) {
+        self.coalesces.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl TargetBin {
+    /// Report everything that happens to this bin to the given [`BinMetrics`].
+    pub fn with_metrics(mut self, metrics: Arc<dyn BinMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// The [`BinMetrics`] that this bin reports to, if any.
+    pub fn metrics(&self) -> Option<&Arc<dyn BinMetrics>> {
+        self.metrics.as_ref()
+    }
+
+    /// A copy of this bin that does not report to any [`BinMetrics`], for simulating placements
+    /// that might not end up happening.
+    pub(crate) fn clone_without_metrics(&self) -> TargetBin {
+        TargetBin {
+            metrics: None,
+            ..self.clone()
+        }
+    }
+
+    /// Overwrite `copy` with a copy of this bin that does not report to any [`BinMetrics`],
+    /// reusing the allocations that `copy` already has.
+    pub(crate) fn clone_without_metrics_into(&self, copy: &mut TargetBin) {
+        let TargetBin {
+            max_width,
+            max_height,
+            max_depth,
+            available_bin_sections,
+            size_classes,
+            metrics: _,
+            block_size,
+            layered_depth,
+            frozen,
+            exclusion_zones,
+            free_section_volume,
+            placed_rect_count,
+            priority,
+            cost,
+            used_sections,
+            max_weight,
+            placed_weight,
+            origin,
+        } = self;
+
+        copy.max_width = *max_width;
+        copy.max_height = *max_height;
+        copy.max_depth = *max_depth;
+        copy.available_bin_sections
+            .clone_from(available_bin_sections);
+        copy.size_classes.clone_from(size_classes);
+        copy.metrics = None;
+        copy.block_size = *block_size;
+        copy.layered_depth = *layered_depth;
+        copy.frozen = *frozen;
+        copy.exclusion_zones.clone_from(exclusion_zones);
+        copy.free_section_volume = *free_section_volume;
+        copy.placed_rect_count = *placed_rect_count;
+        copy.priority = *priority;
+        copy.cost = *cost;
+        copy.used_sections.clone_from(used_sections);
+        copy.max_weight = *max_weight;
+        copy.placed_weight = *placed_weight;
+        copy.origin = *origin;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bin_section::BinSection;
+    use crate::width_height_depth::WidthHeightDepth;
+    use crate::{
+        contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace, RectToInsert,
+    };
+    use alloc::collections::BTreeMap;
+
+    /// Verify that packing reports one allocation per rectangle, and none for the copies of the
+    /// bin that are used to check whether a group fits.
+    #[test]
+    fn packing_records_allocations() {
+        let metrics = Arc::new(CountingBinMetrics::default());
+
+        let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect(1, None, RectToInsert::new(5, 5, 1));
+        rects_to_place.push_rect(2, None, RectToInsert::new(5, 5, 1));
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert(0, TargetBin::new(10, 5, 1).with_metrics(metrics.clone()));
+
+        pack_rects(
+            &rects_to_place,
+            &mut target_bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+
+        assert_eq!(metrics.allocations(), 2);
+        assert_eq!(metrics.failures(), 0);
+        assert_eq!(metrics.average_search_length(), 1.);
+    }
+
+    /// Verify that returning space to a bin and then coalescing it gets reported.
+    #[test]
+    fn records_evictions_and_coalesces() {
+        let metrics = Arc::new(CountingBinMetrics::default());
+
+        let mut bin = TargetBin::new(10, 10, 1).with_metrics(metrics.clone());
+        bin.available_bin_sections.clear();
+        bin.push_available_bin_section_unchecked(BinSection::new(
+            0,
+            0,
+            0,
+            WidthHeightDepth::new(5, 10, 1),
+        ));
+        bin.push_available_bin_section_unchecked(BinSection::new(
+            5,
+            0,
+            0,
+            WidthHeightDepth::new(5, 10, 1),
+        ));
+        bin.coalesce_all_available_sections();
+
+        assert_eq!(metrics.evictions(), 2);
+        assert_eq!(metrics.coalesces(), 1);
+    }
+}
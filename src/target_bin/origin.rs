@@ -0,0 +1,57 @@
+//! Placing a [`TargetBin`] within the caller's coordinate space.
+
+use crate::TargetBin;
+
+impl TargetBin {
+    /// Place the bin's `(0, 0, 0)` at the given position of the caller's coordinate space, which
+    /// may be negative, such as for a page of a virtual texture with signed page coordinates.
+    ///
+    /// Every rectangle placed into the bin carries the origin, so that
+    /// [`PackedLocation.world_x`], [`PackedLocation.world_y`] and [`PackedLocation.world_z`] are
+    /// in the caller's coordinate space. Everything else, such as the free sections and
+    /// [`PackedLocation.x`], stays relative to the bin.
+    ///
+    /// Defaults to `(0, 0, 0)`.
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace, RectToInsert,
+    ///     TargetBin,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    /// rects_to_place.push_rect("tile", None, RectToInsert::new(4, 4, 1));
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert("page", TargetBin::new(64, 64, 1).with_origin(-128, 64, 0));
+    ///
+    /// let packed = pack_rects(
+    ///     &rects_to_place,
+    ///     &mut target_bins,
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    /// )
+    /// .unwrap();
+    ///
+    /// let (_, tile) = packed.packed_locations()["tile"];
+    /// assert_eq!((tile.x(), tile.y()), (0, 0));
+    /// assert_eq!((tile.world_x(), tile.world_y()), (Some(-128), Some(64)));
+    /// ```
+    ///
+    /// [`PackedLocation.world_x`]: crate::PackedLocation::world_x
+    /// [`PackedLocation.world_y`]: crate::PackedLocation::world_y
+    /// [`PackedLocation.world_z`]: crate::PackedLocation::world_z
+    /// [`PackedLocation.x`]: crate::PackedLocation::x
+    pub fn with_origin(mut self, x: i64, y: i64, z: i64) -> Self {
+        self.origin = [x, y, z];
+        self
+    }
+
+    /// See [`TargetBin.with_origin`].
+    ///
+    /// [`TargetBin.with_origin`]: TargetBin::with_origin
+    pub fn origin(&self) -> [i64; 3] {
+        self.origin
+    }
+}
@@ -0,0 +1,409 @@
+//! Choosing the section of a [`TargetBin`] that an incoming rectangle gets placed into.
+
+use crate::bin_section::BinSection;
+use crate::pack_options::{is_valid_split, PackContext};
+use crate::{
+    sort_by_size_largest_to_smallest, BinEdge, BoxSizeHeuristic, ComparePotentialContainers,
+    PackOptions, PackedLocation, PlacementPolicy, RectToInsert, RemainingSections, TargetBin,
+};
+
+impl TargetBin {
+    /// Place the incoming rectangle into one of the available sections that can fit it,
+    /// splitting that section into new sections.
+    ///
+    /// Sections are tried starting from the most recently added ones, which are the smaller
+    /// sections from the most recent split. If the bin has [`SizeClasses`] the free-list of the
+    /// incoming rectangle's size class is searched first.
+    ///
+    /// Which of the sections that can fit the rectangle gets used is decided by the
+    /// [`PlacementPolicy`].
+    ///
+    /// Returns `None` if the rectangle does not fit in any section.
+    ///
+    /// [`SizeClasses`]: crate::SizeClasses
+    pub(crate) fn place<Heuristic, Compare>(
+        &mut self,
+        incoming: &RectToInsert,
+        ctx: &PackContext<Heuristic, Compare>,
+    ) -> Option<PackedLocation>
+    where
+        Heuristic: BoxSizeHeuristic + ?Sized,
+        Compare: ComparePotentialContainers + ?Sized,
+    {
+        self.place_with_split_rank(incoming, ctx, 0)
+    }
+
+    /// The same as [`TargetBin.place`], but split the chosen section using the configuration at
+    /// the given rank instead of the best one.
+    ///
+    /// See [`BinSection.try_place_with_split_rank`].
+    pub(crate) fn place_with_split_rank<Heuristic, Compare>(
+        &mut self,
+        incoming: &RectToInsert,
+        ctx: &PackContext<Heuristic, Compare>,
+        split_rank: usize,
+    ) -> Option<PackedLocation>
+    where
+        Heuristic: BoxSizeHeuristic + ?Sized,
+        Compare: ComparePotentialContainers + ?Sized,
+    {
+        if self.frozen || !self.can_carry(incoming.weight) {
+            return None;
+        }
+
+        if incoming.is_empty() {
+            return self.place_empty(incoming, ctx.options);
+        }
+
+        // The free sections are only merged and split to make room for the rectangle, so they are
+        // put back the way they were if it doesn't fit after all.
+        let reshapes_free_sections = ctx.options.merge_free_sections
+            || (!ctx.options.one_dimensional
+                && (incoming.layer.is_some() || incoming.edge.is_some()));
+        let saved = if reshapes_free_sections {
+            Some(self.save_free_sections())
+        } else {
+            None
+        };
+
+        let location = self.place_into_free_sections(incoming, ctx, split_rank);
+        if let (None, Some(saved)) = (location.as_ref(), saved) {
+            self.restore_free_sections(saved);
+        }
+
+        location
+    }
+
+    /// The part of [`TargetBin.place_with_split_rank`] that searches the free sections, which may
+    /// merge and split them even when the rectangle doesn't fit.
+    ///
+    /// [`TargetBin.place_with_split_rank`]: TargetBin::place_with_split_rank
+    fn place_into_free_sections<Heuristic, Compare>(
+        &mut self,
+        incoming: &RectToInsert,
+        ctx: &PackContext<Heuristic, Compare>,
+        split_rank: usize,
+    ) -> Option<PackedLocation>
+    where
+        Heuristic: BoxSizeHeuristic + ?Sized,
+        Compare: ComparePotentialContainers + ?Sized,
+    {
+        if ctx.options.merge_free_sections {
+            self.coalesce_all_available_sections();
+        }
+
+        if ctx.options.one_dimensional {
+            return self.place_range(incoming, ctx);
+        }
+
+        if let Some(layer) = incoming.layer {
+            self.split_available_sections_at_layer(layer);
+        }
+
+        let requested = incoming.whd;
+        let (incoming, padding) = &self.rect_to_place(incoming, ctx.options);
+        if let Some(edge) = incoming.edge {
+            self.split_available_sections_at_edge(edge, incoming.unrotated_whd_in_blocks());
+        }
+        let volume = incoming.unrotated_whd_in_blocks().volume();
+        let edge_aligned = match ctx.options.elongated_aspect_ratio {
+            Some(min_aspect_ratio) => incoming.is_elongated(min_aspect_ratio),
+            None => false,
+        };
+
+        let mut chosen: Option<(usize, PackedLocation, RemainingSections)> = None;
+        let mut sections_searched = 0;
+
+        for section_idx in self.section_search_order(volume) {
+            if !ctx.examine_section() {
+                return None;
+            }
+            sections_searched += 1;
+            let section = &self.available_bin_sections[section_idx];
+
+            let placement = match section.try_place_in_allowed_orientations(
+                incoming,
+                ctx.more_suitable_containers_fn,
+                ctx.box_size_heuristic,
+                ctx.options.orientation_comparator.as_deref(),
+                split_rank,
+            ) {
+                Ok(placement) => placement,
+                Err(_) => continue,
+            };
+
+            if let Some(min_support_percent) = ctx.options.min_support_percent {
+                let footprint = self.section_occupied_by(&self.location_in_texels(placement.0));
+                if !self.is_supported(&footprint, min_support_percent) {
+                    continue;
+                }
+            }
+
+            if let Some(validator) = ctx.options.placement_validator.as_ref() {
+                let mut location = self
+                    .location_in_texels(placement.0)
+                    .unpadded(*padding, requested);
+                location.content_hash = incoming.content_hash;
+                if !validator.is_valid(&location) {
+                    continue;
+                }
+            }
+
+            let is_better = match chosen.as_ref() {
+                None => true,
+                Some((chosen_idx, _, _)) => {
+                    let chosen_section = &self.available_bin_sections[*chosen_idx];
+                    self.is_better_section(section, chosen_section, edge_aligned, ctx)
+                }
+            };
+            if is_better {
+                chosen = Some((section_idx, placement.0, placement.1));
+            }
+
+            let is_first_fit = ctx.options.placement_policy == PlacementPolicy::FirstFit;
+            if is_first_fit && (!edge_aligned || self.touches_edge(section)) {
+                break;
+            }
+        }
+
+        let (section_idx, placement, mut new_sections) = match chosen {
+            Some(chosen) => chosen,
+            None => {
+                trace!(
+                    "rect does not fit into any section; rect={:?} sections_searched={}",
+                    incoming,
+                    sections_searched
+                );
+                if let Some(metrics) = self.metrics.as_ref() {
+                    metrics.record_failure(sections_searched);
+                }
+                return None;
+            }
+        };
+
+        trace!(
+            "placed rect into section; rect={:?} section={:?} sections_searched={}",
+            incoming,
+            self.available_bin_sections[section_idx],
+            sections_searched
+        );
+
+        let mut split;
+        let new_sections = match ctx.options.split_strategy.as_ref() {
+            Some(split_strategy) => {
+                let section = &self.available_bin_sections[section_idx];
+                let placed = BinSection::new(placement.x, placement.y, placement.z, placement.whd);
+                split = split_strategy.split(section, &placed);
+                split.retain(|new_section| new_section.whd.volume() > 0);
+                if !is_valid_split(section, &placed, &split) {
+                    trace!(
+                        "split strategy returned invalid sections; section={:?} placed={:?} split={:?}",
+                        section,
+                        placed,
+                        split
+                    );
+                    return None;
+                }
+
+                &mut split[..]
+            }
+            None => new_sections.sections_mut(),
+        };
+
+        sort_by_size_largest_to_smallest(new_sections, ctx.box_size_heuristic);
+        self.remove_filled_section(section_idx);
+        for new_section in new_sections.iter() {
+            self.insert_available_section(*new_section);
+        }
+
+        self.placed_rect_count += 1;
+        self.placed_weight += incoming.weight;
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.record_allocation(sections_searched);
+        }
+
+        let mut location = self
+            .location_in_texels(placement)
+            .unpadded(*padding, requested);
+        location.content_hash = incoming.content_hash;
+        self.record_used_section(&location);
+
+        Some(location)
+    }
+
+    /// Place a rectangle that was previously placed at the given location, such as in another
+    /// bin, with the same size and padding that it was placed with then.
+    ///
+    /// This doesn't need the [`PackOptions`] that the rectangle was originally packed with.
+    ///
+    /// [`PackOptions`]: crate::PackOptions
+    pub(crate) fn place_again<Heuristic, Compare>(
+        &mut self,
+        incoming: &RectToInsert,
+        previous: &PackedLocation,
+        ctx: &PackContext<Heuristic, Compare>,
+    ) -> Option<PackedLocation>
+    where
+        Heuristic: BoxSizeHeuristic + ?Sized,
+        Compare: ComparePotentialContainers + ?Sized,
+    {
+        let mut allocated = *incoming;
+        allocated.whd = previous.allocated_whd;
+        allocated.padding = Some(previous.padding);
+
+        let mut location = self.place(&allocated, ctx)?;
+        location.whd = previous.whd;
+        location.content_hash = previous.content_hash;
+
+        Some(location)
+    }
+
+    /// Place a rectangle that takes up no space, without changing the free sections.
+    ///
+    /// The rectangle goes at the origin, unless its layer or edge calls for another position.
+    /// Returns `None` if that position is past its `max_x` or `max_y`, on a layer outside of the
+    /// bin or rejected by the [`ValidatePlacement`].
+    ///
+    /// Like any other rectangle it counts towards the bin's placed rectangles and weight.
+    ///
+    /// [`ValidatePlacement`]: crate::ValidatePlacement
+    fn place_empty(
+        &mut self,
+        incoming: &RectToInsert,
+        options: &PackOptions,
+    ) -> Option<PackedLocation> {
+        let whd = incoming.whd;
+        let x = match incoming.edge {
+            Some(BinEdge::Right) => self.max_width.checked_sub(whd.width)?,
+            _ => 0,
+        };
+        let y = match incoming.edge {
+            Some(BinEdge::Top) => self.max_height.checked_sub(whd.height)?,
+            _ => 0,
+        };
+        let z = incoming.layer.unwrap_or(0);
+
+        if z >= self.max_depth {
+            return None;
+        }
+        if let Some(max_x) = incoming.max_x {
+            if x as u64 + whd.width as u64 > max_x as u64 {
+                return None;
+            }
+        }
+        if let Some(max_y) = incoming.max_y {
+            if y as u64 + whd.height as u64 > max_y as u64 {
+                return None;
+            }
+        }
+
+        let mut location = PackedLocation::new(x, y, z, whd);
+        location.content_hash = incoming.content_hash;
+        location.origin = self.origin;
+        if let Some(validator) = options.placement_validator.as_ref() {
+            if !validator.is_valid(&location) {
+                return None;
+            }
+        }
+
+        self.placed_rect_count += 1;
+        self.placed_weight += incoming.weight;
+
+        Some(location)
+    }
+
+    /// The incoming rectangle as it gets placed into this bin, rounded up, padded and set up for
+    /// the bin's blocks and layers, along with its padding.
+    fn rect_to_place(&self, incoming: &RectToInsert, options: &PackOptions) -> (RectToInsert, u32) {
+        let padding = incoming.padding.unwrap_or(options.padding);
+
+        let mut rounded = *incoming;
+        if let Some(size_rounding) = options.size_rounding {
+            rounded.whd = incoming.whd.rounded_up_to(size_rounding);
+        }
+
+        (self.rect_for_bin(&rounded.padded(padding)), padding)
+    }
+}
+
+impl TargetBin {
+    /// The size, according to the box size heuristic, of the smallest available section that can
+    /// fit the incoming rectangle.
+    pub(crate) fn smallest_section_that_fits<Heuristic, Compare>(
+        &self,
+        incoming: &RectToInsert,
+        ctx: &PackContext<Heuristic, Compare>,
+    ) -> Option<u128>
+    where
+        Heuristic: BoxSizeHeuristic + ?Sized,
+        Compare: ComparePotentialContainers + ?Sized,
+    {
+        if self.frozen || !self.can_carry(incoming.weight) {
+            return None;
+        }
+
+        let (incoming, _) = &self.rect_to_place(incoming, ctx.options);
+
+        self.available_bin_sections
+            .iter()
+            .filter(|section| section.incoming_can_fit_in_allowed_orientation(incoming))
+            .map(|section| ctx.box_size_heuristic.box_size(section.whd))
+            .min()
+    }
+}
+
+impl TargetBin {
+    /// Whether the `section` should be used instead of the `chosen_section`, which was tried
+    /// earlier.
+    ///
+    /// When the rectangle is `edge_aligned` sections that touch an edge of the bin always win.
+    fn is_better_section<Heuristic, Compare>(
+        &self,
+        section: &BinSection,
+        chosen_section: &BinSection,
+        edge_aligned: bool,
+        ctx: &PackContext<Heuristic, Compare>,
+    ) -> bool
+    where
+        Heuristic: BoxSizeHeuristic + ?Sized,
+        Compare: ComparePotentialContainers + ?Sized,
+    {
+        if edge_aligned && self.touches_edge(section) != self.touches_edge(chosen_section) {
+            return self.touches_edge(section);
+        }
+
+        match ctx.options.placement_policy {
+            PlacementPolicy::FirstFit => false,
+            PlacementPolicy::BestFit => {
+                ctx.box_size_heuristic.box_size(section.whd)
+                    < ctx.box_size_heuristic.box_size(chosen_section.whd)
+            }
+            PlacementPolicy::BottomLeft => {
+                bottom_left_key(section, ctx.box_size_heuristic)
+                    < bottom_left_key(chosen_section, ctx.box_size_heuristic)
+            }
+        }
+    }
+
+    /// Whether the section runs along the left, right, top or bottom edge of the bin.
+    fn touches_edge(&self, section: &BinSection) -> bool {
+        section.x == 0
+            || section.y == 0
+            || section.x + section.whd.width == self.max_width
+            || section.y + section.whd.height == self.max_height
+    }
+}
+
+/// Lower is better. Prefer the lowest, then leftmost, then front-most position, falling back to
+/// the smallest section.
+fn bottom_left_key<Heuristic: BoxSizeHeuristic + ?Sized>(
+    section: &BinSection,
+    box_size_heuristic: &Heuristic,
+) -> (u32, u32, u32, u128) {
+    (
+        section.y,
+        section.x,
+        section.z,
+        box_size_heuristic.box_size(section.whd),
+    )
+}
@@ -0,0 +1,48 @@
+//! Choosing which bins get filled first.
+
+use crate::TargetBin;
+
+impl TargetBin {
+    /// Try placing rectangles into this bin before every bin with a lower priority, no matter how
+    /// large either of them are, such as for filling atlas page 0 before page 1.
+    ///
+    /// Bins with the same priority are tried smallest first.
+    ///
+    /// Defaults to `0`.
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace, RectToInsert,
+    ///     TargetBin,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    /// rects_to_place.push_rect("sprite", None, RectToInsert::new(4, 4, 1));
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert("page-0", TargetBin::new(1024, 1024, 1).with_priority(1));
+    /// target_bins.insert("page-1", TargetBin::new(256, 256, 1));
+    ///
+    /// let packed = pack_rects(
+    ///     &rects_to_place,
+    ///     &mut target_bins,
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(packed.packed_locations()["sprite"].0, "page-0");
+    /// ```
+    pub fn with_priority(mut self, priority: u32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// The bin's priority, see [`TargetBin.with_priority`].
+    ///
+    /// [`TargetBin.with_priority`]: TargetBin::with_priority
+    pub fn priority(&self) -> u32 {
+        self.priority
+    }
+}
@@ -16,10 +16,10 @@ impl TargetBin {
     ///
     /// ## Performance
     ///
-    /// This checks that your [`BinSection`] does not overlap any other bin sections. In many
-    /// cases this will be negligible, however it is important to note that this has a worst case
-    /// time complexity of `O(Width * Height * Depth)`, where the worst case is tht you have a bin
-    /// full of `1x1x1` rectangles.
+    /// This checks that your [`BinSection`] does not overlap any other bin sections or exclusion
+    /// zones. In many cases this will be negligible, however it is important to note that this
+    /// has a worst case time complexity of `O(Width * Height * Depth)`, where the worst case is
+    /// tht you have a bin full of `1x1x1` rectangles.
     ///
     /// To skip the validity checks use [`TargetBin.push_available_bin_section_unchecked`].
     ///
@@ -35,6 +35,15 @@ impl TargetBin {
             return Err(PushBinSectionError::OutOfBounds(bin_section));
         }
 
+        for exclusion_zone in self.exclusion_zones.iter() {
+            if bin_section.intersection(exclusion_zone).is_some() {
+                return Err(PushBinSectionError::OverlapsExclusionZone {
+                    exclusion_zone: *exclusion_zone,
+                    new_section: bin_section,
+                });
+            }
+        }
+
         for available in self.available_bin_sections.iter() {
             if available.overlaps(&bin_section) {
                 return Err(PushBinSectionError::Overlaps {
@@ -57,7 +66,13 @@ impl TargetBin {
     ///
     /// [`TargetBin.push_available_bin_section`]: #method.push_available_bin_section
     pub fn push_available_bin_section_unchecked(&mut self, bin_section: BinSection) {
-        self.available_bin_sections.push(bin_section);
+        self.insert_available_section(bin_section);
+        self.release_used_sections(&bin_section);
+        self.placed_rect_count = self.placed_rect_count.saturating_sub(1);
+
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.record_eviction();
+        }
     }
 }
 
@@ -74,6 +89,17 @@ pub enum PushBinSectionError {
         /// The section that you were trying to add to the [`TargetBin`];
         new_section: BinSection,
     },
+    /// Attempted to push a [`BinSection`] that overlaps one of the bin's exclusion zones.
+    ///
+    /// See [`TargetBin.add_exclusion_zone`].
+    ///
+    /// [`TargetBin.add_exclusion_zone`]: TargetBin::add_exclusion_zone
+    OverlapsExclusionZone {
+        /// The exclusion zone that the section overlaps.
+        exclusion_zone: BinSection,
+        /// The section that you were trying to add to the [`TargetBin`];
+        new_section: BinSection,
+    },
 }
 
 impl Display for PushBinSectionError {
@@ -90,6 +116,14 @@ impl Display for PushBinSectionError {
                 .field("remaining_section", remaining_section)
                 .field("new_section", new_section)
                 .finish(),
+            PushBinSectionError::OverlapsExclusionZone {
+                exclusion_zone,
+                new_section,
+            } => f
+                .debug_struct("OverlapsExclusionZone")
+                .field("exclusion_zone", exclusion_zone)
+                .field("new_section", new_section)
+                .finish(),
         }
     }
 }
@@ -0,0 +1,103 @@
+//! The fast path for placing ranges into one dimensional bins, see [`pack_ranges`].
+//!
+//! [`pack_ranges`]: crate::pack_ranges
+
+use crate::bin_section::BinSection;
+use crate::pack_options::PackContext;
+use crate::packed_location::RotatedBy;
+use crate::width_height_depth::WidthHeightDepth;
+use crate::{BoxSizeHeuristic, ComparePotentialContainers};
+use crate::{PackedLocation, RectToInsert, TargetBin};
+
+impl TargetBin {
+    /// Place the range at the start of the first free section that is long enough to hold it,
+    /// shrinking that section from the front.
+    ///
+    /// Unlike [`TargetBin.place`] nothing needs to be split, so none of the heuristics are used.
+    pub(crate) fn place_range<Heuristic, Compare>(
+        &mut self,
+        incoming: &RectToInsert,
+        ctx: &PackContext<Heuristic, Compare>,
+    ) -> Option<PackedLocation>
+    where
+        Heuristic: BoxSizeHeuristic + ?Sized,
+        Compare: ComparePotentialContainers + ?Sized,
+    {
+        let length = incoming.whd.width;
+        let max_end = incoming.max_x.unwrap_or(u32::MAX);
+
+        let mut sections_searched = 0;
+        let mut chosen = None;
+        for section_idx in self.section_search_order(length as u128) {
+            if !ctx.examine_section() {
+                return None;
+            }
+            sections_searched += 1;
+
+            let section = &self.available_bin_sections[section_idx];
+            if section.whd.width < length || section.x + length > max_end {
+                continue;
+            }
+
+            let mut location = range_location(section.x, incoming);
+            location.origin = self.origin;
+            if let Some(validator) = ctx.options.placement_validator.as_ref() {
+                if !validator.is_valid(&location) {
+                    continue;
+                }
+            }
+
+            chosen = Some((section_idx, location));
+            break;
+        }
+
+        let (section_idx, location) = match chosen {
+            Some(chosen) => chosen,
+            None => {
+                if let Some(metrics) = self.metrics.as_ref() {
+                    metrics.record_failure(sections_searched);
+                }
+                return None;
+            }
+        };
+
+        let section = self.remove_available_section(section_idx);
+        if section.whd.width > length {
+            self.insert_available_section(BinSection::new(
+                section.x + length,
+                0,
+                0,
+                WidthHeightDepth::new(section.whd.width - length, 1, 1),
+            ));
+        }
+
+        self.placed_rect_count += 1;
+        self.placed_weight += incoming.weight;
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.record_allocation(sections_searched);
+        }
+
+        self.record_used_section(&location);
+
+        Some(location)
+    }
+}
+
+/// Where a range that starts at the given `x` gets placed.
+fn range_location(x: u32, incoming: &RectToInsert) -> PackedLocation {
+    PackedLocation {
+        x,
+        y: 0,
+        z: 0,
+        whd: incoming.whd,
+        allocated_whd: incoming.whd,
+        padding: 0,
+        content_hash: incoming.content_hash,
+        origin: [0, 0, 0],
+        x_axis_rotation: RotatedBy::ZeroDegrees,
+        y_axis_rotation: RotatedBy::ZeroDegrees,
+        z_axis_rotation: RotatedBy::ZeroDegrees,
+        flipped_horizontally: false,
+        flipped_vertically: false,
+    }
+}
@@ -0,0 +1,285 @@
+//! Splitting a [`TargetBin`]'s free sections into independent free-lists by size.
+
+use crate::bin_section::BinSection;
+use crate::TargetBin;
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// Size classes used to split the free sections of a [`TargetBin`] into independent free-lists.
+///
+/// A section belongs to the first class whose upper bound is greater than or equal to its
+/// volume. Sections that are larger than every upper bound belong to a final, unbounded class.
+///
+/// So `SizeClasses::new(vec![16, 256])` has three classes: tiny (volume <= 16), small
+/// (volume <= 256) and large (everything else).
+///
+/// When placing a rectangle the free-list of the rectangle's own class is searched first,
+/// followed by the free-lists of each larger class. Smaller classes are never searched since
+/// none of their sections could hold the rectangle.
+///
+/// This means that tiny rectangles fill tiny left over sections before they start to carve up
+/// the large sections that bigger rectangles need.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SizeClasses {
+    upper_bounds: Vec<u128>,
+    /// The (exclusive) end index of each class' free-list within the
+    /// [`TargetBin.available_bin_sections`].
+    free_list_ends: Vec<usize>,
+}
+
+impl SizeClasses {
+    /// Create size classes from the inclusive volume upper bound of every class except the last.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the upper bounds are not in strictly ascending order.
+    pub fn new(upper_bounds: Vec<u128>) -> Self {
+        assert!(
+            upper_bounds.windows(2).all(|pair| pair[0] < pair[1]),
+            "Size class upper bounds must be strictly ascending"
+        );
+
+        let free_list_ends = vec![0; upper_bounds.len() + 1];
+
+        SizeClasses {
+            upper_bounds,
+            free_list_ends,
+        }
+    }
+
+    /// The inclusive volume upper bound of every class except the last, unbounded, class.
+    pub fn upper_bounds(&self) -> &[u128] {
+        &self.upper_bounds
+    }
+
+    /// The index of the class that a box of the given volume belongs to.
+    pub fn class_of(&self, volume: u128) -> usize {
+        self.upper_bounds
+            .iter()
+            .position(|upper_bound| volume <= *upper_bound)
+            .unwrap_or(self.upper_bounds.len())
+    }
+
+    fn free_list(&self, class: usize) -> Range<usize> {
+        let start = if class == 0 {
+            0
+        } else {
+            self.free_list_ends[class - 1]
+        };
+
+        start..self.free_list_ends[class]
+    }
+}
+
+/// The free sections of a [`TargetBin`], saved so that they can be put back after merging or
+/// splitting them turned out not to help.
+pub(crate) struct SavedFreeSections {
+    sections: Vec<BinSection>,
+    free_list_ends: Option<Vec<usize>>,
+    volume: u128,
+}
+
+impl TargetBin {
+    /// Save the free sections and their free-lists, see [`TargetBin.restore_free_sections`].
+    ///
+    /// [`TargetBin.restore_free_sections`]: TargetBin::restore_free_sections
+    pub(crate) fn save_free_sections(&self) -> SavedFreeSections {
+        SavedFreeSections {
+            sections: self.available_bin_sections.clone(),
+            free_list_ends: self
+                .size_classes
+                .as_ref()
+                .map(|size_classes| size_classes.free_list_ends.clone()),
+            volume: self.free_section_volume,
+        }
+    }
+
+    /// Put back the free sections from before a [`TargetBin.save_free_sections`].
+    ///
+    /// [`TargetBin.save_free_sections`]: TargetBin::save_free_sections
+    pub(crate) fn restore_free_sections(&mut self, saved: SavedFreeSections) {
+        self.available_bin_sections = saved.sections;
+        self.free_section_volume = saved.volume;
+
+        if let (Some(size_classes), Some(free_list_ends)) =
+            (self.size_classes.as_mut(), saved.free_list_ends)
+        {
+            size_classes.free_list_ends = free_list_ends;
+        }
+    }
+
+    /// Split this bin's free sections into one free-list per size class.
+    ///
+    /// See [`SizeClasses`] for how incoming rectangles are routed to the free-lists.
+    pub fn with_size_classes(mut self, size_classes: SizeClasses) -> Self {
+        let sections = core::mem::take(&mut self.available_bin_sections);
+        self.free_section_volume = 0;
+
+        self.size_classes = Some(size_classes);
+        for section in sections {
+            self.insert_available_section(section);
+        }
+
+        self
+    }
+
+    /// The size classes that this bin's free sections are split into, if any.
+    pub fn size_classes(&self) -> Option<&SizeClasses> {
+        self.size_classes.as_ref()
+    }
+
+    /// Add a free section to the end of the free-list of its size class.
+    pub(crate) fn insert_available_section(&mut self, section: BinSection) {
+        self.free_section_volume += section.whd.volume();
+
+        match self.size_classes.as_mut() {
+            None => self.available_bin_sections.push(section),
+            Some(size_classes) => {
+                let class = size_classes.class_of(section.whd.volume());
+
+                let idx = size_classes.free_list_ends[class];
+                self.available_bin_sections.insert(idx, section);
+
+                for end in size_classes.free_list_ends[class..].iter_mut() {
+                    *end += 1;
+                }
+            }
+        }
+    }
+
+    /// Remove every free section from every free-list.
+    pub(crate) fn clear_available_sections(&mut self) {
+        self.available_bin_sections.clear();
+        self.free_section_volume = 0;
+
+        if let Some(size_classes) = self.size_classes.as_mut() {
+            for end in size_classes.free_list_ends.iter_mut() {
+                *end = 0;
+            }
+        }
+    }
+
+    /// Remove the free section at the given index from its free-list.
+    pub(crate) fn remove_available_section(&mut self, idx: usize) -> BinSection {
+        if let Some(size_classes) = self.size_classes.as_mut() {
+            for end in size_classes.free_list_ends.iter_mut() {
+                if *end > idx {
+                    *end -= 1;
+                }
+            }
+        }
+
+        let section = self.available_bin_sections.remove(idx);
+        self.free_section_volume -= section.whd.volume();
+
+        section
+    }
+
+    /// The indices of the free sections that could hold a box of the given volume, in the order
+    /// that they should be tried.
+    ///
+    /// Within a free-list the most recently added sections are tried first.
+    pub(crate) fn section_search_order(&self, volume: u128) -> impl Iterator<Item = usize> + '_ {
+        let (all_sections, by_class) = match self.size_classes.as_ref() {
+            None => (Some((0..self.available_bin_sections.len()).rev()), None),
+            Some(size_classes) => {
+                let by_class = (size_classes.class_of(volume)..=size_classes.upper_bounds.len())
+                    .flat_map(move |class| size_classes.free_list(class).rev());
+
+                (None, Some(by_class))
+            }
+        };
+
+        all_sections
+            .into_iter()
+            .flatten()
+            .chain(by_class.into_iter().flatten())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pack_options::PackContext;
+    use crate::width_height_depth::WidthHeightDepth;
+    use crate::{
+        contains_smallest_box, volume_heuristic, PackOptions, PackedLocation, RectToInsert,
+    };
+
+    /// Verify that sections are kept in the free-list of their size class.
+    #[test]
+    fn sections_are_partitioned_by_class() {
+        let bin = bin_with_large_then_tiny_section().with_size_classes(SizeClasses::new(vec![10]));
+
+        assert_eq!(
+            bin.available_bin_sections,
+            vec![tiny_section(), large_section()]
+        );
+        assert_eq!(bin.size_classes.unwrap().free_list_ends, vec![1, 2]);
+    }
+
+    /// Verify that an incoming rectangle is routed to the free-list of its size class before
+    /// larger free-lists, even if a larger section was added more recently.
+    #[test]
+    fn tiny_rect_placed_into_tiny_free_list_first() {
+        let mut bin =
+            bin_with_tiny_then_large_section().with_size_classes(SizeClasses::new(vec![10]));
+
+        let placement = place(&mut bin, RectToInsert::new(2, 2, 1)).unwrap();
+
+        assert_eq!((placement.x, placement.y), (50, 0));
+        assert_eq!(bin.available_bin_sections, vec![large_section()]);
+    }
+
+    /// Without size classes the most recently added section is tried first.
+    #[test]
+    fn without_size_classes_most_recent_section_used_first() {
+        let mut bin = bin_with_tiny_then_large_section();
+
+        let placement = place(&mut bin, RectToInsert::new(2, 2, 1)).unwrap();
+
+        assert_eq!((placement.x, placement.y), (0, 0));
+    }
+
+    /// Verify that the search starts at the free-list of the incoming rectangle's size class,
+    /// skipping the free-lists of smaller classes, and tries every section without size classes.
+    #[test]
+    fn search_order_starts_at_class_of_volume() {
+        let bin = bin_with_large_then_tiny_section();
+        assert_eq!(bin.section_search_order(4).collect::<Vec<_>>(), vec![1, 0]);
+
+        let bin = bin.with_size_classes(SizeClasses::new(vec![10]));
+        assert_eq!(bin.section_search_order(4).collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(bin.section_search_order(40).collect::<Vec<_>>(), vec![1]);
+    }
+
+    fn place(bin: &mut TargetBin, rect: RectToInsert) -> Option<PackedLocation> {
+        let options = PackOptions::default();
+
+        bin.place(
+            &rect,
+            &PackContext::new(&volume_heuristic, &contains_smallest_box, &options),
+        )
+    }
+
+    fn bin_with_tiny_then_large_section() -> TargetBin {
+        let mut bin = TargetBin::new(100, 100, 1);
+        bin.available_bin_sections = vec![tiny_section(), large_section()];
+        bin
+    }
+
+    fn bin_with_large_then_tiny_section() -> TargetBin {
+        let mut bin = TargetBin::new(100, 100, 1);
+        bin.available_bin_sections = vec![large_section(), tiny_section()];
+        bin
+    }
+
+    fn tiny_section() -> BinSection {
+        BinSection::new(50, 0, 0, WidthHeightDepth::new(2, 2, 1))
+    }
+
+    fn large_section() -> BinSection {
+        BinSection::new(0, 0, 0, WidthHeightDepth::new(50, 100, 1))
+    }
+}
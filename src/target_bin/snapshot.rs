@@ -0,0 +1,89 @@
+//! Rolling a [`TargetBin`] back to an earlier state.
+
+use crate::TargetBin;
+
+/// The state of a [`TargetBin`] at the time of a [`TargetBin.snapshot`], which the bin can be
+/// rolled back to using [`TargetBin.restore`].
+///
+/// [`TargetBin.snapshot`]: TargetBin::snapshot
+/// [`TargetBin.restore`]: TargetBin::restore
+#[derive(Debug, Clone)]
+pub struct BinSnapshot {
+    bin: TargetBin,
+}
+
+impl TargetBin {
+    /// Capture the bin's current state, such as before speculatively placing a batch of glyphs
+    /// that might have to be rolled back if some of them don't fit.
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace, RectToInsert,
+    ///     TargetBin,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert("page", TargetBin::new(16, 16, 1));
+    /// let snapshot = target_bins["page"].snapshot();
+    ///
+    /// let mut batch: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    /// batch.push_rect("a", None, RectToInsert::new(8, 8, 1));
+    /// pack_rects(&batch, &mut target_bins, &volume_heuristic, &contains_smallest_box).unwrap();
+    ///
+    /// // Decide against keeping the batch after all.
+    /// target_bins.get_mut("page").unwrap().restore(&snapshot);
+    /// assert_eq!(target_bins["page"].placed_rect_count(), 0);
+    /// ```
+    pub fn snapshot(&self) -> BinSnapshot {
+        BinSnapshot {
+            bin: self.clone_without_metrics(),
+        }
+    }
+
+    /// Roll the bin back to the state that it was in when the snapshot was taken, reusing the
+    /// memory that the bin already has.
+    ///
+    /// The bin keeps reporting to its own [`BinMetrics`], if any. The rectangles that were placed
+    /// since the snapshot are not reported as evicted.
+    ///
+    /// [`BinMetrics`]: crate::BinMetrics
+    pub fn restore(&mut self, snapshot: &BinSnapshot) {
+        let metrics = self.metrics.take();
+        snapshot.bin.clone_without_metrics_into(self);
+        self.metrics = metrics;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pack_options::PackContext;
+    use crate::{contains_smallest_box, volume_heuristic, PackOptions, RectToInsert, TargetBin};
+
+    /// Verify that restoring a snapshot undoes every placement since, and that the same snapshot
+    /// can be restored more than once.
+    #[test]
+    fn restore_undoes_placements() {
+        let options = PackOptions::default();
+        let ctx = PackContext::new(&volume_heuristic, &contains_smallest_box, &options);
+
+        let mut bin = TargetBin::new(10, 10, 1);
+        let first = bin.place(&RectToInsert::new(4, 4, 1), &ctx).unwrap();
+        let snapshot = bin.snapshot();
+
+        for _ in 0..2 {
+            bin.place(&RectToInsert::new(6, 6, 1), &ctx).unwrap();
+            bin.place(&RectToInsert::new(3, 3, 1), &ctx).unwrap();
+
+            bin.restore(&snapshot);
+            assert_eq!(bin.placed_rect_count(), 1);
+            assert_eq!(bin.free_volume(), 100 - 16);
+            assert_eq!(
+                bin.available_bin_sections,
+                snapshot.bin.available_bin_sections
+            );
+        }
+
+        assert_ne!(bin.place(&RectToInsert::new(4, 4, 1), &ctx), Some(first));
+    }
+}
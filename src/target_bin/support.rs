@@ -0,0 +1,61 @@
+//! Checking that boxes rest on something, see [`PackOptions.with_min_support`].
+//!
+//! [`PackOptions.with_min_support`]: crate::PackOptions::with_min_support
+
+use crate::bin_section::BinSection;
+use crate::TargetBin;
+
+impl TargetBin {
+    /// Whether at least `min_support_percent` of the bottom face of a box that would take up the
+    /// given section rests on the floor of the bin or on the used sections directly below it.
+    pub(crate) fn is_supported(&self, footprint: &BinSection, min_support_percent: u32) -> bool {
+        if footprint.y == 0 {
+            return true;
+        }
+
+        let bottom_area = footprint.whd.width as u128 * footprint.whd.depth as u128;
+        let overlap = |start: u32, len: u32, other_start: u32, other_len: u32| {
+            let end = (start + len).min(other_start + other_len);
+            end.saturating_sub(start.max(other_start)) as u128
+        };
+
+        let supported_area: u128 = self
+            .used_sections
+            .iter()
+            .flatten()
+            .filter(|used| used.y + used.whd.height == footprint.y)
+            .map(|used| {
+                overlap(footprint.x, footprint.whd.width, used.x, used.whd.width)
+                    * overlap(footprint.z, footprint.whd.depth, used.z, used.whd.depth)
+            })
+            .sum();
+
+        supported_area * 100 >= bottom_area * min_support_percent as u128
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bin_section::BinSection;
+    use crate::width_height_depth::WidthHeightDepth;
+    use crate::TargetBin;
+
+    /// Verify that a box is supported by the floor or by enough of the boxes directly below it.
+    #[test]
+    fn supported_by_floor_or_boxes_below() {
+        let mut bin = TargetBin::new(10, 10, 10).with_used_section_tracking(true);
+        bin.used_sections.as_mut().unwrap().push(BinSection::new(
+            0,
+            0,
+            0,
+            WidthHeightDepth::new(2, 2, 4),
+        ));
+
+        let footprint = |x, y| BinSection::new(x, y, 0, WidthHeightDepth::new(4, 1, 4));
+
+        assert!(bin.is_supported(&footprint(5, 0), 100));
+        assert!(bin.is_supported(&footprint(0, 2), 50));
+        assert!(!bin.is_supported(&footprint(0, 2), 51));
+        assert!(!bin.is_supported(&footprint(0, 3), 1));
+    }
+}
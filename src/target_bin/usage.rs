@@ -0,0 +1,184 @@
+//! Running totals of how much of a [`TargetBin`] is in use.
+
+use crate::bin_section::BinSection;
+use crate::{BoxSizeHeuristic, TargetBin};
+
+impl TargetBin {
+    /// The volume, in texels, of the bin's free sections.
+    ///
+    /// This is kept up to date as rectangles are placed and sections are pushed back, so it is
+    /// cheap to call every frame.
+    pub fn free_volume(&self) -> u128 {
+        self.free_section_volume * self.block_size.volume()
+    }
+
+    /// The combined area, in texels, of the fronts of the bin's free sections, ignoring their
+    /// depth.
+    ///
+    /// For a 2d bin with a depth of 1 this is the same as the [`TargetBin.free_volume`].
+    ///
+    /// [`TargetBin.free_volume`]: TargetBin::free_volume
+    pub fn free_area(&self) -> u128 {
+        let block_area = self.block_size.width as u128 * self.block_size.height as u128;
+
+        self.available_bin_sections
+            .iter()
+            .map(|section| section.whd.width as u128 * section.whd.height as u128 * block_area)
+            .sum()
+    }
+
+    /// How much of the free volume lies outside of the largest free section, from `0.0` when all
+    /// of it is in one section to almost `1.0` when it is scattered across many small sections.
+    ///
+    /// A bin whose free volume is high but whose fragmentation is also high can fail to fit a
+    /// large rectangle, such as a sign that it is time for
+    /// [`TargetBin.coalesce_all_available_sections`] or for a new bin.
+    ///
+    /// A full bin has a fragmentation of `0.0`.
+    ///
+    /// [`TargetBin.coalesce_all_available_sections`]: TargetBin::coalesce_all_available_sections
+    pub fn fragmentation_ratio(&self) -> f64 {
+        if self.free_section_volume == 0 {
+            return 0.;
+        }
+
+        let largest = self
+            .available_bin_sections
+            .iter()
+            .map(|section| section.whd.volume())
+            .max()
+            .unwrap_or(0);
+
+        1. - largest as f64 / self.free_section_volume as f64
+    }
+
+    /// The free section that the heuristic ranks as the largest, or `None` if the bin is full.
+    ///
+    /// For a bin with a [`TargetBin.with_block_size`] the section is measured in blocks.
+    ///
+    /// Which heuristic to use depends on the question being asked. For example, ranking sections
+    /// by their shortest side tells whether a square glyph would still fit without running a
+    /// pack.
+    ///
+    /// ```
+    /// use rectangle_pack::{TargetBin, WidthHeightDepth};
+    ///
+    /// let bin = TargetBin::new(300, 1024, 1);
+    ///
+    /// let shortest_side = |whd: WidthHeightDepth| whd.width().min(whd.height()) as u128;
+    /// let largest = bin.largest_free_section(&shortest_side).unwrap();
+    ///
+    /// assert!(largest.width() >= 256 && largest.height() >= 256);
+    /// ```
+    ///
+    /// [`TargetBin.with_block_size`]: TargetBin::with_block_size
+    pub fn largest_free_section<Heuristic: BoxSizeHeuristic + ?Sized>(
+        &self,
+        box_size_heuristic: &Heuristic,
+    ) -> Option<&BinSection> {
+        self.available_bin_sections
+            .iter()
+            .max_by_key(|section| box_size_heuristic.box_size(section.whd))
+    }
+
+    /// The volume, in texels, of the bin that isn't free.
+    ///
+    /// This includes the space that the placed rectangles take up, along with their padding and
+    /// the rest of any partially used blocks (see [`TargetBin.with_block_size`]), as well as the
+    /// bin's exclusion zones (see [`TargetBin.add_exclusion_zone`]).
+    ///
+    /// [`TargetBin.with_block_size`]: TargetBin::with_block_size
+    /// [`TargetBin.add_exclusion_zone`]: TargetBin::add_exclusion_zone
+    pub fn used_volume(&self) -> u128 {
+        self.dimensions().volume() - self.free_volume()
+    }
+
+    /// The number of rectangles that are placed in the bin.
+    ///
+    /// Every rectangle placed into the bin adds one, and every section returned to the bin using
+    /// [`TargetBin.push_available_bin_section`] (or
+    /// [`TargetBin.push_available_bin_section_unchecked`]) is counted as one removed rectangle.
+    ///
+    /// [`TargetBin.push_available_bin_section`]: TargetBin::push_available_bin_section
+    /// [`TargetBin.push_available_bin_section_unchecked`]: TargetBin::push_available_bin_section_unchecked
+    pub fn placed_rect_count(&self) -> usize {
+        self.placed_rect_count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bin_section::BinSection;
+    use crate::pack_options::PackContext;
+    use crate::width_height_depth::WidthHeightDepth;
+    use crate::{contains_smallest_box, volume_heuristic, PackOptions, RectToInsert, TargetBin};
+
+    /// Verify that the totals follow along as rectangles are placed and removed.
+    #[test]
+    fn totals_follow_placements_and_pushed_sections() {
+        let options = PackOptions::default();
+        let ctx = PackContext::new(&volume_heuristic, &contains_smallest_box, &options);
+
+        let mut bin = TargetBin::new(10, 10, 1).with_block_size(2, 2, 1);
+        assert_eq!(bin.free_volume(), 100);
+        assert_eq!(bin.used_volume(), 0);
+
+        let location = bin.place(&RectToInsert::new(3, 4, 1), &ctx).unwrap();
+        bin.place(&RectToInsert::new(2, 2, 1), &ctx).unwrap();
+        assert_eq!(bin.used_volume(), 16 + 4);
+        assert_eq!(bin.free_volume(), 80);
+        assert_eq!(bin.placed_rect_count(), 2);
+
+        let freed = bin.section_occupied_by(&location);
+        bin.push_available_bin_section(freed).unwrap();
+        assert_eq!(bin.used_volume(), 4);
+        assert_eq!(bin.placed_rect_count(), 1);
+
+        bin.clear();
+        assert_eq!(bin.free_volume(), 100);
+        assert_eq!(bin.placed_rect_count(), 0);
+
+        let mut bin = TargetBin::new(10, 10, 1);
+        bin.add_exclusion_zone(BinSection::new(0, 0, 0, WidthHeightDepth::new(5, 5, 1)));
+        assert_eq!(bin.used_volume(), 25);
+    }
+
+    /// Verify that the largest section depends on the heuristic, and that a full bin has none.
+    #[test]
+    fn largest_free_section() {
+        let mut bin = TargetBin::new(10, 10, 1);
+        bin.available_bin_sections = vec![
+            BinSection::new(0, 0, 0, WidthHeightDepth::new(10, 3, 1)),
+            BinSection::new(0, 3, 0, WidthHeightDepth::new(4, 4, 1)),
+        ];
+
+        let shortest_side = |whd: WidthHeightDepth| whd.width.min(whd.height) as u128;
+        assert_eq!(bin.largest_free_section(&volume_heuristic).unwrap().y(), 0);
+        assert_eq!(bin.largest_free_section(&shortest_side).unwrap().y(), 3);
+
+        bin.available_bin_sections.clear();
+        assert!(bin.largest_free_section(&volume_heuristic).is_none());
+    }
+
+    /// Verify that the free area ignores depth and that splitting the free space into more
+    /// sections raises the fragmentation.
+    #[test]
+    fn free_area_and_fragmentation() {
+        let options = PackOptions::default();
+        let ctx = PackContext::new(&volume_heuristic, &contains_smallest_box, &options);
+
+        let bin = TargetBin::new(10, 10, 2);
+        assert_eq!(bin.free_area(), 100);
+        assert_eq!(bin.fragmentation_ratio(), 0.);
+
+        let mut bin = TargetBin::new(10, 10, 1);
+        bin.place(&RectToInsert::new(5, 5, 1), &ctx).unwrap();
+        assert_eq!(bin.free_area(), 75);
+        assert!(bin.fragmentation_ratio() > 0.);
+
+        bin.place(&RectToInsert::new(5, 5, 1), &ctx).unwrap();
+        bin.place(&RectToInsert::new(5, 5, 1), &ctx).unwrap();
+        bin.place(&RectToInsert::new(5, 5, 1), &ctx).unwrap();
+        assert_eq!(bin.fragmentation_ratio(), 0.);
+    }
+}
@@ -0,0 +1,153 @@
+//! Keeping track of the sections of a [`TargetBin`] that rectangles were placed into.
+
+use crate::bin_section::BinSection;
+use crate::{PackedLocation, TargetBin};
+
+use alloc::vec::Vec;
+
+impl TargetBin {
+    /// Keep a list of the sections that rectangles were placed into, alongside the free
+    /// sections, so that the bin alone can answer which parts of it are occupied, such as for
+    /// drawing a debug view of an atlas.
+    ///
+    /// Every placed rectangle adds the section returned by [`TargetBin.section_occupied_by`].
+    /// Pushing a free section back using [`TargetBin.push_available_bin_section`] removes the
+    /// used sections that lie within it.
+    ///
+    /// This should be called before placing anything into the bin, since the rectangles that
+    /// were placed before tracking started aren't known.
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace, RectToInsert,
+    ///     TargetBin,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    /// rects_to_place.push_rect("sprite", None, RectToInsert::new(4, 4, 1));
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert(0, TargetBin::new(16, 16, 1).with_used_section_tracking(true));
+    ///
+    /// let packed = pack_rects(
+    ///     &rects_to_place,
+    ///     &mut target_bins,
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    /// )
+    /// .unwrap();
+    ///
+    /// let (_, sprite) = packed.packed_locations()["sprite"];
+    /// assert_eq!(
+    ///     target_bins[&0].used_sections(),
+    ///     Some(&[target_bins[&0].section_occupied_by(&sprite)][..])
+    /// );
+    /// ```
+    ///
+    /// [`TargetBin.push_available_bin_section`]: TargetBin::push_available_bin_section
+    /// [`TargetBin.section_occupied_by`]: TargetBin::section_occupied_by
+    pub fn with_used_section_tracking(mut self, track_used_sections: bool) -> Self {
+        self.used_sections = if track_used_sections {
+            Some(Vec::new())
+        } else {
+            None
+        };
+        self
+    }
+
+    /// The sections that rectangles were placed into, in the order that they were placed, if
+    /// [`TargetBin.with_used_section_tracking`] is enabled.
+    ///
+    /// Like the free sections, these are measured in blocks for a bin with a
+    /// [`TargetBin.with_block_size`].
+    ///
+    /// [`TargetBin.with_used_section_tracking`]: TargetBin::with_used_section_tracking
+    /// [`TargetBin.with_block_size`]: TargetBin::with_block_size
+    pub fn used_sections(&self) -> Option<&[BinSection]> {
+        self.used_sections.as_deref()
+    }
+
+    /// The used section that covers the texel at the given position, if
+    /// [`TargetBin.with_used_section_tracking`] is enabled and a rectangle was placed there.
+    ///
+    /// Padding counts as part of the used section that it surrounds.
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace, RectToInsert,
+    ///     TargetBin,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    /// rects_to_place.push_rect("sprite", None, RectToInsert::new(4, 4, 1));
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert(0, TargetBin::new(16, 16, 1).with_used_section_tracking(true));
+    ///
+    /// pack_rects(
+    ///     &rects_to_place,
+    ///     &mut target_bins,
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert!(target_bins[&0].used_section_at(3, 3, 0).is_some());
+    /// assert!(target_bins[&0].used_section_at(4, 3, 0).is_none());
+    /// ```
+    ///
+    /// [`TargetBin.with_used_section_tracking`]: TargetBin::with_used_section_tracking
+    pub fn used_section_at(&self, x: u32, y: u32, z: u32) -> Option<&BinSection> {
+        let x = x / self.block_size.width;
+        let y = y / self.block_size.height;
+        let z = z / self.block_size.depth;
+
+        self.used_sections
+            .as_ref()?
+            .iter()
+            .find(|used| used.contains_point(x, y, z))
+    }
+
+    /// Record the section that a rectangle was just placed into.
+    pub(crate) fn record_used_section(&mut self, location: &PackedLocation) {
+        let section = self.section_occupied_by(location);
+
+        if let Some(used_sections) = self.used_sections.as_mut() {
+            used_sections.push(section);
+        }
+    }
+
+    /// Forget the used sections that lie within a section that was freed.
+    pub(crate) fn release_used_sections(&mut self, freed: &BinSection) {
+        if let Some(used_sections) = self.used_sections.as_mut() {
+            used_sections.retain(|used| freed.intersection(used) != Some(*used));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pack_options::PackContext;
+    use crate::{contains_smallest_box, volume_heuristic, PackOptions, RectToInsert, TargetBin};
+
+    /// Verify that freeing a rectangle forgets its used section, while the rest are kept.
+    #[test]
+    fn freed_sections_are_no_longer_used() {
+        let options = PackOptions::default();
+        let ctx = PackContext::new(&volume_heuristic, &contains_smallest_box, &options);
+
+        let mut bin = TargetBin::new(10, 10, 1).with_used_section_tracking(true);
+        let first = bin.place(&RectToInsert::new(2, 2, 1), &ctx).unwrap();
+        let second = bin.place(&RectToInsert::new(3, 3, 1), &ctx).unwrap();
+
+        let freed = bin.section_occupied_by(&first);
+        bin.push_available_bin_section(freed).unwrap();
+
+        assert_eq!(
+            bin.used_sections(),
+            Some(&[bin.section_occupied_by(&second)][..])
+        );
+    }
+}
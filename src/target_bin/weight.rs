@@ -0,0 +1,106 @@
+//! Limiting how much weight is placed into a [`TargetBin`], such as when loading containers.
+
+use crate::TargetBin;
+
+impl TargetBin {
+    /// Only place rectangles into this bin while their combined [`RectToInsert.with_weight`]
+    /// stays at or below `max_weight`, on top of only placing them where there is space.
+    ///
+    /// Sections that are pushed back using [`TargetBin.push_available_bin_section`] don't give
+    /// back any weight, since the bin doesn't know what was removed. [`TargetBin.clear`] does.
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace, RectToInsert,
+    ///     TargetBin,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut rects_to_place: GroupedRectsToPlace<_, ()> = GroupedRectsToPlace::new();
+    /// rects_to_place.push_rect("engine", None, RectToInsert::new(2, 2, 2).with_weight(800));
+    /// rects_to_place.push_rect("gearbox", None, RectToInsert::new(2, 2, 2).with_weight(400));
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert("truck", TargetBin::new(10, 10, 10).with_max_weight(1000));
+    /// target_bins.insert("van", TargetBin::new(10, 10, 10).with_max_weight(500));
+    ///
+    /// let packed = pack_rects(
+    ///     &rects_to_place,
+    ///     &mut target_bins,
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(packed.packed_locations()["engine"].0, "truck");
+    /// assert_eq!(packed.packed_locations()["gearbox"].0, "van");
+    /// ```
+    ///
+    /// [`RectToInsert.with_weight`]: crate::RectToInsert::with_weight
+    /// [`TargetBin.push_available_bin_section`]: TargetBin::push_available_bin_section
+    /// [`TargetBin.clear`]: TargetBin::clear
+    pub fn with_max_weight(mut self, max_weight: u64) -> Self {
+        self.max_weight = Some(max_weight);
+        self
+    }
+
+    /// See [`TargetBin.with_max_weight`].
+    ///
+    /// [`TargetBin.with_max_weight`]: TargetBin::with_max_weight
+    pub fn max_weight(&self) -> Option<u64> {
+        self.max_weight
+    }
+
+    /// The combined weight of the rectangles that are placed in the bin.
+    pub fn placed_weight(&self) -> u64 {
+        self.placed_weight
+    }
+
+    /// Whether this much more weight can be placed into the bin.
+    pub(crate) fn can_carry(&self, weight: u64) -> bool {
+        match self.max_weight {
+            Some(max_weight) => self.placed_weight.saturating_add(weight) <= max_weight,
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pack_options::PackContext;
+    use crate::{contains_smallest_box, volume_heuristic, PackOptions, RectToInsert, TargetBin};
+
+    /// Verify that a box is not placed once the bin would be too heavy, even though there is
+    /// space left, and that clearing the bin gives the weight back.
+    #[test]
+    fn respects_max_weight() {
+        let options = PackOptions::default();
+        let ctx = PackContext::new(&volume_heuristic, &contains_smallest_box, &options);
+
+        let mut bin = TargetBin::new(10, 10, 10).with_max_weight(10);
+        let crate_of_bolts = RectToInsert::new(1, 1, 1).with_weight(6);
+
+        assert!(bin.place(&crate_of_bolts, &ctx).is_some());
+        assert!(bin.place(&crate_of_bolts, &ctx).is_none());
+        assert_eq!(bin.placed_weight(), 6);
+
+        bin.clear();
+        assert!(bin.place(&crate_of_bolts, &ctx).is_some());
+    }
+
+    /// Verify that rectangles that take up no space still count towards the bin's weight and
+    /// placed rectangles.
+    #[test]
+    fn empty_rects_carry_weight() {
+        let options = PackOptions::default();
+        let ctx = PackContext::new(&volume_heuristic, &contains_smallest_box, &options);
+
+        let mut bin = TargetBin::new(10, 10, 10).with_max_weight(10);
+        let sack_of_air = RectToInsert::new(0, 1, 1).with_weight(6);
+
+        assert!(bin.place(&sack_of_air, &ctx).is_some());
+        assert!(bin.place(&sack_of_air, &ctx).is_none());
+        assert_eq!(bin.placed_weight(), 6);
+        assert_eq!(bin.placed_rect_count(), 1);
+    }
+}
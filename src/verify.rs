@@ -0,0 +1,222 @@
+//! Checking that a [`RectanglePackOk`] describes a valid packing.
+
+use crate::bin_section::BinSection;
+use crate::grouped_rects_to_place::Group;
+use crate::{GroupedRectsToPlace, PackedLocation, RectanglePackOk, TargetBin};
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+
+/// Something that is wrong with the placements of a [`RectanglePackOk`].
+///
+/// See [`RectanglePackOk.verify`].
+///
+/// [`RectanglePackOk.verify`]: RectanglePackOk::verify
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlacementIssue<'a, RectToPlaceId, BinId, GroupId> {
+    /// A rectangle was placed into a bin that isn't one of the target bins.
+    UnknownBin {
+        #[allow(missing_docs)]
+        rect_id: &'a RectToPlaceId,
+        #[allow(missing_docs)]
+        bin_id: &'a BinId,
+    },
+    /// A rectangle sticks out of its bin.
+    OutOfBounds {
+        #[allow(missing_docs)]
+        rect_id: &'a RectToPlaceId,
+        #[allow(missing_docs)]
+        bin_id: &'a BinId,
+    },
+    /// Two rectangles that were placed into the same bin overlap each other.
+    Overlap {
+        #[allow(missing_docs)]
+        rect_ids: [&'a RectToPlaceId; 2],
+        #[allow(missing_docs)]
+        bin_id: &'a BinId,
+    },
+    /// The placed rectangles of a group ended up in more than one bin.
+    SplitGroup {
+        #[allow(missing_docs)]
+        group_id: &'a GroupId,
+        /// The bins that the group's rectangles are in, in ascending order.
+        bin_ids: Vec<&'a BinId>,
+    },
+}
+
+impl<RectToPlaceId, BinId> RectanglePackOk<RectToPlaceId, BinId>
+where
+    RectToPlaceId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    BinId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+{
+    /// Check that every placement is within its bin, that no two placements in the same bin
+    /// overlap and that the placed rectangles of every group share a bin, independently of how
+    /// the rectangles were placed.
+    ///
+    /// Returns every issue that was found, which is empty if the placements are valid. Useful
+    /// for testing code that builds or adjusts placements by hand, such as when combining results
+    /// using [`RectanglePackOk.merge`].
+    ///
+    /// Rectangles are checked without their padding (see [`RectToInsert.with_padding`]). Every
+    /// pair of rectangles in a bin is compared, so this is meant for tests and debugging rather
+    /// than for every pack.
+    ///
+    /// ```
+    /// use rectangle_pack::{
+    ///     contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace,
+    ///     RectToInsert, TargetBin,
+    /// };
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut rects_to_place: GroupedRectsToPlace<_, _> = GroupedRectsToPlace::new();
+    /// rects_to_place.push_rect("grass", Some(vec!["terrain"]), RectToInsert::new(8, 8, 1));
+    /// rects_to_place.push_rect("stone", Some(vec!["terrain"]), RectToInsert::new(8, 8, 1));
+    ///
+    /// let mut target_bins = BTreeMap::new();
+    /// target_bins.insert("atlas", TargetBin::new(16, 16, 1));
+    ///
+    /// let packed = pack_rects(
+    ///     &rects_to_place,
+    ///     &mut target_bins,
+    ///     &volume_heuristic,
+    ///     &contains_smallest_box,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert!(packed.verify(&rects_to_place, &target_bins).is_empty());
+    /// ```
+    ///
+    /// [`RectanglePackOk.merge`]: RectanglePackOk::merge
+    /// [`RectToInsert.with_padding`]: crate::RectToInsert::with_padding
+    pub fn verify<'a, GroupId>(
+        &'a self,
+        rects_to_place: &'a GroupedRectsToPlace<RectToPlaceId, GroupId>,
+        target_bins: &BTreeMap<BinId, TargetBin>,
+    ) -> Vec<PlacementIssue<'a, RectToPlaceId, BinId, GroupId>>
+    where
+        GroupId: Debug + Hash + PartialEq + Eq + Clone + Ord + PartialOrd,
+    {
+        let mut issues = Vec::new();
+
+        let mut placed: Vec<(&BinId, &RectToPlaceId, BinSection)> = self
+            .packed_locations
+            .iter()
+            .map(|(rect_id, (bin_id, location))| (bin_id, rect_id, footprint(location)))
+            .collect();
+        placed.sort_by(|(a_bin, a_rect, _), (b_bin, b_rect, _)| {
+            (a_bin, a_rect).cmp(&(b_bin, b_rect))
+        });
+
+        for (bin_id, rect_id, footprint) in placed.iter().copied() {
+            let bin = match target_bins.get(bin_id) {
+                Some(bin) => bin.dimensions(),
+                None => {
+                    issues.push(PlacementIssue::UnknownBin { rect_id, bin_id });
+                    continue;
+                }
+            };
+
+            let bin = BinSection::new(0, 0, 0, bin);
+            if footprint.whd.volume() > 0 && footprint.intersection(&bin) != Some(footprint) {
+                issues.push(PlacementIssue::OutOfBounds { rect_id, bin_id });
+            }
+        }
+
+        for (idx, (bin_id, a, a_footprint)) in placed.iter().copied().enumerate() {
+            for (other_bin_id, b, b_footprint) in placed[idx + 1..].iter().copied() {
+                if other_bin_id != bin_id {
+                    break;
+                }
+
+                if a_footprint.intersection(&b_footprint).is_some() {
+                    issues.push(PlacementIssue::Overlap {
+                        rect_ids: [a, b],
+                        bin_id,
+                    });
+                }
+            }
+        }
+
+        for (group, members) in rects_to_place.group_id_to_inbound_ids.iter() {
+            let group_id = match group {
+                Group::Grouped(group_id) => group_id,
+                Group::Ungrouped(_) => continue,
+            };
+
+            let mut bin_ids: Vec<&BinId> = members
+                .iter()
+                .filter_map(|member| self.packed_locations.get(member))
+                .map(|(bin_id, _)| bin_id)
+                .collect();
+            bin_ids.sort();
+            bin_ids.dedup();
+
+            if bin_ids.len() > 1 {
+                issues.push(PlacementIssue::SplitGroup { group_id, bin_ids });
+            }
+        }
+
+        issues
+    }
+}
+
+/// The space within its bin that a placed rectangle takes up, without its padding.
+fn footprint(location: &PackedLocation) -> BinSection {
+    BinSection::new(location.x, location.y, location.z, location.effective_whd())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{contains_smallest_box, pack_rects, volume_heuristic, RectToInsert};
+
+    /// Verify that moving placements around by hand is caught.
+    #[test]
+    fn reports_invalid_placements() {
+        let mut rects_to_place: GroupedRectsToPlace<_, _> = GroupedRectsToPlace::new();
+        rects_to_place.push_rect("a", Some(vec!["group"]), RectToInsert::new(4, 4, 1));
+        rects_to_place.push_rect("b", Some(vec!["group"]), RectToInsert::new(4, 4, 1));
+        rects_to_place.push_rect("c", None, RectToInsert::new(4, 4, 1));
+
+        let mut target_bins = BTreeMap::new();
+        target_bins.insert(0, TargetBin::new(8, 8, 1));
+        target_bins.insert(1, TargetBin::new(8, 8, 1));
+
+        let mut packed = pack_rects(
+            &rects_to_place,
+            &mut target_bins,
+            &volume_heuristic,
+            &contains_smallest_box,
+        )
+        .unwrap();
+        assert!(packed.verify(&rects_to_place, &target_bins).is_empty());
+
+        // Move "a" out of the other bin, and stack "b" on top of "c".
+        let c = packed.packed_locations["c"];
+        let other_bin = 1 - c.0;
+        packed.packed_locations.insert("b", c);
+        let a = packed.packed_locations.get_mut("a").unwrap();
+        a.0 = other_bin;
+        a.1.x = 6;
+
+        let issues = packed.verify(&rects_to_place, &target_bins);
+
+        assert_eq!(
+            issues,
+            vec![
+                PlacementIssue::OutOfBounds {
+                    rect_id: &"a",
+                    bin_id: &other_bin,
+                },
+                PlacementIssue::Overlap {
+                    rect_ids: [&"b", &"c"],
+                    bin_id: &c.0,
+                },
+                PlacementIssue::SplitGroup {
+                    group_id: &"group",
+                    bin_ids: vec![&0, &1],
+                },
+            ]
+        );
+    }
+}
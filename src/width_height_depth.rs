@@ -24,7 +24,41 @@ impl WidthHeightDepth {
         }
     }
 
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
     pub fn volume(&self) -> u128 {
         self.width as u128 * self.height as u128 * self.depth as u128
     }
+
+    /// This volume with each axis rounded up to the next multiple of the given size.
+    pub(crate) fn rounded_up_to(&self, multiple: WidthHeightDepth) -> WidthHeightDepth {
+        let blocks = self.blocks_covering(multiple);
+
+        WidthHeightDepth {
+            width: blocks.width * multiple.width,
+            height: blocks.height * multiple.height,
+            depth: blocks.depth * multiple.depth,
+        }
+    }
+
+    /// The number of blocks of the given size needed to cover this volume along each axis.
+    pub(crate) fn blocks_covering(&self, block_size: WidthHeightDepth) -> WidthHeightDepth {
+        let blocks = |len: u32, block_len: u32| len.div_ceil(block_len);
+
+        WidthHeightDepth {
+            width: blocks(self.width, block_size.width),
+            height: blocks(self.height, block_size.height),
+            depth: blocks(self.depth, block_size.depth),
+        }
+    }
 }
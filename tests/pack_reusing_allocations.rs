@@ -0,0 +1,87 @@
+//! Verify that a warmed up [`Packer`] only allocates the placements that it returns.
+//!
+//! This lives in its own test binary since it replaces the global allocator.
+
+#![cfg(feature = "std")]
+
+use rectangle_pack::{GroupedRectsToPlace, Packer, RectToInsert, RectanglePackOk, TargetBin};
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::collections::BTreeMap;
+
+thread_local! {
+    /// Only allocations made by the test's own thread are counted, since the test harness
+    /// allocates on other threads while the test runs.
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+/// Counts the allocations of the current thread.
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|allocations| allocations.set(allocations.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|allocations| allocations.set(allocations.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Verify that packing the same rectangles again into cleared bins allocates nothing but the
+/// nodes of the map of placements, which are counted by cloning the map.
+#[test]
+fn warm_pack_reusing_only_allocates_placements() {
+    let mut rects_to_place: GroupedRectsToPlace<_, _> = GroupedRectsToPlace::new();
+    for id in 0..50u32 {
+        let group = match id % 5 {
+            0 => Some(vec![id % 3]),
+            _ => None,
+        };
+        rects_to_place.push_rect(id, group, RectToInsert::new(4 + id % 13, 4 + id % 7, 1));
+    }
+
+    let mut target_bins = BTreeMap::new();
+    target_bins.insert("small", TargetBin::new(64, 64, 1));
+    target_bins.insert("large", TargetBin::new(256, 256, 1));
+
+    let mut packer = Packer::new();
+    let mut packed = RectanglePackOk::default();
+    packer
+        .pack_reusing(&rects_to_place, &mut target_bins, &mut packed)
+        .unwrap();
+
+    for _ in 0..3 {
+        for bin in target_bins.values_mut() {
+            bin.clear();
+        }
+
+        let before = allocations();
+        packer
+            .pack_reusing(&rects_to_place, &mut target_bins, &mut packed)
+            .unwrap();
+        let packing = allocations() - before;
+        assert_eq!(packed.packed_locations().len(), 50);
+
+        let before = allocations();
+        let placements = packed.packed_locations().clone();
+        let placement_nodes = allocations() - before;
+        drop(placements);
+
+        assert_eq!(packing, placement_nodes);
+    }
+}